@@ -1,20 +1,46 @@
 use crate::Args;
+use crate::backup::backup_edited_buffers;
 use crate::components::StatusBar;
 use crate::components::{EditorTabButton, EmptyPanel};
+use crate::file_watcher::watch_open_files;
+use crate::git::watch_git_status;
+use crate::global_defaults::{
+    CloseOtherTabsCommand, CloseSavedTabsCommand, CloseTabCommand, CloseTabsToTheRightCommand,
+    SplitDownCommand, SplitRightCommand,
+};
+use crate::lsp::publish_diagnostics;
+use crate::session::{autosave_session, load_session, restore_session};
 use crate::settings::watch_settings;
-use crate::state::{EditorSidePanel, EditorView, TabProps};
+use crate::state::{EditorSidePanel, EditorView, TabProps, WindowCloseHandle};
 use crate::theme::github_dark_theme;
 use crate::views::commander::commander_ui::Commander;
+use crate::views::confirm_close::confirm_close_ui::ConfirmClose;
+use crate::views::context_menu::ContextMenu;
+use crate::views::context_menu::context_menu_state::ContextMenuEntry;
 use crate::views::file_explorer::FileExplorer;
+use crate::views::file_explorer::commands::RevealInExplorerCommand;
 use crate::views::file_explorer::file_explorer_ui::{
-    ExplorerItem, FolderState, read_folder_as_items,
+    ExplorerItem, FolderState, read_folder_as_items, reveal_in_explorer,
 };
+use crate::views::file_explorer::file_explorer_watcher::watch_explorer_folders;
+use crate::views::file_prompt::file_prompt_ui::FilePromptPopup;
 use crate::views::file_search::file_search_ui::FileSearch;
-use crate::views::panels::tabs::editor::EditorTab;
+use crate::views::go_to_line::go_to_line_ui::GoToLine;
+use crate::views::notifications::notifications_state::Notification;
+use crate::views::notifications::{Notifications, Toasts};
+use crate::views::panels::tabs::diff::{DiffTab, compare_files};
+use crate::views::panels::tabs::editor::{
+    AppStateEditorUtils, CopyActiveFilePathCommand, EditorTab, RadioEditorUtils,
+};
+use crate::views::panels::tabs::recovery::RecoveryTab;
 use crate::views::panels::tabs::welcome::WelcomeTab;
+use crate::views::problems::Problems;
+use crate::views::references::References;
+use crate::views::source_control::SourceControl;
 use crate::views::tab_switcher::tab_switcher_ui::TabSwitcher;
+use crate::views::workspace_search::WorkspaceSearch;
 use crate::{
-    fs::{FSLocal, FSTransport},
+    fs::{FSLocal, FSTransport, split_path_position},
     state::EditorCommands,
 };
 use crate::{global_defaults::GlobalDefaults, state::KeyboardShortcuts};
@@ -22,14 +48,32 @@ use freya::helpers::from_fn;
 use freya::prelude::*;
 use freya::radio::*;
 use futures::StreamExt;
+use lsp_types::notification::{Notification as _, PublishDiagnostics};
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::state::{AppState, AppTask, Channel as AppChannel, PanelId, TabId};
+use crate::state::{AppState, AppTask, Channel as AppChannel, CloseRequest, PanelId, TabId};
+
+/// Entries of the context menu of a tab, they act on the active tab.
+fn tab_context_menu_entries() -> Vec<ContextMenuEntry> {
+    vec![
+        ContextMenuEntry::Command(CloseTabCommand::id()),
+        ContextMenuEntry::Command(CloseOtherTabsCommand::id()),
+        ContextMenuEntry::Command(CloseTabsToTheRightCommand::id()),
+        ContextMenuEntry::Command(CloseSavedTabsCommand::id()),
+        ContextMenuEntry::Separator,
+        ContextMenuEntry::Command(CopyActiveFilePathCommand::id()),
+        ContextMenuEntry::Command(RevealInExplorerCommand::id()),
+        ContextMenuEntry::Separator,
+        ContextMenuEntry::Command(SplitRightCommand::id()),
+        ContextMenuEntry::Command(SplitDownCommand::id()),
+    ]
+}
 
 #[derive(PartialEq)]
-pub struct AppView(pub Args);
+pub struct AppView(pub Args, pub WindowCloseHandle);
 impl App for AppView {
     fn render(&self) -> impl IntoElement {
         use_init_theme(github_dark_theme);
@@ -39,12 +83,9 @@ impl App for AppView {
         let mut radio_app_state = use_hook(move || {
             let default_transport: FSTransport = Arc::new(Box::new(FSLocal));
             let (task_sender, mut task_receiver) = futures_channel::mpsc::unbounded::<AppTask>();
+            self.1.connect(task_sender.clone());
 
-            let mut app_state = AppState::new(default_transport, task_sender);
-
-            if self.0.paths.is_empty() {
-                WelcomeTab::open_with(&mut app_state);
-            }
+            let app_state = AppState::new(default_transport, task_sender);
 
             let station = RadioStation::create(app_state);
             provide_context(station);
@@ -67,6 +108,36 @@ impl App for AppView {
                                 transport.as_read(),
                             );
                         }
+                        AppTask::LspNotification(notification)
+                            if notification.method == PublishDiagnostics::METHOD =>
+                        {
+                            match serde_json::from_value(notification.params) {
+                                Ok(params) => {
+                                    let mut app_state =
+                                        radio_app_state.write_channel(AppChannel::Problems);
+                                    publish_diagnostics(
+                                        &mut app_state,
+                                        &notification.server,
+                                        params,
+                                    );
+                                }
+                                Err(err) => warn!(
+                                    "Language server [{}] sent invalid diagnostics: {err}",
+                                    notification.server
+                                ),
+                            }
+                        }
+                        AppTask::CloseWindow => {
+                            radio_app_state
+                                .write_channel(AppChannel::Global)
+                                .request_close(CloseRequest::Window);
+                        }
+                        AppTask::LspNotification(notification) => {
+                            info!(
+                                "Language server [{}] sent `{}`",
+                                notification.server, notification.method
+                            );
+                        }
                     }
                 }
             });
@@ -74,20 +145,51 @@ impl App for AppView {
             radio_app_state
         });
 
-        // Load specified files and folders asynchronously
+        // Restore the previous session, then load specified files and folders asynchronously
         use_hook(move || {
             let args = self.0.clone();
+            let session = if args.diff.is_some() && args.paths.is_empty() {
+                None
+            } else if args.paths.is_empty() {
+                load_session(None)
+            } else if args.restore {
+                let folders = args
+                    .paths
+                    .iter()
+                    .filter_map(|path| path.canonicalize().ok())
+                    .filter(|path| path.is_dir())
+                    .collect::<Vec<_>>();
+                load_session(Some(&folders))
+            } else {
+                None
+            };
             spawn(async move {
+                match session {
+                    Some(session) => restore_session(radio_app_state, session).await,
+                    None if args.paths.is_empty() && args.diff.is_none() => {
+                        WelcomeTab::open_with(&mut radio_app_state.write());
+                    }
+                    None => {}
+                }
+                // Offer the unsaved changes of the previous run
+                if !radio_app_state.read().recovered_files.is_empty() {
+                    RecoveryTab::open_with(&mut radio_app_state.write());
+                }
+                if let Some([old, new]) = args.diff.as_deref() {
+                    compare_files(radio_app_state, old.clone(), new.clone()).await;
+                }
                 for path in args.paths {
+                    let (path, position) = split_path_position(&path);
                     if path.is_file() {
                         let transport = radio_app_state.read().default_transport.clone();
 
                         let mut app_state = radio_app_state.write();
-                        EditorTab::open_with(
+                        EditorTab::open_at(
                             radio_app_state,
                             &mut app_state,
                             path.clone(),
                             transport.as_read(),
+                            position,
                         )
                     } else if path.is_dir() {
                         let mut app_state = radio_app_state.write_channel(AppChannel::FileExplorer);
@@ -96,20 +198,44 @@ impl App for AppView {
                             .canonicalize(&path)
                             .await
                             .unwrap();
+                        // Already opened by the restored session
+                        if app_state
+                            .file_explorer
+                            .folders
+                            .iter()
+                            .any(|folder| folder.path() == &folder_path)
+                        {
+                            continue;
+                        }
 
                         let items =
                             read_folder_as_items(&folder_path, &app_state.default_transport).await;
-                        if let Ok(items) = items {
-                            app_state.file_explorer.open_folder(ExplorerItem::Folder {
-                                path: folder_path.to_path_buf(),
-                                state: FolderState::Opened(items),
-                            });
+                        match items {
+                            Ok(items) => {
+                                app_state.file_explorer.open_folder(ExplorerItem::Folder {
+                                    path: folder_path.to_path_buf(),
+                                    state: FolderState::Opened(items),
+                                    is_ignored: false,
+                                });
+                            }
+                            Err(err) => {
+                                drop(app_state);
+                                radio_app_state
+                                    .write_channel(AppChannel::Notifications)
+                                    .notify(Notification::error(format!(
+                                        "Failed to open {}: {err}",
+                                        folder_path.display()
+                                    )));
+                            }
                         }
                     }
                 }
             });
         });
 
+        use_hook(|| spawn(autosave_session(radio_app_state)));
+        use_hook(|| spawn(backup_edited_buffers(radio_app_state)));
+
         use_hook(|| {
             spawn(async move {
                 let res = watch_settings(radio_app_state).await;
@@ -119,6 +245,53 @@ impl App for AppView {
             })
         });
 
+        use_hook(|| {
+            spawn(async move {
+                let res = watch_open_files(radio_app_state).await;
+                if res.is_none() {
+                    info!("Failed to watch the open files in background.");
+                }
+            })
+        });
+
+        use_hook(|| {
+            spawn(async move {
+                let res = watch_explorer_folders(radio_app_state).await;
+                if res.is_none() {
+                    info!("Failed to watch the explorer folders in background.");
+                }
+            })
+        });
+
+        use_hook(|| {
+            spawn(async move {
+                let res = watch_git_status(radio_app_state).await;
+                if res.is_none() {
+                    info!("Failed to watch the git status in background.");
+                }
+            })
+        });
+
+        // Reveal the file of the active tab in the explorer, if enabled
+        let mut followed_path = use_state(|| None::<PathBuf>);
+        use_side_effect(move || {
+            let path = radio_app_state
+                .read()
+                .settings
+                .explorer
+                .follow_active_file
+                .then(|| radio_app_state.get_active_editor_tab())
+                .flatten()
+                .map(|tab_id| radio_app_state.read().editor_tab(tab_id).path.clone());
+            if *followed_path.peek() == path {
+                return;
+            }
+            followed_path.set(path.clone());
+            if let Some(path) = path {
+                spawn(reveal_in_explorer(radio_app_state, path, false));
+            }
+        });
+
         let mut editor_commands = use_hook(|| State::create(EditorCommands::default()));
         let mut keyboard_shorcuts = use_hook(|| State::create(KeyboardShortcuts::default()));
 
@@ -133,9 +306,25 @@ impl App for AppView {
                 &mut editor_commands.write(),
                 radio_app_state,
             );
+            FileExplorer::init(
+                &mut keyboard_shorcuts.write(),
+                &mut editor_commands.write(),
+                radio_app_state,
+            );
+            DiffTab::init(
+                &mut keyboard_shorcuts.write(),
+                &mut editor_commands.write(),
+                radio_app_state,
+            );
         });
 
         let on_global_key_down = move |e: Event<KeyboardEventData>| {
+            let is_ctrl = matches!(e.data().code, Code::ControlLeft | Code::ControlRight);
+            if is_ctrl && !radio_app_state.read().is_ctrl_pressed {
+                radio_app_state
+                    .write_channel(AppChannel::Modifiers)
+                    .is_ctrl_pressed = true;
+            }
             let handled = keyboard_shorcuts.write().run(
                 e.data(),
                 &mut editor_commands.write(),
@@ -153,6 +342,9 @@ impl App for AppView {
             if !is_ctrl {
                 return;
             }
+            radio_app_state
+                .write_channel(AppChannel::Modifiers)
+                .is_ctrl_pressed = false;
             if radio_app_state.read().tab_switcher.is_none() {
                 return;
             }
@@ -160,9 +352,13 @@ impl App for AppView {
             app_state.commit_tab_switcher();
         };
 
-        let (focused_view, side_panel) = {
+        let (focused_view, side_panel, search_revision) = {
             let app_state = radio_app_state.read();
-            (app_state.focused_view, app_state.side_panel)
+            (
+                app_state.focused_view,
+                app_state.side_panel,
+                app_state.workspace_search.revision,
+            )
         };
 
         let app_state_writable = radio_app_state.slice_mut_current(|s| s).into_writable();
@@ -220,7 +416,25 @@ impl App for AppView {
                         on_close: (move |_: ()| {
                             radio_app_state
                                 .write_channel(AppChannel::Global)
-                                .close_tab(tab_id);
+                                .request_close(CloseRequest::Tab(tab_id));
+                        })
+                        .into(),
+                        on_context_menu: (move |position: CursorPoint| {
+                            let mut state = radio_app_state.write_channel(AppChannel::Global);
+                            let panel_id = state
+                                .panel_tree
+                                .as_ref()
+                                .and_then(|tree| tree.find_tab(&tab_id))
+                                .map(|(panel_id, _)| panel_id);
+                            if let Some(panel_id) = panel_id {
+                                // The entries act on the active tab, so activate this one first
+                                state.focused_panel = Some(panel_id);
+                                state.focus_tab(panel_id, Some(tab_id));
+                                if state.focused_view != EditorView::Panels {
+                                    state.focused_view = EditorView::Panels;
+                                }
+                            }
+                            state.open_context_menu(position, tab_context_menu_entries());
                         })
                         .into(),
                         value: tab_data.title,
@@ -239,6 +453,7 @@ impl App for AppView {
                         .child(EditorTabButton {
                             tab_id,
                             on_close: (|_: ()| {}).into(),
+                            on_context_menu: (|_: CursorPoint| {}).into(),
                             value: tab_data.title,
                             is_selected: true,
                             icon: tab_data.icon,
@@ -261,7 +476,7 @@ impl App for AppView {
                     e.prevent_default();
                     radio_app_state
                         .write_channel(AppChannel::Global)
-                        .close_panel(panel_id);
+                        .request_close(CloseRequest::Panel(panel_id));
                 };
 
                 rect()
@@ -337,7 +552,15 @@ impl App for AppView {
                         .order(0usize)
                         .min_size(10.)
                         .child(match panel {
-                            EditorSidePanel::FileExplorer => FileExplorer,
+                            EditorSidePanel::FileExplorer => FileExplorer.into_element(),
+                            EditorSidePanel::Search => WorkspaceSearch {
+                                revision: search_revision,
+                            }
+                            .into_element(),
+                            EditorSidePanel::Problems => Problems.into_element(),
+                            EditorSidePanel::References => References.into_element(),
+                            EditorSidePanel::Notifications => Notifications.into_element(),
+                            EditorSidePanel::SourceControl => SourceControl.into_element(),
                         }),
                 );
             }
@@ -365,6 +588,17 @@ impl App for AppView {
             .maybe_child(
                 (focused_view == EditorView::FileSearch).then_some(FileSearch { radio_app_state }),
             )
+            .maybe_child(
+                (focused_view == EditorView::GoToLine).then_some(GoToLine { radio_app_state }),
+            )
+            .maybe_child(
+                (focused_view == EditorView::ConfirmClose)
+                    .then_some(ConfirmClose { radio_app_state }),
+            )
+            .maybe_child(
+                (focused_view == EditorView::FilePrompt)
+                    .then_some(FilePromptPopup { radio_app_state }),
+            )
             .maybe_child(
                 (focused_view == EditorView::TabSwitcher)
                     .then_some(TabSwitcher { radio_app_state }),
@@ -375,5 +609,7 @@ impl App for AppView {
                     .child(main_container),
             )
             .child(StatusBar { focused_view })
+            .child(Toasts)
+            .child(ContextMenu { editor_commands })
     }
 }
@@ -0,0 +1,178 @@
+use std::{
+    collections::HashMap,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    diff::{DiffLine, diff_lines},
+    settings::home_dir,
+    state::{AppState, RadioAppState},
+};
+
+/// How often edited buffers are checked for changes to back up.
+const BACKUP_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Serialize, Deserialize)]
+struct Backup {
+    path: PathBuf,
+    content: String,
+    /// Seconds since the Unix epoch.
+    modified: u64,
+}
+
+/// An unsaved buffer backed up by a previous run.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RecoveredFile {
+    pub path: PathBuf,
+    pub content: String,
+    pub modified: SystemTime,
+    /// Whether the file still exists on disk.
+    pub exists: bool,
+    /// Changes against the file on disk.
+    pub diff: Vec<DiffLine>,
+}
+
+impl RecoveredFile {
+    fn new(backup: Backup) -> Self {
+        let on_disk = fs::read_to_string(&backup.path).ok();
+        let diff = diff_lines(on_disk.as_deref().unwrap_or_default(), &backup.content);
+        Self {
+            exists: on_disk.is_some(),
+            diff,
+            modified: UNIX_EPOCH + Duration::from_secs(backup.modified),
+            path: backup.path,
+            content: backup.content,
+        }
+    }
+}
+
+fn backups_dir() -> Option<PathBuf> {
+    Some(home_dir()?.join(".valin").join("backups"))
+}
+
+/// 64-bit FNV-1a hash. Unlike [DefaultHasher] it's the same across builds, so the next
+/// run finds the backups of this one.
+fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn backup_path(path: &Path) -> Option<PathBuf> {
+    let hash = stable_hash(path.as_os_str().as_encoded_bytes());
+    Some(backups_dir()?.join(format!("{hash:016x}.json")))
+}
+
+/// Buffers left unsaved by a previous run, sorted by path.
+pub fn load_recovered_files() -> Vec<RecoveredFile> {
+    let Some(entries) = backups_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut files = entries
+        .flatten()
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.path()).ok()?;
+            let backup = serde_json::from_str::<Backup>(&content).ok()?;
+            Some(RecoveredFile::new(backup))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Forget the backup of a file.
+pub fn remove_backup(path: &Path) {
+    if let Some(backup_path) = backup_path(path) {
+        let _ = fs::remove_file(backup_path);
+    }
+}
+
+/// Forget the backup of a file once it's saved or closed, unless it was left by a
+/// previous run and is still offered to be recovered.
+pub fn forget_backup(app_state: &AppState, path: &Path) {
+    if !app_state
+        .recovered_files
+        .iter()
+        .any(|file| file.path == path)
+    {
+        remove_backup(path);
+    }
+}
+
+/// Keep the backup of a file that was moved under its new path.
+pub fn move_backup(from: &Path, to: &Path) -> Option<()> {
+    let from_backup_path = backup_path(from)?;
+    let content = fs::read_to_string(&from_backup_path).ok()?;
+    let mut backup = serde_json::from_str::<Backup>(&content).ok()?;
+    backup.path = to.to_path_buf();
+    fs::write(backup_path(to)?, serde_json::to_string(&backup).ok()?).ok()?;
+    fs::remove_file(from_backup_path).ok()
+}
+
+fn write_backup(path: &Path, content: String) -> Option<()> {
+    let backup_path = backup_path(path)?;
+    fs::create_dir_all(backup_path.parent()?).ok()?;
+    let backup = Backup {
+        path: path.to_path_buf(),
+        content,
+        modified: SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs(),
+    };
+    fs::write(backup_path, serde_json::to_string(&backup).ok()?).ok()
+}
+
+/// Back up the edited buffers every few seconds, forgetting them once they
+/// are saved or closed. Whatever is left when valin exits, or crashes, is
+/// offered to be recovered on the next launch.
+pub async fn backup_edited_buffers(radio_app_state: RadioAppState) {
+    // Content hash of the backups written by this run
+    let mut written = HashMap::<PathBuf, u64>::new();
+    loop {
+        smol::Timer::after(BACKUP_INTERVAL).await;
+
+        let edited = radio_app_state
+            .read()
+            .tabs
+            .values()
+            .filter_map(|tab| tab.as_text_editor())
+            .filter(|editor_tab| editor_tab.data.is_edited())
+            .map(|editor_tab| (editor_tab.path.clone(), editor_tab.data.rope.clone()))
+            .collect::<HashMap<_, _>>();
+
+        written.retain(|path, _| {
+            let is_edited = edited.contains_key(path);
+            if !is_edited {
+                remove_backup(path);
+            }
+            is_edited
+        });
+
+        for (path, rope) in edited {
+            let mut hasher = DefaultHasher::new();
+            for chunk in rope.chunks() {
+                chunk.hash(&mut hasher);
+            }
+            let hash = hasher.finish();
+            if written.get(&path) == Some(&hash) {
+                continue;
+            }
+            let content = rope.to_string();
+            let backed_up = smol::unblock({
+                let path = path.clone();
+                move || write_backup(&path, content)
+            })
+            .await;
+            // Failed backups are tried again on the next round
+            if backed_up.is_some() {
+                written.insert(path, hash);
+            } else {
+                warn!("Failed to back up {path:?}");
+            }
+        }
+    }
+}
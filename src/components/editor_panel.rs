@@ -1,5 +1,6 @@
 use super::{CrossIcon, Logo};
 use crate::state::{AppState, Channel, EditorView, PanelId, TabId};
+use crate::views::context_menu::context_menu_state::secondary_press_location;
 use freya::prelude::*;
 use freya::radio::use_radio;
 
@@ -43,6 +44,7 @@ pub struct EditorTabButton {
     pub tab_id: TabId,
     pub value: String,
     pub on_close: EventHandler<()>,
+    pub on_context_menu: EventHandler<CursorPoint>,
     pub is_selected: bool,
     pub icon: Option<Bytes>,
 }
@@ -52,7 +54,14 @@ impl Component for EditorTabButton {
         let mut is_hovering = use_state(|| false);
 
         let radio = use_radio::<AppState, Channel>(Channel::follow_tab(self.tab_id));
-        let is_edited = radio.read().tab(&self.tab_id).get_data().edited;
+        let (is_edited, git_status) = {
+            let app_state = radio.read();
+            let tab = app_state.tab(&self.tab_id);
+            let git_status = tab
+                .as_text_editor()
+                .and_then(|editor_tab| app_state.git.status(&editor_tab.path));
+            (tab.get_data().edited, git_status)
+        };
 
         let background = match (*is_hovering.read(), self.is_selected) {
             (_, true) | (true, _) => (13, 17, 23).into(),
@@ -64,13 +73,21 @@ impl Component for EditorTabButton {
             background
         };
 
+        let title_color: Color = git_status.map_or((230, 237, 243).into(), |status| status.color());
+
         let on_close = self.on_close.clone();
+        let on_context_menu = self.on_context_menu.clone();
 
         rect()
             .width(Size::px(140.0))
             .height(Size::fill())
             .on_pointer_over(move |_| is_hovering.set(true))
             .on_pointer_out(move |_| is_hovering.set(false))
+            .on_pointer_press(move |e: Event<PointerEventData>| {
+                if let Some(position) = secondary_press_location(&e) {
+                    on_context_menu.call(position);
+                }
+            })
             .child(
                 rect()
                     .height(Size::px(2.))
@@ -97,6 +114,7 @@ impl Component for EditorTabButton {
                             .max_lines(1)
                             .font_size(13.)
                             .text_overflow(TextOverflow::Ellipsis)
+                            .color(title_color)
                             .text(self.value.clone()),
                     )
                     .child(
@@ -109,7 +127,8 @@ impl Component for EditorTabButton {
                                 e.prevent_default();
                                 on_close.call(());
                             })
-                            .maybe_child(if is_edited {
+                            // Unsaved changes show a dot, until hovered to close
+                            .maybe_child(if is_edited && !*is_hovering.read() {
                                 Some(
                                     rect()
                                         .center()
@@ -1,11 +1,13 @@
 mod editor_panel;
 mod icons;
+mod option_toggle;
 mod overlay;
 mod status_bar;
 mod tab;
 
 pub use editor_panel::*;
 pub use icons::*;
+pub use option_toggle::*;
 pub use overlay::*;
 pub use status_bar::*;
 pub use tab::*;
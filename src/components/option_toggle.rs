@@ -0,0 +1,27 @@
+use freya::prelude::*;
+
+/// Small toggle button for search options like case sensitivity or regex.
+#[derive(Clone, PartialEq)]
+pub struct OptionToggle {
+    pub text: &'static str,
+    pub enabled: bool,
+    pub on_press: EventHandler<Event<PressEventData>>,
+}
+
+impl Component for OptionToggle {
+    fn render(&self) -> impl IntoElement {
+        let background = if self.enabled {
+            Color::from((47, 129, 247, 0.4))
+        } else {
+            Color::TRANSPARENT
+        };
+
+        rect().background(background).corner_radius(6.).child(
+            Button::new()
+                .flat()
+                .compact()
+                .on_press(self.on_press.clone())
+                .child(self.text),
+        )
+    }
+}
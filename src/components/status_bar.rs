@@ -3,7 +3,7 @@ use freya::{prelude::*, text_edit::TextEditor};
 
 use crate::{
     state::{Channel, EditorSidePanel, EditorView},
-    views::panels::tabs::settings::Settings,
+    views::{notifications::NotificationsButton, panels::tabs::settings::Settings},
 };
 
 #[derive(Clone, PartialEq)]
@@ -25,7 +25,28 @@ impl Component for StatusBar {
             app_state.toggle_side_panel(EditorSidePanel::FileExplorer);
         };
 
+        let toggle_search = move |_| {
+            let mut app_state = radio_app_state.write_channel(Channel::Global);
+            app_state.toggle_side_panel(EditorSidePanel::Search);
+        };
+
+        let toggle_problems = move |_| {
+            let mut app_state = radio_app_state.write_channel(Channel::Global);
+            app_state.toggle_side_panel(EditorSidePanel::Problems);
+        };
+
+        let toggle_source_control = move |_| {
+            let mut app_state = radio_app_state.write_channel(Channel::Global);
+            app_state.toggle_side_panel(EditorSidePanel::SourceControl);
+        };
+
         let app_state = radio_app_state.read();
+        let (errors, warnings) = app_state.problems.counts();
+        let branch = app_state
+            .source_control
+            .repository(&app_state)
+            .map(|repository| repository.branch.as_deref().unwrap_or("Detached HEAD"))
+            .map(|branch| format!("⎇ {branch}"));
         let active_tab = app_state
             .focused_panel
             .and_then(|pid| app_state.panel_tree.as_ref()?.panel(&pid))
@@ -72,13 +93,35 @@ impl Component for StatusBar {
                                     .on_press(toggle_file_explorer)
                                     .child("📁"),
                             )
+                            .child(
+                                Button::new()
+                                    .flat()
+                                    .compact()
+                                    .on_press(toggle_search)
+                                    .child("🔍"),
+                            )
+                            .child(
+                                Button::new()
+                                    .flat()
+                                    .compact()
+                                    .on_press(toggle_problems)
+                                    .child(format!("⊗ {errors} ⚠ {warnings}")),
+                            )
+                            .maybe_child(branch.map(|branch| {
+                                Button::new()
+                                    .flat()
+                                    .compact()
+                                    .on_press(toggle_source_control)
+                                    .child(branch)
+                            }))
                             .child(
                                 Button::new()
                                     .flat()
                                     .compact()
                                     .on_press(open_settings)
                                     .child("⚙️"),
-                            ),
+                            )
+                            .child(NotificationsButton),
                     )
                     .maybe_child(tab_data.map(|((row, col), language_id)| {
                         rect()
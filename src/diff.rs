@@ -0,0 +1,316 @@
+use std::ops::Range;
+
+/// Past this many edits the changed lines are reported as replaced at once,
+/// to keep the search bounded.
+const MAX_EDITS: usize = 2_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LineChange {
+    Equal,
+    Removed,
+    Added,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DiffLine {
+    pub change: LineChange,
+    /// 0-based line in the old text, if the line is in it.
+    pub old_line: Option<usize>,
+    /// 0-based line in the new text, if the line is in it.
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+/// Lines of `old` and `new`, as removed, added or kept in both.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let equal = |old_line: usize, new_line: usize| DiffLine {
+        change: LineChange::Equal,
+        old_line: Some(old_line),
+        new_line: Some(new_line),
+        text: old[old_line].to_string(),
+    };
+
+    let mut lines = (0..prefix).map(|i| equal(i, i)).collect::<Vec<_>>();
+    let edits = shortest_edit(old_middle, new_middle).unwrap_or_else(|| {
+        (0..old_middle.len())
+            .map(Edit::Remove)
+            .chain((0..new_middle.len()).map(Edit::Add))
+            .collect()
+    });
+    for edit in edits {
+        lines.push(match edit {
+            Edit::Keep(a, b) => equal(prefix + a, prefix + b),
+            Edit::Remove(a) => DiffLine {
+                change: LineChange::Removed,
+                old_line: Some(prefix + a),
+                new_line: None,
+                text: old_middle[a].to_string(),
+            },
+            Edit::Add(b) => DiffLine {
+                change: LineChange::Added,
+                old_line: None,
+                new_line: Some(prefix + b),
+                text: new_middle[b].to_string(),
+            },
+        });
+    }
+    let old_start = old.len() - suffix;
+    let new_start = new.len() - suffix;
+    lines.extend((0..suffix).map(|i| equal(old_start + i, new_start + i)));
+    lines
+}
+
+/// Ranges of `lines` with changes, each with up to `context` unchanged lines
+/// around it. Ranges that would overlap are merged.
+pub fn hunks(lines: &[DiffLine], context: usize) -> Vec<Range<usize>> {
+    let mut hunks: Vec<Range<usize>> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.change == LineChange::Equal {
+            continue;
+        }
+        let start = i.saturating_sub(context);
+        let end = (i + 1 + context).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if last.end >= start => last.end = end,
+            _ => hunks.push(start..end),
+        }
+    }
+    hunks
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// A run of changed lines.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// 0-based lines in the old text, empty if lines were only added.
+    pub old_lines: Range<usize>,
+    /// 0-based lines in the new text, empty if lines were only deleted.
+    pub new_lines: Range<usize>,
+}
+
+/// Every run of consecutive changed lines of `lines`.
+pub fn changes(lines: &[DiffLine]) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let (mut old_line, mut new_line) = (0, 0);
+    let mut lines = lines.iter().peekable();
+    while let Some(line) = lines.next() {
+        if line.change == LineChange::Equal {
+            old_line += 1;
+            new_line += 1;
+            continue;
+        }
+        let (old_start, new_start) = (old_line, new_line);
+        let mut line = line;
+        loop {
+            match line.change {
+                LineChange::Removed => old_line += 1,
+                LineChange::Added => new_line += 1,
+                LineChange::Equal => unreachable!(),
+            }
+            match lines.next_if(|next| next.change != LineChange::Equal) {
+                Some(next) => line = next,
+                None => break,
+            }
+        }
+        let kind = match (old_line > old_start, new_line > new_start) {
+            (true, true) => ChangeKind::Modified,
+            (false, _) => ChangeKind::Added,
+            (true, false) => ChangeKind::Deleted,
+        };
+        changes.push(Change {
+            kind,
+            old_lines: old_start..old_line,
+            new_lines: new_start..new_line,
+        });
+    }
+    changes
+}
+
+/// Indexes of the lines of a diff shown on a row side by side, the old one first.
+pub type SideBySideRow = (Option<usize>, Option<usize>);
+
+/// Lines of a diff side by side, as indexes of `lines`: unchanged lines on both sides,
+/// and each removed line next to the added line replacing it.
+pub fn side_by_side(lines: &[DiffLine]) -> Vec<SideBySideRow> {
+    let mut rows = Vec::new();
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    for (i, line) in lines.iter().enumerate() {
+        match line.change {
+            LineChange::Removed => removed.push(i),
+            LineChange::Added => added.push(i),
+            LineChange::Equal => {
+                pair_lines(&mut rows, &mut removed, &mut added);
+                rows.push((Some(i), Some(i)));
+            }
+        }
+    }
+    pair_lines(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+fn pair_lines(rows: &mut Vec<SideBySideRow>, removed: &mut Vec<usize>, added: &mut Vec<usize>) {
+    for i in 0..removed.len().max(added.len()) {
+        rows.push((removed.get(i).copied(), added.get(i).copied()));
+    }
+    removed.clear();
+    added.clear();
+}
+
+/// Split a line in words, runs of spaces and single symbols.
+fn words(text: &str) -> Vec<&str> {
+    let kind = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+    let mut words = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+        if kind(c) != 2 {
+            while let Some((i, next)) = chars.next_if(|(_, next)| kind(*next) == kind(c)) {
+                end = i + next.len_utf8();
+            }
+        }
+        words.push(&text[start..end]);
+    }
+    words
+}
+
+/// Char ranges of `old` and `new` that differ, comparing them word by word.
+pub fn inline_changes(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let (old_words, new_words) = (words(old), words(new));
+    let char_starts = |words: &[&str]| {
+        let mut starts = vec![0];
+        for word in words {
+            starts.push(starts.last().unwrap() + word.chars().count());
+        }
+        starts
+    };
+    let (old_starts, new_starts) = (char_starts(&old_words), char_starts(&new_words));
+
+    let edits = shortest_edit(&old_words, &new_words).unwrap_or_else(|| {
+        (0..old_words.len())
+            .map(Edit::Remove)
+            .chain((0..new_words.len()).map(Edit::Add))
+            .collect()
+    });
+    let push = |ranges: &mut Vec<Range<usize>>, range: Range<usize>| match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    };
+    let (mut old_ranges, mut new_ranges) = (Vec::new(), Vec::new());
+    for edit in edits {
+        match edit {
+            Edit::Keep(..) => {}
+            Edit::Remove(a) => push(&mut old_ranges, old_starts[a]..old_starts[a + 1]),
+            Edit::Add(b) => push(&mut new_ranges, new_starts[b]..new_starts[b + 1]),
+        }
+    }
+    (old_ranges, new_ranges)
+}
+
+/// Amount of `(added, removed)` lines.
+pub fn diff_stats(lines: &[DiffLine]) -> (usize, usize) {
+    lines
+        .iter()
+        .fold((0, 0), |(added, removed), line| match line.change {
+            LineChange::Added => (added + 1, removed),
+            LineChange::Removed => (added, removed + 1),
+            LineChange::Equal => (added, removed),
+        })
+}
+
+enum Edit {
+    Keep(usize, usize),
+    Remove(usize),
+    Add(usize),
+}
+
+/// Myers' algorithm, `None` if it takes more than [MAX_EDITS].
+fn shortest_edit(a: &[&str], b: &[&str]) -> Option<Vec<Edit>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (n + m) as usize;
+    // Furthest x reached on each diagonal `k`, stored at `k + offset`
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    // `v` for diagonals `-d - 1..=d + 1` before each step `d`
+    let mut trace = Vec::new();
+    let at = |k: isize| (k + offset) as usize;
+
+    let mut end = None;
+    'search: for d in 0..=max.min(MAX_EDITS) as isize {
+        trace.push(v[at(-d - 1)..=at(d + 1)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                end = Some(d);
+                break 'search;
+            }
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..=end?).rev() {
+        let v = &trace[d as usize];
+        let get = |k: isize| v[(k + d + 1) as usize];
+        let k = x - y;
+        let previous_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let previous_x = get(previous_k);
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Keep(x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == previous_x {
+                edits.push(Edit::Add(previous_y as usize));
+            } else {
+                edits.push(Edit::Remove(previous_x as usize));
+            }
+        }
+        (x, y) = (previous_x, previous_y);
+    }
+    edits.reverse();
+    Some(edits)
+}
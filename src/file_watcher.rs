@@ -0,0 +1,202 @@
+use std::{
+    collections::HashSet,
+    hash::{DefaultHasher, Hasher},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use freya::prelude::spawn;
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::{info, warn};
+
+use crate::{
+    state::{Channel, RadioAppState, TabId},
+    views::{notifications::notifications_state::Notification, panels::tabs::diff::DiffTab},
+};
+
+/// How often the watched folders are synced with the open files.
+const SYNC_INTERVAL: Duration = Duration::from_secs(2);
+/// How long to wait for a burst of changes to settle.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Hash of a text, the same however it's split into chunks.
+pub fn content_hash<'a>(chunks: impl IntoIterator<Item = &'a str>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for chunk in chunks {
+        hasher.write(chunk.as_bytes());
+    }
+    hasher.finish()
+}
+
+/// Folders of the files open in editor tabs.
+fn open_folders(radio_app_state: RadioAppState) -> HashSet<PathBuf> {
+    radio_app_state
+        .read()
+        .tabs
+        .values()
+        .filter_map(|tab| tab.as_text_editor()?.path.parent().map(Path::to_path_buf))
+        .collect()
+}
+
+fn find_editor_tab(radio_app_state: RadioAppState, path: &Path) -> Option<TabId> {
+    let app_state = radio_app_state.read();
+    let tab_id = app_state.find_tab_by_content_id(&path.to_string_lossy())?;
+    app_state.tab(&tab_id).as_text_editor().map(|_| tab_id)
+}
+
+async fn read_from_disk(radio_app_state: RadioAppState, path: &Path) -> Option<String> {
+    let transport = radio_app_state.read().default_transport.clone();
+    transport.read_to_string(path).await.ok()
+}
+
+/// Replace the content of a tab with its file, even if it has unsaved changes.
+fn reload_from_disk(mut radio_app_state: RadioAppState, path: PathBuf) {
+    spawn(async move {
+        let Some(content) = read_from_disk(radio_app_state, &path).await else {
+            return;
+        };
+        let Some(tab_id) = find_editor_tab(radio_app_state, &path) else {
+            return;
+        };
+        let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+        let font_size = app_state.font_size();
+        if let Some(editor_tab) = app_state.tab_mut(&tab_id).as_text_editor_mut() {
+            editor_tab.reload(&content, font_size);
+        }
+    });
+}
+
+/// Show the unsaved changes of a tab against its file.
+pub fn compare_with_disk(mut radio_app_state: RadioAppState, path: PathBuf) {
+    spawn(async move {
+        let Some(content) = read_from_disk(radio_app_state, &path).await else {
+            return;
+        };
+        let Some(tab_id) = find_editor_tab(radio_app_state, &path) else {
+            return;
+        };
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        let Some(editor_tab) = app_state.tab(&tab_id).as_text_editor() else {
+            return;
+        };
+        let file_name = editor_tab.file_name();
+        let buffer = editor_tab.data.rope.to_string();
+        DiffTab::open_with(
+            &mut app_state,
+            format!("disk:{}", path.display()),
+            (format!("{file_name} (on disk)"), &content),
+            (format!("{file_name} (unsaved)"), &buffer),
+        );
+    });
+}
+
+/// Reload the tab of a file that changed on disk, or ask what to do if it has unsaved changes.
+async fn check_file(mut radio_app_state: RadioAppState, path: PathBuf) {
+    let Some(tab_id) = find_editor_tab(radio_app_state, &path) else {
+        return;
+    };
+    // Deleted files are kept open as they are
+    let Some(content) = read_from_disk(radio_app_state, &path).await else {
+        return;
+    };
+    let hash = content_hash([content.as_str()]);
+    let (is_known, is_edited, file_name) = {
+        let app_state = radio_app_state.read();
+        let Some(editor_tab) = app_state.tab(&tab_id).as_text_editor() else {
+            return;
+        };
+        (
+            editor_tab.disk_hash == hash,
+            editor_tab.data.is_edited(),
+            editor_tab.file_name(),
+        )
+    };
+    // Either saved by valin or already handled
+    if is_known {
+        return;
+    }
+
+    let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+    let font_size = app_state.font_size();
+    let Some(editor_tab) = app_state.tab_mut(&tab_id).as_text_editor_mut() else {
+        return;
+    };
+    if !is_edited {
+        editor_tab.reload(&content, font_size);
+        info!("Reloaded {path:?}, it changed on disk");
+        return;
+    }
+    // Keeping the unsaved changes doesn't ask again until the file changes again
+    editor_tab.disk_hash = hash;
+    drop(app_state);
+
+    let reload_path = path.clone();
+    radio_app_state
+        .write_channel(Channel::Notifications)
+        .notify(
+            Notification::warning(format!(
+                "{file_name} changed on disk, but it has unsaved changes."
+            ))
+            .sticky()
+            .action("Keep Mine", |_| {})
+            .action("Reload", move |radio_app_state| {
+                reload_from_disk(radio_app_state, reload_path.clone())
+            })
+            .persistent_action("Compare", move |radio_app_state| {
+                compare_with_disk(radio_app_state, path.clone())
+            }),
+        );
+}
+
+/// Follow changes made by other programs to the files open in editor tabs.
+/// Tabs without unsaved changes are reloaded, keeping the cursor where it was.
+pub async fn watch_open_files(radio_app_state: RadioAppState) -> Option<()> {
+    let (tx, mut rx) = futures_channel::mpsc::unbounded::<PathBuf>();
+
+    let mut watcher = RecommendedWatcher::new(
+        move |ev: notify::Result<Event>| {
+            if let Ok(ev) = ev
+                && (ev.kind.is_modify() || ev.kind.is_create())
+            {
+                for path in ev.paths {
+                    let _ = tx.unbounded_send(path);
+                }
+            }
+        },
+        Config::default(),
+    )
+    .ok()?;
+
+    // Folders are watched instead of the files, to follow files replaced by a rename
+    let mut watched = HashSet::<PathBuf>::new();
+    loop {
+        let folders = open_folders(radio_app_state);
+        for folder in watched.difference(&folders) {
+            let _ = watcher.unwatch(folder);
+        }
+        for folder in folders.difference(&watched) {
+            if let Err(err) = watcher.watch(folder, RecursiveMode::NonRecursive) {
+                warn!("Failed to watch {folder:?}: {err}");
+            }
+        }
+        watched = folders;
+
+        let changed = smol::future::or(async { rx.recv().await.ok() }, async {
+            smol::Timer::after(SYNC_INTERVAL).await;
+            None
+        })
+        .await;
+        let Some(path) = changed else {
+            continue;
+        };
+
+        smol::Timer::after(DEBOUNCE).await;
+        let mut changed = HashSet::from([path]);
+        while let Ok(path) = rx.try_recv() {
+            changed.insert(path);
+        }
+        for path in changed {
+            check_file(radio_app_state, path).await;
+        }
+    }
+}
@@ -3,8 +3,6 @@ use std::{
     sync::Arc,
 };
 
-use smol::fs::{File, OpenOptions};
-
 pub type FSTransport = Arc<Box<dyn FSTransportInterface>>;
 
 #[async_trait::async_trait]
@@ -16,7 +14,27 @@ pub trait FSReadTransportInterface {
 pub trait FSTransportInterface: FSReadTransportInterface {
     fn as_read(&self) -> Box<dyn FSReadTransportInterface + 'static>;
 
-    async fn open(&self, path: &Path, open_options: &mut OpenOptions) -> smol::io::Result<File>;
+    /// Replace the content of a file all at once, so a failed write leaves it untouched.
+    async fn write_atomic(&self, path: &Path, content: &[u8]) -> smol::io::Result<()>;
+
+    /// Write a file, creating it if it doesn't exist yet.
+    async fn write(&self, path: &Path, content: &[u8]) -> smol::io::Result<()>;
+
+    /// Create a folder along with its missing parents.
+    async fn create_dir(&self, path: &Path) -> smol::io::Result<()>;
+
+    /// Copy a file, or a folder with everything inside it.
+    async fn copy(&self, from: &Path, to: &Path) -> smol::io::Result<()>;
+
+    async fn rename(&self, from: &Path, to: &Path) -> smol::io::Result<()>;
+
+    /// Delete a file, or a folder with everything inside it, for good.
+    async fn remove(&self, path: &Path) -> smol::io::Result<()>;
+
+    /// Move a file or a folder to the trash, so it can still be restored.
+    async fn trash(&self, path: &Path) -> smol::io::Result<()>;
+
+    async fn exists(&self, path: &Path) -> bool;
 
     async fn read_dir(&self, path: &Path) -> smol::io::Result<smol::fs::ReadDir>;
 
@@ -1,4 +1,7 @@
-use smol::fs::OpenOptions;
+use std::path::Path;
+
+use futures::StreamExt;
+use smol::io::AsyncWriteExt;
 
 use super::{FSReadTransportInterface, FSTransportInterface};
 
@@ -17,12 +20,78 @@ impl FSTransportInterface for FSLocal {
         Box::new(FSLocal)
     }
 
-    async fn open(
-        &self,
-        path: &std::path::Path,
-        open_options: &mut OpenOptions,
-    ) -> smol::io::Result<smol::fs::File> {
-        open_options.open(path).await
+    async fn write_atomic(&self, path: &Path, content: &[u8]) -> smol::io::Result<()> {
+        // Write through symlinks instead of replacing them
+        let path = match smol::fs::canonicalize(path).await {
+            Ok(path) => path,
+            Err(_) => path.to_path_buf(),
+        };
+        let permissions = smol::fs::metadata(&path)
+            .await
+            .ok()
+            .map(|metadata| metadata.permissions());
+
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_path =
+            path.with_file_name(format!(".{file_name}.{}.valin-tmp", std::process::id()));
+
+        let written = async {
+            let mut file = smol::fs::File::create(&temp_path).await?;
+            file.write_all(content).await?;
+            file.sync_all().await?;
+            if let Some(permissions) = permissions {
+                smol::fs::set_permissions(&temp_path, permissions).await?;
+            }
+            smol::fs::rename(&temp_path, &path).await
+        }
+        .await;
+        if written.is_err() {
+            let _ = smol::fs::remove_file(&temp_path).await;
+        }
+        written
+    }
+
+    async fn write(&self, path: &Path, content: &[u8]) -> smol::io::Result<()> {
+        smol::fs::write(path, content).await
+    }
+
+    async fn create_dir(&self, path: &Path) -> smol::io::Result<()> {
+        smol::fs::create_dir_all(path).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> smol::io::Result<()> {
+        if !smol::fs::metadata(from).await?.is_dir() {
+            return smol::fs::copy(from, to).await.map(|_| ());
+        }
+
+        smol::fs::create_dir(to).await?;
+        let mut entries = smol::fs::read_dir(from).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            Box::pin(self.copy(&entry.path(), &to.join(entry.file_name()))).await?;
+        }
+        Ok(())
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> smol::io::Result<()> {
+        smol::fs::rename(from, to).await
+    }
+
+    async fn remove(&self, path: &Path) -> smol::io::Result<()> {
+        if smol::fs::symlink_metadata(path).await?.is_dir() {
+            smol::fs::remove_dir_all(path).await
+        } else {
+            smol::fs::remove_file(path).await
+        }
+    }
+
+    async fn trash(&self, path: &Path) -> smol::io::Result<()> {
+        let path = path.to_path_buf();
+        smol::unblock(move || trash::delete(path).map_err(smol::io::Error::other)).await
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        smol::fs::symlink_metadata(path).await.is_ok()
     }
 
     async fn read_dir(&self, path: &std::path::Path) -> smol::io::Result<smol::fs::ReadDir> {
@@ -1,5 +1,7 @@
 mod interface;
 mod local;
+mod position;
 
 pub use interface::*;
 pub use local::*;
+pub use position::*;
@@ -0,0 +1,42 @@
+use std::path::{Path, PathBuf};
+
+/// Parse a 1-based `line` or `line:col` into a 0-based `(line, col)`.
+pub fn parse_line_col(text: &str) -> Option<(usize, usize)> {
+    let (line, col) = match text.trim().split_once(':') {
+        Some((line, col)) => (line, Some(col)),
+        None => (text.trim(), None),
+    };
+    let line = line.parse::<usize>().ok()?;
+    let col = match col {
+        Some(col) => col.parse::<usize>().ok()?,
+        None => 1,
+    };
+    Some((line.saturating_sub(1), col.saturating_sub(1)))
+}
+
+/// Split a trailing `:line` or `:line:col` off a path, as printed by compilers
+/// and other terminal tools. Drive letters such as `C:\` are left alone.
+pub fn split_position(text: &str) -> (&str, Option<(usize, usize)>) {
+    let colons = text
+        .rmatch_indices(':')
+        .take(2)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    // Try `:line:col` before `:line`
+    for i in colons.into_iter().rev() {
+        if let Some(position) = parse_line_col(&text[i + 1..]) {
+            return (&text[..i], Some(position));
+        }
+    }
+    (text, None)
+}
+
+/// Like [split_position], unless a file with that exact name exists.
+pub fn split_path_position(path: &Path) -> (PathBuf, Option<(usize, usize)>) {
+    if path.exists() {
+        return (path.to_path_buf(), None);
+    }
+    let text = path.to_string_lossy();
+    let (path, position) = split_position(&text);
+    (PathBuf::from(path), position)
+}
@@ -0,0 +1,119 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use super::{repository_path, run_git_with_input};
+
+/// A commit that last changed some lines of a file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlameCommit {
+    pub hash: String,
+    pub author: String,
+    /// Seconds since the Unix epoch.
+    pub time: i64,
+    pub summary: String,
+    /// Path of the file in this commit, it might have been renamed since.
+    pub path: String,
+    /// Parent commit and path of the file in it, unless the file was added in this commit.
+    pub previous: Option<(String, String)>,
+}
+
+impl BlameCommit {
+    /// Lines that aren't committed yet are blamed on a hash made of zeros.
+    pub fn is_committed(&self) -> bool {
+        !self.hash.bytes().all(|byte| byte == b'0')
+    }
+
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(8)]
+    }
+}
+
+/// Commit that last changed each line of a file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Blame {
+    pub root: PathBuf,
+    pub commits: Vec<BlameCommit>,
+    /// Index in `commits` of each line.
+    pub lines: Vec<usize>,
+}
+
+impl Blame {
+    pub fn line(&self, row: usize) -> Option<&BlameCommit> {
+        self.commits.get(*self.lines.get(row)?)
+    }
+}
+
+/// Blame the lines of `content`, the text of a file as it's being edited, so the
+/// unsaved lines show as not committed yet.
+pub async fn read_blame(path: &Path, content: &str) -> io::Result<Blame> {
+    let (root, relative) = repository_path(path)
+        .await
+        .ok_or_else(|| io::Error::other("The file isn't in a git repository"))?;
+    let output = run_git_with_input(
+        &root,
+        &["blame", "--porcelain", "--contents", "-", "--", &relative],
+        Some(content),
+    )
+    .await?;
+    Ok(parse_blame(root, &output))
+}
+
+/// Parse the output of `git blame --porcelain`. Each line starts with a header
+/// `<hash> <original line> <final line>`, followed by the details of its commit the
+/// first time it shows up, and the line itself after a tab.
+fn parse_blame(root: PathBuf, output: &str) -> Blame {
+    let mut commits = Vec::<BlameCommit>::new();
+    let mut indexes = HashMap::<&str, usize>::new();
+    let mut rows = Vec::new();
+    let mut current = None;
+
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let is_header = matches!(key.len(), 40 | 64) && key.bytes().all(|b| b.is_ascii_hexdigit());
+        if is_header {
+            let index = *indexes.entry(key).or_insert_with(|| {
+                commits.push(BlameCommit {
+                    hash: key.to_string(),
+                    ..BlameCommit::default()
+                });
+                commits.len() - 1
+            });
+            let final_line = value
+                .split(' ')
+                .nth(1)
+                .and_then(|line| line.parse::<usize>().ok())
+                .unwrap_or_default();
+            rows.push((final_line.saturating_sub(1), index));
+            current = Some(index);
+            continue;
+        }
+        let Some(commit) = current.and_then(|index| commits.get_mut(index)) else {
+            continue;
+        };
+        match key {
+            "author" => commit.author = value.to_string(),
+            "author-time" => commit.time = value.parse().unwrap_or_default(),
+            "summary" => commit.summary = value.to_string(),
+            "filename" => commit.path = value.to_string(),
+            "previous" => {
+                commit.previous = value
+                    .split_once(' ')
+                    .map(|(hash, path)| (hash.to_string(), path.to_string()))
+            }
+            _ => {}
+        }
+    }
+
+    rows.sort_by_key(|(row, _)| *row);
+    Blame {
+        root,
+        commits,
+        lines: rows.into_iter().map(|(_, index)| index).collect(),
+    }
+}
@@ -0,0 +1,61 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use smol::{io::AsyncWriteExt, process::Command};
+
+/// Run git in a folder, returning what it printed, or what it complained about if it failed.
+pub async fn run_git(dir: &Path, args: &[&str]) -> io::Result<String> {
+    run_git_with_input(dir, args, None).await
+}
+
+/// Like [run_git], writing `input` to its standard input.
+pub async fn run_git_with_input(
+    dir: &Path,
+    args: &[&str],
+    input: Option<&str>,
+) -> io::Result<String> {
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        // Reading the status shouldn't take the index lock from a git running in a terminal
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(input) = input
+        && let Some(mut stdin) = child.stdin.take()
+    {
+        stdin.write_all(input.as_bytes()).await?;
+    }
+
+    let output = child.output().await?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(io::Error::other(message));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Root of the work tree of the local repository containing a folder, if any.
+pub async fn repository_root(folder: &Path) -> Option<PathBuf> {
+    let root = run_git(folder, &["rev-parse", "--show-toplevel"])
+        .await
+        .ok()?;
+    let root = PathBuf::from(root.trim_end());
+    Some(root.canonicalize().unwrap_or(root))
+}
+
+/// Repository of a file, and the path of the file inside of it as git writes it.
+pub async fn repository_path(path: &Path) -> Option<(PathBuf, String)> {
+    let root = repository_root(path.parent()?).await?;
+    let relative = path.strip_prefix(&root).ok()?;
+    Some((root, relative.to_string_lossy().replace('\\', "/")))
+}
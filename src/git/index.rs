@@ -0,0 +1,38 @@
+use std::{io, path::Path};
+
+use super::{repository_path, run_git, run_git_with_input};
+
+/// Content of a file as staged in the index, or `None` if it isn't tracked.
+pub async fn read_staged(path: &Path) -> Option<String> {
+    let (root, relative) = repository_path(path).await?;
+    run_git(&root, &["show", &format!(":{relative}")])
+        .await
+        .ok()
+}
+
+/// Stage `content` as the new version of a file, leaving the file itself alone.
+pub async fn stage_content(path: &Path, content: &str) -> io::Result<()> {
+    let (root, relative) = repository_path(path)
+        .await
+        .ok_or_else(|| io::Error::other("The file isn't in a git repository"))?;
+
+    let staged = run_git(&root, &["ls-files", "--stage", "--", &relative]).await?;
+    let mode = staged
+        .split_whitespace()
+        .next()
+        .unwrap_or("100644")
+        .to_string();
+    let object = run_git_with_input(
+        &root,
+        &["hash-object", "-w", "--stdin", "--path", &relative],
+        Some(content),
+    )
+    .await?;
+    let cache_info = format!("{mode},{},{relative}", object.trim());
+    run_git(
+        &root,
+        &["update-index", "--add", "--cacheinfo", &cache_info],
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,15 @@
+mod blame;
+mod command;
+mod index;
+mod operations;
+mod status;
+#[cfg(test)]
+mod tests;
+mod watcher;
+
+pub use blame::*;
+pub use command::*;
+pub use index::*;
+pub use operations::*;
+pub use status::*;
+pub use watcher::*;
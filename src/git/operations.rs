@@ -0,0 +1,84 @@
+use std::{io, path::Path};
+
+use super::{repository_path, run_git, run_git_with_input};
+
+/// Path of a file inside of a repository as git writes it.
+fn relative_path(root: &Path, path: &Path) -> io::Result<String> {
+    let relative = path
+        .strip_prefix(root)
+        .map_err(|_| io::Error::other(format!("{} isn't in {}", path.display(), root.display())))?;
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Content of a file as of the last commit, or `None` if it wasn't committed.
+pub async fn read_committed(path: &Path) -> Option<String> {
+    let (root, relative) = repository_path(path).await?;
+    read_revision(&root, "HEAD", &relative).await
+}
+
+/// Content of a file as of a revision, or `None` if it didn't exist then.
+/// `relative` is the path inside of the repository as git writes it.
+pub async fn read_revision(root: &Path, revision: &str, relative: &str) -> Option<String> {
+    run_git(root, &["show", &format!("{revision}:{relative}")])
+        .await
+        .ok()
+}
+
+/// Stage every change of a file, including its removal.
+pub async fn stage_file(root: &Path, path: &Path) -> io::Result<()> {
+    let relative = relative_path(root, path)?;
+    run_git(root, &["add", "--all", "--", &relative]).await?;
+    Ok(())
+}
+
+/// Take the changes of a file out of the index, leaving the file itself alone.
+pub async fn unstage_file(root: &Path, path: &Path) -> io::Result<()> {
+    let relative = relative_path(root, path)?;
+    run_git(root, &["reset", "--quiet", "--", &relative]).await?;
+    Ok(())
+}
+
+/// Put back the staged version of a file, dropping the changes made to it since.
+/// Untracked files are left to the caller, git can't bring them back.
+pub async fn discard_file(root: &Path, path: &Path) -> io::Result<()> {
+    let relative = relative_path(root, path)?;
+    run_git(root, &["checkout", "--quiet", "--", &relative]).await?;
+    Ok(())
+}
+
+/// Commit the staged changes, or replace the last commit with them if `amend`.
+pub async fn commit(root: &Path, message: &str, amend: bool) -> io::Result<()> {
+    let mut args = vec!["commit", "--quiet", "--file", "-"];
+    if amend {
+        args.push("--amend");
+    }
+    run_git_with_input(root, &args, Some(message)).await?;
+    Ok(())
+}
+
+/// Message of the last commit, to start amending it from.
+pub async fn last_commit_message(root: &Path) -> io::Result<String> {
+    commit_message(root, "HEAD").await
+}
+
+/// Full message of a commit.
+pub async fn commit_message(root: &Path, revision: &str) -> io::Result<String> {
+    let message = run_git(root, &["log", "-1", "--format=%B", revision, "--"]).await?;
+    Ok(message.trim_end().to_string())
+}
+
+/// Names of the local branches.
+pub async fn local_branches(root: &Path) -> io::Result<Vec<String>> {
+    let output = run_git(
+        root,
+        &["for-each-ref", "--format=%(refname:short)", "refs/heads"],
+    )
+    .await?;
+    Ok(output.lines().map(str::to_string).collect())
+}
+
+/// Check out a local branch. Git refuses when it would overwrite uncommitted changes.
+pub async fn switch_branch(root: &Path, branch: &str) -> io::Result<()> {
+    run_git(root, &["switch", "--quiet", branch]).await?;
+    Ok(())
+}
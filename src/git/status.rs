@@ -0,0 +1,262 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use freya::prelude::Color;
+use futures_channel::mpsc::UnboundedSender;
+
+use super::run_git;
+
+/// Status of a file in the work tree, sorted from the least to the most relevant,
+/// so folders can show the most relevant one of their files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GitStatus {
+    Ignored,
+    Untracked,
+    Added,
+    Modified,
+    Conflicted,
+}
+
+impl GitStatus {
+    /// From the `XY` code of `git status --porcelain`.
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "!!" => Some(Self::Ignored),
+            "??" => Some(Self::Untracked),
+            "DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU" => Some(Self::Conflicted),
+            _ if code.starts_with(['A', 'R', 'C']) => Some(Self::Added),
+            _ if code.trim().is_empty() => None,
+            _ => Some(Self::Modified),
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Ignored => (110, 118, 129).into(),
+            Self::Untracked | Self::Added => (63, 185, 80).into(),
+            Self::Modified => (210, 153, 34).into(),
+            Self::Conflicted => (248, 81, 73).into(),
+        }
+    }
+
+    /// Letter shown next to the file.
+    pub fn badge(&self) -> Option<&'static str> {
+        match self {
+            Self::Ignored => None,
+            Self::Untracked => Some("U"),
+            Self::Added => Some("A"),
+            Self::Modified => Some("M"),
+            Self::Conflicted => Some("C"),
+        }
+    }
+}
+
+/// A changed file, as listed by the source control panel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    /// Letter of the change, `M`, `A`, `D`, `R`, `C` or `T` like git writes them,
+    /// `U` for untracked files and `!` for conflicts.
+    pub code: char,
+}
+
+impl FileChange {
+    pub fn color(&self) -> Color {
+        match self.code {
+            'A' | 'U' | 'C' => GitStatus::Added.color(),
+            'D' | '!' => GitStatus::Conflicted.color(),
+            _ => GitStatus::Modified.color(),
+        }
+    }
+}
+
+/// Status of the work tree of a repository.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepositoryStatus {
+    pub root: PathBuf,
+    /// Checked out branch, `None` when the `HEAD` is detached.
+    pub branch: Option<String>,
+    /// Files changed in the index.
+    pub staged: Vec<FileChange>,
+    /// Files changed in the work tree but not in the index, conflicts and untracked files.
+    pub unstaged: Vec<FileChange>,
+    /// The changed files, and the untracked or ignored folders as a whole.
+    entries: HashMap<PathBuf, GitStatus>,
+    /// Most relevant status of the changed files inside of each folder.
+    folders: HashMap<PathBuf, GitStatus>,
+}
+
+impl RepositoryStatus {
+    pub fn new(root: PathBuf, entries: HashMap<PathBuf, GitStatus>) -> Self {
+        let mut folders = HashMap::<PathBuf, GitStatus>::new();
+        for (path, status) in &entries {
+            if *status == GitStatus::Ignored {
+                continue;
+            }
+            for folder in path.ancestors().skip(1) {
+                if !folder.starts_with(&root) {
+                    break;
+                }
+                let folder_status = folders.entry(folder.to_path_buf()).or_insert(*status);
+                *folder_status = (*folder_status).max(*status);
+            }
+        }
+        Self {
+            root,
+            branch: None,
+            staged: Vec::new(),
+            unstaged: Vec::new(),
+            entries,
+            folders,
+        }
+    }
+
+    pub fn status(&self, path: &Path) -> Option<GitStatus> {
+        if let Some(status) = self.entries.get(path) {
+            return Some(*status);
+        }
+        // Everything inside of an untracked or ignored folder is as well
+        let inherited = path
+            .ancestors()
+            .skip(1)
+            .take_while(|folder| folder.starts_with(&self.root))
+            .find_map(|folder| self.entries.get(folder))
+            .filter(|status| matches!(status, GitStatus::Untracked | GitStatus::Ignored));
+        inherited.or_else(|| self.folders.get(path)).copied()
+    }
+}
+
+/// Read the status of the work tree of a repository.
+pub async fn read_status(root: &Path) -> io::Result<RepositoryStatus> {
+    let output = run_git(
+        root,
+        &[
+            "status",
+            "--porcelain=v1",
+            "-z",
+            "--branch",
+            "--ignored=matching",
+            "--untracked-files=normal",
+        ],
+    )
+    .await?;
+
+    let mut branch = None;
+    let mut entries = HashMap::new();
+    let (mut staged, mut unstaged) = (Vec::new(), Vec::new());
+    let mut lines = output.split('\0');
+    while let Some(line) = lines.next() {
+        if let Some(header) = line.strip_prefix("## ") {
+            branch = parse_branch(header);
+            continue;
+        }
+        if line.len() < 4 {
+            continue;
+        }
+        let (code, path) = line.split_at(3);
+        let code = &code[..2];
+        // Renames and copies are followed by the path they come from
+        if code.starts_with(['R', 'C']) {
+            lines.next();
+        }
+        let path = root.join(path.trim_end_matches('/'));
+        let status = GitStatus::from_code(code);
+        let (index, work_tree) = (code.as_bytes()[0] as char, code.as_bytes()[1] as char);
+        match status {
+            Some(GitStatus::Ignored) | None => {}
+            Some(GitStatus::Untracked) => unstaged.push(FileChange {
+                path: path.clone(),
+                code: 'U',
+            }),
+            Some(GitStatus::Conflicted) => unstaged.push(FileChange {
+                path: path.clone(),
+                code: '!',
+            }),
+            Some(_) => {
+                if index != ' ' {
+                    staged.push(FileChange {
+                        path: path.clone(),
+                        code: index,
+                    });
+                }
+                if work_tree != ' ' {
+                    unstaged.push(FileChange {
+                        path: path.clone(),
+                        code: work_tree,
+                    });
+                }
+            }
+        }
+        if let Some(status) = status {
+            entries.insert(path, status);
+        }
+    }
+    staged.sort_by(|a, b| a.path.cmp(&b.path));
+    unstaged.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(RepositoryStatus {
+        branch,
+        staged,
+        unstaged,
+        ..RepositoryStatus::new(root.to_path_buf(), entries)
+    })
+}
+
+/// Branch from the header of `git status --branch`, like `main...origin/main [ahead 1]`
+/// or `No commits yet on main`.
+fn parse_branch(header: &str) -> Option<String> {
+    if header.starts_with("HEAD (no branch)") {
+        return None;
+    }
+    let header = header
+        .strip_prefix("No commits yet on ")
+        .or_else(|| header.strip_prefix("Initial commit on "))
+        .unwrap_or(header);
+    let branch = header.split("...").next()?.split(" [").next()?;
+    Some(branch.to_string())
+}
+
+/// Status of the repositories of the folders opened in the explorer.
+#[derive(Default)]
+pub struct GitState {
+    pub repositories: Vec<RepositoryStatus>,
+    refresh_sender: Option<UnboundedSender<PathBuf>>,
+}
+
+impl GitState {
+    /// The innermost repository containing a path.
+    pub fn repository(&self, path: &Path) -> Option<&RepositoryStatus> {
+        self.repositories
+            .iter()
+            .filter(|repository| path.starts_with(&repository.root))
+            .max_by_key(|repository| repository.root.components().count())
+    }
+
+    pub fn status(&self, path: &Path) -> Option<GitStatus> {
+        self.repository(path)?.status(path)
+    }
+
+    pub fn set_repository(&mut self, status: RepositoryStatus) {
+        match self
+            .repositories
+            .iter_mut()
+            .find(|repository| repository.root == status.root)
+        {
+            Some(repository) => *repository = status,
+            None => self.repositories.push(status),
+        }
+    }
+
+    pub fn set_refresh_sender(&mut self, sender: UnboundedSender<PathBuf>) {
+        self.refresh_sender = Some(sender);
+    }
+
+    /// Read the status of the repository of a path again, like after saving it.
+    pub fn request_refresh(&self, path: &Path) {
+        if let Some(sender) = &self.refresh_sender {
+            let _ = sender.unbounded_send(path.to_path_buf());
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::path::{Path, PathBuf};
+
+use smol::block_on;
+use tempfile::TempDir;
+
+use super::{
+    commit, discard_file, last_commit_message, local_branches, read_staged, read_status, run_git,
+    stage_file, switch_branch, unstage_file,
+};
+
+/// A repository on the `main` branch with `file.txt` committed as `one\n`.
+fn repository() -> (TempDir, PathBuf) {
+    let folder = tempfile::tempdir().unwrap();
+    let root = folder.path().canonicalize().unwrap();
+    git(&root, &["init", "--quiet", "--initial-branch", "main"]);
+    git(&root, &["config", "user.name", "Valin"]);
+    git(&root, &["config", "user.email", "valin@example.com"]);
+    git(&root, &["config", "commit.gpgsign", "false"]);
+    write(&root, "one\n");
+    git(&root, &["add", "file.txt"]);
+    git(&root, &["commit", "--quiet", "--message", "Initial"]);
+    (folder, root)
+}
+
+fn git(root: &Path, args: &[&str]) -> String {
+    block_on(run_git(root, args)).unwrap()
+}
+
+fn write(root: &Path, content: &str) {
+    std::fs::write(root.join("file.txt"), content).unwrap();
+}
+
+fn read(root: &Path) -> String {
+    std::fs::read_to_string(root.join("file.txt")).unwrap()
+}
+
+#[test]
+fn stages_and_unstages_files() {
+    let (_folder, root) = repository();
+    let path = root.join("file.txt");
+    write(&root, "two\n");
+
+    block_on(stage_file(&root, &path)).unwrap();
+    assert_eq!(block_on(read_staged(&path)).as_deref(), Some("two\n"));
+    let status = block_on(read_status(&root)).unwrap();
+    assert_eq!(status.staged.len(), 1);
+    assert_eq!(status.staged[0].path, path);
+    assert_eq!(status.staged[0].code, 'M');
+    assert!(status.unstaged.is_empty());
+
+    block_on(unstage_file(&root, &path)).unwrap();
+    assert_eq!(block_on(read_staged(&path)).as_deref(), Some("one\n"));
+    assert_eq!(read(&root), "two\n");
+    let status = block_on(read_status(&root)).unwrap();
+    assert!(status.staged.is_empty());
+    assert_eq!(status.unstaged[0].code, 'M');
+}
+
+#[test]
+fn stages_removed_files() {
+    let (_folder, root) = repository();
+    let path = root.join("file.txt");
+    std::fs::remove_file(&path).unwrap();
+
+    block_on(stage_file(&root, &path)).unwrap();
+    let status = block_on(read_status(&root)).unwrap();
+    assert_eq!(status.staged[0].code, 'D');
+    assert_eq!(block_on(read_staged(&path)), None);
+}
+
+#[test]
+fn discards_unstaged_changes() {
+    let (_folder, root) = repository();
+    let path = root.join("file.txt");
+    write(&root, "two\n");
+    block_on(stage_file(&root, &path)).unwrap();
+    write(&root, "three\n");
+
+    // Goes back to the staged version, not to the committed one
+    block_on(discard_file(&root, &path)).unwrap();
+    assert_eq!(read(&root), "two\n");
+    let status = block_on(read_status(&root)).unwrap();
+    assert!(status.unstaged.is_empty());
+}
+
+#[test]
+fn commits_and_amends() {
+    let (_folder, root) = repository();
+    let path = root.join("file.txt");
+    write(&root, "two\n");
+    block_on(stage_file(&root, &path)).unwrap();
+
+    block_on(commit(&root, "Second\n\nWith a body", false)).unwrap();
+    assert_eq!(
+        block_on(last_commit_message(&root)).unwrap(),
+        "Second\n\nWith a body"
+    );
+    assert!(block_on(read_status(&root)).unwrap().staged.is_empty());
+
+    write(&root, "three\n");
+    block_on(stage_file(&root, &path)).unwrap();
+    block_on(commit(&root, "Amended", true)).unwrap();
+    assert_eq!(block_on(last_commit_message(&root)).unwrap(), "Amended");
+    assert_eq!(git(&root, &["rev-list", "--count", "HEAD"]).trim(), "2");
+    assert_eq!(git(&root, &["show", "HEAD:file.txt"]), "three\n");
+}
+
+#[test]
+fn refuses_empty_commits() {
+    let (_folder, root) = repository();
+    assert!(block_on(commit(&root, "Nothing", false)).is_err());
+}
+
+#[test]
+fn switches_branches() {
+    let (_folder, root) = repository();
+    git(&root, &["branch", "other"]);
+    assert_eq!(block_on(local_branches(&root)).unwrap(), ["main", "other"]);
+
+    block_on(switch_branch(&root, "other")).unwrap();
+    let status = block_on(read_status(&root)).unwrap();
+    assert_eq!(status.branch.as_deref(), Some("other"));
+
+    let path = root.join("file.txt");
+    write(&root, "two\n");
+    block_on(stage_file(&root, &path)).unwrap();
+    block_on(commit(&root, "On other", false)).unwrap();
+
+    // Uncommitted changes that the switch would overwrite keep the branch checked out
+    write(&root, "three\n");
+    assert!(block_on(switch_branch(&root, "main")).is_err());
+    let status = block_on(read_status(&root)).unwrap();
+    assert_eq!(status.branch.as_deref(), Some("other"));
+    assert_eq!(read(&root), "three\n");
+
+    block_on(discard_file(&root, &path)).unwrap();
+    block_on(switch_branch(&root, "main")).unwrap();
+    assert_eq!(read(&root), "one\n");
+}
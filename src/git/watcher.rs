@@ -0,0 +1,256 @@
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use ignore::WalkBuilder;
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::warn;
+
+use crate::{
+    state::{Channel, RadioAppState},
+    views::{
+        file_explorer::file_explorer_filter::unignored_entries,
+        panels::tabs::editor::refresh_git_bases,
+    },
+};
+
+use super::{GitStatus, read_status, repository_root};
+
+/// How often the watched repositories are synced with the opened folders.
+const SYNC_INTERVAL: Duration = Duration::from_secs(2);
+/// How long the work trees must stay quiet before the status is read again.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// Longest a burst of changes can delay the status from being read again.
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// Whether a change under a repository can change its status. Inside of `.git` only
+/// the index, `HEAD` and the refs matter, and nothing that is ignored.
+fn is_relevant(radio_app_state: RadioAppState, root: &Path, path: &Path) -> bool {
+    if let Ok(git_path) = path.strip_prefix(root.join(".git")) {
+        return git_path == Path::new("index")
+            || git_path == Path::new("HEAD")
+            || git_path.starts_with("refs");
+    }
+    radio_app_state.read().git.status(path) != Some(GitStatus::Ignored)
+}
+
+/// The folders inside of `folder` (itself included) that aren't ignored, without going
+/// into ignored ones like `target/` nor into `.git`.
+async fn unignored_folders(folder: &Path) -> Vec<PathBuf> {
+    let folder = folder.to_path_buf();
+    smol::unblock(move || {
+        WalkBuilder::new(&folder)
+            .hidden(false)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_dir()))
+            .map(|entry| entry.into_path())
+            .collect()
+    })
+    .await
+}
+
+/// The folders of a repository that are watched, so big ignored folders like `target/`
+/// don't flood the watcher.
+#[derive(Default)]
+struct WatchedRepository {
+    folders: HashSet<PathBuf>,
+}
+
+impl WatchedRepository {
+    /// Watch the parts of `.git` the status depends on and the non-ignored work tree.
+    async fn watch(watcher: &mut RecommendedWatcher, root: &Path) -> Self {
+        let git = root.join(".git");
+        for (path, mode) in [
+            (&git, RecursiveMode::NonRecursive),
+            (&git.join("refs"), RecursiveMode::Recursive),
+        ] {
+            if let Err(err) = watcher.watch(path, mode) {
+                warn!("Failed to watch {path:?}: {err}");
+            }
+        }
+        let mut repository = Self::default();
+        repository.watch_folders(watcher, root).await;
+        repository
+    }
+
+    async fn watch_folders(&mut self, watcher: &mut RecommendedWatcher, folder: &Path) {
+        for folder in unignored_folders(folder).await {
+            if self.folders.contains(&folder) {
+                continue;
+            }
+            match watcher.watch(&folder, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    self.folders.insert(folder);
+                }
+                Err(err) => warn!("Failed to watch {folder:?}: {err}"),
+            }
+        }
+    }
+
+    /// Start watching the changed paths that are new non-ignored folders, and stop
+    /// tracking the removed ones.
+    async fn sync_folders(&mut self, watcher: &mut RecommendedWatcher, changed: &[PathBuf]) {
+        for path in changed {
+            if !path.exists() {
+                if self.folders.remove(path) {
+                    let _ = watcher.unwatch(path);
+                }
+                continue;
+            }
+            if !path.is_dir() || self.folders.contains(path) {
+                continue;
+            }
+            let Some(parent) = path.parent() else {
+                continue;
+            };
+            if self.folders.contains(parent) && unignored_entries(parent).await.contains(path) {
+                self.watch_folders(watcher, path).await;
+            }
+        }
+    }
+
+    fn unwatch(self, watcher: &mut RecommendedWatcher, root: &Path) {
+        let git = root.join(".git");
+        for path in [git.clone(), git.join("refs")].iter().chain(&self.folders) {
+            let _ = watcher.unwatch(path);
+        }
+    }
+}
+
+async fn refresh_repository(mut radio_app_state: RadioAppState, root: &Path) {
+    match read_status(root).await {
+        Ok(status) => {
+            let is_changed = radio_app_state.read().git.repository(root) != Some(&status);
+            if is_changed {
+                radio_app_state
+                    .write_channel(Channel::Git)
+                    .git
+                    .set_repository(status);
+            }
+        }
+        Err(err) => warn!("Failed to read the git status of {root:?}: {err}"),
+    }
+    // Staging doesn't always change the status, but it does change what the editors
+    // compare against
+    refresh_git_bases(radio_app_state, root).await;
+}
+
+/// Keep the git status of the repositories of the opened folders up to date, as files
+/// change on disk, are saved or are staged.
+pub async fn watch_git_status(mut radio_app_state: RadioAppState) -> Option<()> {
+    let (tx, mut rx) = futures_channel::mpsc::unbounded::<PathBuf>();
+    radio_app_state
+        .write_channel(Channel::Git)
+        .git
+        .set_refresh_sender(tx.clone());
+
+    let mut watcher = RecommendedWatcher::new(
+        move |ev: notify::Result<Event>| {
+            if let Ok(ev) = ev
+                && !ev.kind.is_access()
+            {
+                for path in ev.paths {
+                    let _ = tx.unbounded_send(path);
+                }
+            }
+        },
+        Config::default(),
+    )
+    .ok()?;
+
+    // Repository of each opened folder, `None` for the ones outside of any
+    let mut repositories = HashMap::<PathBuf, Option<PathBuf>>::new();
+    let mut watched = HashMap::<PathBuf, WatchedRepository>::new();
+    loop {
+        let folders = radio_app_state
+            .read()
+            .file_explorer
+            .folders
+            .iter()
+            .map(|folder| folder.path().clone())
+            .collect::<Vec<_>>();
+        repositories.retain(|folder, _| folders.contains(folder));
+        for folder in folders {
+            if let Entry::Vacant(entry) = repositories.entry(folder) {
+                let root = repository_root(entry.key()).await;
+                entry.insert(root);
+            }
+        }
+
+        let roots = repositories
+            .values()
+            .flatten()
+            .cloned()
+            .collect::<HashSet<_>>();
+        let removed = watched
+            .keys()
+            .filter(|root| !roots.contains(*root))
+            .cloned()
+            .collect::<Vec<_>>();
+        for root in &removed {
+            if let Some(repository) = watched.remove(root) {
+                repository.unwatch(&mut watcher, root);
+            }
+        }
+        if !removed.is_empty() {
+            radio_app_state
+                .write_channel(Channel::Git)
+                .git
+                .repositories
+                .retain(|repository| roots.contains(&repository.root));
+        }
+        for root in roots {
+            if let Entry::Vacant(entry) = watched.entry(root.clone()) {
+                entry.insert(WatchedRepository::watch(&mut watcher, &root).await);
+                refresh_repository(radio_app_state, &root).await;
+            }
+        }
+
+        let changed = smol::future::or(async { rx.recv().await.ok() }, async {
+            smol::Timer::after(SYNC_INTERVAL).await;
+            None
+        })
+        .await;
+        let Some(path) = changed else {
+            continue;
+        };
+
+        // Wait for bursts like a checkout rewriting many files to settle
+        let mut changed = vec![path];
+        let deadline = Instant::now() + MAX_DELAY;
+        while Instant::now() < deadline {
+            let next = smol::future::or(async { rx.recv().await.ok() }, async {
+                smol::Timer::after(DEBOUNCE).await;
+                None
+            })
+            .await;
+            let Some(path) = next else {
+                break;
+            };
+            changed.push(path);
+        }
+
+        let mut changed_roots = Vec::new();
+        for (root, repository) in &mut watched {
+            let changed = changed
+                .iter()
+                .filter(|path| path.starts_with(root))
+                .cloned()
+                .collect::<Vec<_>>();
+            repository.sync_folders(&mut watcher, &changed).await;
+            if changed
+                .iter()
+                .any(|path| is_relevant(radio_app_state, root, path))
+            {
+                changed_roots.push(root.clone());
+            }
+        }
+        for root in changed_roots {
+            refresh_repository(radio_app_state, &root).await;
+        }
+    }
+}
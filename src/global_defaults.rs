@@ -1,6 +1,11 @@
+use freya::prelude::Side;
+
 use crate::{
-    state::{Channel, CommandRunContext, EditorCommand, EditorView, RadioAppState},
-    views::panels::tabs::settings::Settings,
+    state::{
+        AppStateUtils, Channel, CloseRequest, CommandRunContext, EditorCommand, EditorSidePanel,
+        EditorView, RadioAppState,
+    },
+    views::panels::tabs::{editor::EditorTab, recovery::RecoveryTab, settings::Settings},
 };
 
 #[allow(non_snake_case)]
@@ -10,9 +15,13 @@ pub mod GlobalDefaults {
     use crate::state::{Channel, EditorCommands, EditorView, KeyboardShortcuts, RadioAppState};
 
     use super::{
-        ClosePanelCommand, CloseTabCommand, CycleTabSwitcherBackCommand, CycleTabSwitcherCommand,
-        FocusNextPanelCommand, FocusPreviousPanelCommand, OpenFileSearchCommand,
-        OpenSettingsCommand, SplitPanelCommand, ToggleCommanderCommand,
+        CloseOtherTabsCommand, ClosePanelCommand, CloseSavedTabsCommand, CloseTabCommand,
+        CloseTabsToTheRightCommand, CycleTabSwitcherBackCommand, CycleTabSwitcherCommand,
+        FocusNextPanelCommand, FocusPreviousPanelCommand, NavigateBackCommand,
+        NavigateForwardCommand, OpenFileSearchCommand, OpenNotificationsCommand,
+        OpenProblemsCommand, OpenRecoveryCommand, OpenSettingsCommand, OpenSourceControlCommand,
+        OpenWorkspaceSearchCommand, SplitDownCommand, SplitPanelCommand, SplitRightCommand,
+        ToggleCommanderCommand,
     };
 
     pub fn init(
@@ -25,12 +34,24 @@ pub mod GlobalDefaults {
         commands.register(ClosePanelCommand(radio_app_state));
         commands.register(ToggleCommanderCommand(radio_app_state));
         commands.register(OpenSettingsCommand(radio_app_state));
+        commands.register(OpenRecoveryCommand(radio_app_state));
         commands.register(CloseTabCommand(radio_app_state));
+        commands.register(CloseOtherTabsCommand(radio_app_state));
+        commands.register(CloseTabsToTheRightCommand(radio_app_state));
+        commands.register(CloseSavedTabsCommand(radio_app_state));
+        commands.register(SplitRightCommand(radio_app_state));
+        commands.register(SplitDownCommand(radio_app_state));
         commands.register(FocusNextPanelCommand(radio_app_state));
         commands.register(FocusPreviousPanelCommand(radio_app_state));
         commands.register(OpenFileSearchCommand(radio_app_state));
+        commands.register(OpenWorkspaceSearchCommand(radio_app_state));
+        commands.register(OpenProblemsCommand(radio_app_state));
+        commands.register(OpenNotificationsCommand(radio_app_state));
+        commands.register(OpenSourceControlCommand(radio_app_state));
         commands.register(CycleTabSwitcherCommand(radio_app_state));
         commands.register(CycleTabSwitcherBackCommand(radio_app_state));
+        commands.register(NavigateBackCommand(radio_app_state));
+        commands.register(NavigateForwardCommand(radio_app_state));
 
         // Register Shortcuts
         keyboard_shorcuts.register(
@@ -41,6 +62,7 @@ pub mod GlobalDefaults {
                 let is_pressing_ctrl = data.modifiers == Modifiers::CONTROL;
                 let is_pressing_ctrl_shift =
                     data.modifiers == (Modifiers::CONTROL | Modifiers::SHIFT);
+                let is_pressing_ctrl_alt = data.modifiers == (Modifiers::CONTROL | Modifiers::ALT);
 
                 match data.code {
                     // Pressing `Escape` closes any popup view
@@ -60,6 +82,18 @@ pub mod GlobalDefaults {
                     Code::KeyP if is_pressing_ctrl => {
                         commands.trigger(OpenFileSearchCommand::id());
                     }
+                    // Pressing `Ctrl Shift F`
+                    Code::KeyF if is_pressing_ctrl_shift => {
+                        commands.trigger(OpenWorkspaceSearchCommand::id());
+                    }
+                    // Pressing `Ctrl Shift M`
+                    Code::KeyM if is_pressing_ctrl_shift => {
+                        commands.trigger(OpenProblemsCommand::id());
+                    }
+                    // Pressing `Ctrl Shift G`
+                    Code::KeyG if is_pressing_ctrl_shift => {
+                        commands.trigger(OpenSourceControlCommand::id());
+                    }
                     // Pressing `Alt E`
                     Code::KeyE if is_pressing_alt => {
                         let mut app_state = radio_app_state.write_channel(Channel::Global);
@@ -89,12 +123,20 @@ pub mod GlobalDefaults {
                     _ if is_pressing_alt && data.key == Key::Character("-".to_string()) => {
                         commands.trigger(ClosePanelCommand::id());
                     }
+                    // Pressing `Alt ArrowLeft`
+                    _ if is_pressing_alt && data.key == Key::Named(NamedKey::ArrowLeft) => {
+                        commands.trigger(NavigateBackCommand::id());
+                    }
                     // Pressing `Alt ArrowRight`
                     _ if is_pressing_alt && data.key == Key::Named(NamedKey::ArrowRight) => {
+                        commands.trigger(NavigateForwardCommand::id());
+                    }
+                    // Pressing `Ctrl Alt ArrowRight`
+                    _ if is_pressing_ctrl_alt && data.key == Key::Named(NamedKey::ArrowRight) => {
                         commands.trigger(FocusNextPanelCommand::id());
                     }
-                    // Pressing `Alt ArrowLeft`
-                    _ if is_pressing_alt && data.key == Key::Named(NamedKey::ArrowLeft) => {
+                    // Pressing `Ctrl Alt ArrowLeft`
+                    _ if is_pressing_ctrl_alt && data.key == Key::Named(NamedKey::ArrowLeft) => {
                         commands.trigger(FocusPreviousPanelCommand::id());
                     }
                     _ => return false,
@@ -231,6 +273,37 @@ impl EditorCommand for OpenSettingsCommand {
     }
 }
 
+#[derive(Clone)]
+pub struct OpenRecoveryCommand(pub RadioAppState);
+
+impl OpenRecoveryCommand {
+    pub fn id() -> &'static str {
+        "open-recovery"
+    }
+}
+
+impl EditorCommand for OpenRecoveryCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Show Recovered Files"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        if !app_state.recovered_files.is_empty() {
+            RecoveryTab::open_with(&mut app_state);
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct CloseTabCommand(pub RadioAppState);
 
@@ -260,6 +333,160 @@ impl EditorCommand for CloseTabCommand {
     }
 }
 
+#[derive(Clone)]
+pub struct CloseOtherTabsCommand(pub RadioAppState);
+
+impl CloseOtherTabsCommand {
+    pub fn id() -> &'static str {
+        "close-other-tabs"
+    }
+}
+
+impl EditorCommand for CloseOtherTabsCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Close Other Tabs"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        if let Some(tab_id) = radio_app_state.get_active_tab() {
+            radio_app_state
+                .write_channel(Channel::Global)
+                .request_close(CloseRequest::OtherTabs(tab_id));
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CloseTabsToTheRightCommand(pub RadioAppState);
+
+impl CloseTabsToTheRightCommand {
+    pub fn id() -> &'static str {
+        "close-tabs-to-the-right"
+    }
+}
+
+impl EditorCommand for CloseTabsToTheRightCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Close Tabs to the Right"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        if let Some(tab_id) = radio_app_state.get_active_tab() {
+            radio_app_state
+                .write_channel(Channel::Global)
+                .request_close(CloseRequest::TabsToTheRight(tab_id));
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CloseSavedTabsCommand(pub RadioAppState);
+
+impl CloseSavedTabsCommand {
+    pub fn id() -> &'static str {
+        "close-saved-tabs"
+    }
+}
+
+impl EditorCommand for CloseSavedTabsCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Close Saved Tabs"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        radio_app_state
+            .write_channel(Channel::Global)
+            .close_saved_tabs();
+    }
+}
+
+#[derive(Clone)]
+pub struct SplitRightCommand(pub RadioAppState);
+
+impl SplitRightCommand {
+    pub fn id() -> &'static str {
+        "split-right"
+    }
+}
+
+impl EditorCommand for SplitRightCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Split Right"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        radio_app_state
+            .write_channel(Channel::Global)
+            .split_active_tab(Side::Right);
+    }
+}
+
+#[derive(Clone)]
+pub struct SplitDownCommand(pub RadioAppState);
+
+impl SplitDownCommand {
+    pub fn id() -> &'static str {
+        "split-down"
+    }
+}
+
+impl EditorCommand for SplitDownCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Split Down"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        radio_app_state
+            .write_channel(Channel::Global)
+            .split_active_tab(Side::Bottom);
+    }
+}
+
 #[derive(Clone)]
 pub struct FocusNextPanelCommand(pub RadioAppState);
 
@@ -314,11 +541,141 @@ impl EditorCommand for OpenFileSearchCommand {
     fn run(&self, ctx: &mut CommandRunContext) {
         let mut radio_app_state = self.0;
         let mut app_state = radio_app_state.write_channel(Channel::Global);
+        app_state.compare_with = None;
         app_state.focus_view(EditorView::FileSearch);
         ctx.focus_previous_view = false;
     }
 }
 
+#[derive(Clone)]
+pub struct OpenWorkspaceSearchCommand(pub RadioAppState);
+
+impl OpenWorkspaceSearchCommand {
+    pub fn id() -> &'static str {
+        "open-workspace-search"
+    }
+}
+
+impl EditorCommand for OpenWorkspaceSearchCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Search in Files"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        app_state.close_popup_before_focus();
+        app_state.side_panel = Some(EditorSidePanel::Search);
+        app_state.workspace_search.focus();
+        ctx.focus_previous_view = false;
+    }
+}
+
+#[derive(Clone)]
+pub struct OpenProblemsCommand(pub RadioAppState);
+
+impl OpenProblemsCommand {
+    pub fn id() -> &'static str {
+        "open-problems"
+    }
+}
+
+impl EditorCommand for OpenProblemsCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Show Problems"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        app_state.close_popup_before_focus();
+        app_state.side_panel = Some(EditorSidePanel::Problems);
+        app_state.focus_view(EditorView::Problems);
+        ctx.focus_previous_view = false;
+    }
+}
+
+#[derive(Clone)]
+pub struct OpenSourceControlCommand(pub RadioAppState);
+
+impl OpenSourceControlCommand {
+    pub fn id() -> &'static str {
+        "open-source-control"
+    }
+}
+
+impl EditorCommand for OpenSourceControlCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Show Source Control"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        radio_app_state.write_channel(Channel::Global).side_panel =
+            Some(EditorSidePanel::SourceControl);
+    }
+}
+
+#[derive(Clone)]
+pub struct OpenNotificationsCommand(pub RadioAppState);
+
+impl OpenNotificationsCommand {
+    pub fn id() -> &'static str {
+        "open-notifications"
+    }
+}
+
+impl EditorCommand for OpenNotificationsCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Show Notifications"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        if app_state.side_panel != Some(EditorSidePanel::Notifications) {
+            app_state.toggle_side_panel(EditorSidePanel::Notifications);
+        }
+        drop(app_state);
+        radio_app_state
+            .write_channel(Channel::Notifications)
+            .notifications
+            .hide_toasts();
+    }
+}
+
 #[derive(Clone)]
 pub struct FocusPreviousPanelCommand(pub RadioAppState);
 
@@ -413,3 +770,61 @@ impl EditorCommand for CycleTabSwitcherBackCommand {
         app_state.cycle_tab_switcher(true);
     }
 }
+
+#[derive(Clone)]
+pub struct NavigateBackCommand(pub RadioAppState);
+
+impl NavigateBackCommand {
+    pub fn id() -> &'static str {
+        "navigate-back"
+    }
+}
+
+impl EditorCommand for NavigateBackCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Go Back"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        EditorTab::navigate_jump_list(radio_app_state, &mut app_state, false);
+    }
+}
+
+#[derive(Clone)]
+pub struct NavigateForwardCommand(pub RadioAppState);
+
+impl NavigateForwardCommand {
+    pub fn id() -> &'static str {
+        "navigate-forward"
+    }
+}
+
+impl EditorCommand for NavigateForwardCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Go Forward"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        EditorTab::navigate_jump_list(radio_app_state, &mut app_state, true);
+    }
+}
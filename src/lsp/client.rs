@@ -0,0 +1,435 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::Path,
+    process::Stdio,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicI64, Ordering},
+    },
+    time::Duration,
+};
+
+use futures::StreamExt;
+use futures_channel::{
+    mpsc::{UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
+use lsp_types::{
+    ClientCapabilities, ClientInfo, InitializeParams, InitializedParams, ServerCapabilities,
+    WorkspaceFolder,
+    notification::{Exit, Initialized, Notification},
+    request::{Initialize, Request, Shutdown},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use smol::{
+    Timer,
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    process::{Child, ChildStdin, ChildStdout, Command},
+};
+use tracing::{info, warn};
+
+use crate::state::AppTask;
+
+use super::path_to_uri;
+
+/// How long a server is given to answer `shutdown` and to exit before it is killed.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How to launch a language server.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LanguageServerCommand {
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+/// A message sent by a language server without being asked for it, like diagnostics.
+#[derive(Clone, Debug)]
+pub struct LspNotification {
+    /// Name of the server that sent it.
+    pub server: String,
+    pub method: String,
+    pub params: Value,
+}
+
+#[derive(Debug, Clone)]
+pub enum LspError {
+    /// The server replied with an error.
+    Response { code: i64, message: String },
+    /// The server exited or the connection was lost.
+    Disconnected,
+    /// The reply couldn't be deserialized.
+    InvalidResponse(String),
+}
+
+impl Display for LspError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Response { code, message } => write!(f, "{message} ({code})"),
+            Self::Disconnected => f.write_str("Language server disconnected"),
+            Self::InvalidResponse(err) => write!(f, "Invalid response: {err}"),
+        }
+    }
+}
+
+type PendingRequests = HashMap<i64, oneshot::Sender<Result<Value, LspError>>>;
+
+enum ConnectionState {
+    /// Waiting for the `initialize` response, messages are held until then.
+    Starting(Vec<String>),
+    Ready,
+    Closed,
+}
+
+struct Connection {
+    state: Mutex<ConnectionState>,
+    pending: Mutex<PendingRequests>,
+    capabilities: Mutex<Option<ServerCapabilities>>,
+    outgoing: UnboundedSender<String>,
+    next_id: AtomicI64,
+}
+
+impl Connection {
+    fn send_raw(&self, message: String) {
+        let _ = self.outgoing.unbounded_send(message);
+    }
+
+    /// Send a message, or hold it until the server is initialized.
+    fn send(&self, message: String) -> bool {
+        match &mut *self.state.lock().unwrap() {
+            ConnectionState::Starting(queue) => queue.push(message),
+            ConnectionState::Ready => self.send_raw(message),
+            ConnectionState::Closed => return false,
+        }
+        true
+    }
+
+    fn close(&self) {
+        *self.state.lock().unwrap() = ConnectionState::Closed;
+        for (_, sender) in self.pending.lock().unwrap().drain() {
+            let _ = sender.send(Err(LspError::Disconnected));
+        }
+    }
+}
+
+/// A JSON-RPC connection to a language server spoken over its stdio.
+#[derive(Clone)]
+pub struct LspClient {
+    name: String,
+    connection: Arc<Connection>,
+    child: Arc<smol::lock::Mutex<Child>>,
+}
+
+impl LspClient {
+    /// Spawn the server and start initializing it in the background.
+    /// Messages sent meanwhile are delivered once it replies to `initialize`.
+    pub fn start(
+        name: String,
+        command: &LanguageServerCommand,
+        root: &Path,
+        notifications: UnboundedSender<AppTask>,
+    ) -> std::io::Result<Self> {
+        let mut child = Command::new(&command.command)
+            .args(&command.args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let (outgoing, outgoing_rx) = futures_channel::mpsc::unbounded::<String>();
+        let connection = Arc::new(Connection {
+            state: Mutex::new(ConnectionState::Starting(Vec::new())),
+            pending: Mutex::default(),
+            capabilities: Mutex::default(),
+            outgoing,
+            next_id: AtomicI64::new(0),
+        });
+
+        smol::spawn(write_messages(stdin, outgoing_rx)).detach();
+        smol::spawn(read_messages(
+            name.clone(),
+            stdout,
+            connection.clone(),
+            notifications,
+        ))
+        .detach();
+
+        let client = Self {
+            name,
+            connection,
+            child: Arc::new(smol::lock::Mutex::new(child)),
+        };
+        client.initialize(root);
+        Ok(client)
+    }
+
+    pub fn capabilities(&self) -> Option<ServerCapabilities> {
+        self.connection.capabilities.lock().unwrap().clone()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(
+            *self.connection.state.lock().unwrap(),
+            ConnectionState::Closed
+        )
+    }
+
+    fn initialize(&self, root: &Path) {
+        let root_uri = path_to_uri(root);
+        #[allow(deprecated)]
+        let params = InitializeParams {
+            process_id: Some(std::process::id()),
+            root_uri: root_uri.clone(),
+            workspace_folders: root_uri.map(|uri| {
+                vec![WorkspaceFolder {
+                    uri,
+                    name: root
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                }]
+            }),
+            capabilities: client_capabilities(),
+            client_info: Some(ClientInfo {
+                name: "valin".to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+            ..Default::default()
+        };
+
+        let (id, receiver) = self.register_request();
+        self.connection
+            .send_raw(request_message(id, Initialize::METHOD, &params));
+
+        let connection = self.connection.clone();
+        let name = self.name.clone();
+        smol::spawn(async move {
+            let response = receiver.await.unwrap_or(Err(LspError::Disconnected));
+            match response.and_then(|value| {
+                serde_json::from_value::<lsp_types::InitializeResult>(value)
+                    .map_err(|err| LspError::InvalidResponse(err.to_string()))
+            }) {
+                Ok(result) => {
+                    *connection.capabilities.lock().unwrap() = Some(result.capabilities);
+                    connection.send_raw(notification_message(
+                        Initialized::METHOD,
+                        &InitializedParams {},
+                    ));
+                    // Flush while holding the lock so nothing jumps ahead of the queue.
+                    let mut state = connection.state.lock().unwrap();
+                    if let ConnectionState::Starting(queue) =
+                        std::mem::replace(&mut *state, ConnectionState::Ready)
+                    {
+                        for message in queue {
+                            connection.send_raw(message);
+                        }
+                    }
+                    drop(state);
+                    info!("Language server [{name}] initialized");
+                }
+                Err(err) => {
+                    warn!("Language server [{name}] failed to initialize: {err}");
+                    connection.close();
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn register_request(&self) -> (i64, oneshot::Receiver<Result<Value, LspError>>) {
+        let id = self.connection.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.connection.pending.lock().unwrap().insert(id, sender);
+        (id, receiver)
+    }
+
+    /// Send a request and wait for its response.
+    pub async fn request<R: Request>(&self, params: R::Params) -> Result<R::Result, LspError> {
+        let (id, receiver) = self.register_request();
+        if !self
+            .connection
+            .send(request_message(id, R::METHOD, &params))
+        {
+            self.connection.pending.lock().unwrap().remove(&id);
+            return Err(LspError::Disconnected);
+        }
+        let value = receiver.await.map_err(|_| LspError::Disconnected)??;
+        serde_json::from_value(value).map_err(|err| LspError::InvalidResponse(err.to_string()))
+    }
+
+    /// Send a notification, no response is expected.
+    pub fn notify<N: Notification>(&self, params: N::Params) {
+        self.connection
+            .send(notification_message(N::METHOD, &params));
+    }
+
+    /// Ask the server to shut down and exit, killing it if it doesn't in time.
+    pub async fn shutdown(&self) {
+        if !self.is_closed() {
+            let shutdown = async {
+                let _ = self.request::<Shutdown>(()).await;
+            };
+            smol::future::or(shutdown, async {
+                Timer::after(SHUTDOWN_TIMEOUT).await;
+            })
+            .await;
+            self.notify::<Exit>(());
+        }
+        self.connection.close();
+
+        let mut child = self.child.lock().await;
+        let exited = async { child.status().await.is_ok() };
+        let exited = smol::future::or(exited, async {
+            Timer::after(SHUTDOWN_TIMEOUT).await;
+            false
+        })
+        .await;
+        if !exited {
+            warn!("Language server [{}] didn't exit, killing it", self.name);
+            let _ = child.kill();
+        }
+    }
+}
+
+fn client_capabilities() -> ClientCapabilities {
+    serde_json::from_value(json!({
+        "textDocument": {
+            "synchronization": { "didSave": true, "dynamicRegistration": false },
+            "publishDiagnostics": { "relatedInformation": true },
+            "completion": {
+                "completionItem": {
+                    "snippetSupport": true,
+                    "documentationFormat": ["markdown", "plaintext"]
+                }
+            },
+            "hover": { "contentFormat": ["markdown", "plaintext"] },
+            "definition": { "linkSupport": true },
+            "references": {}
+        },
+        "workspace": { "workspaceFolders": true, "configuration": true }
+    }))
+    .unwrap_or_default()
+}
+
+fn request_message(id: i64, method: &str, params: &impl serde::Serialize) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }).to_string()
+}
+
+fn notification_message(method: &str, params: &impl serde::Serialize) -> String {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params }).to_string()
+}
+
+async fn write_messages(mut stdin: ChildStdin, mut outgoing: UnboundedReceiver<String>) {
+    while let Some(message) = outgoing.next().await {
+        let header = format!("Content-Length: {}\r\n\r\n", message.len());
+        if stdin.write_all(header.as_bytes()).await.is_err()
+            || stdin.write_all(message.as_bytes()).await.is_err()
+            || stdin.flush().await.is_err()
+        {
+            break;
+        }
+    }
+}
+
+/// Read a single `Content-Length` framed message.
+async fn read_message(reader: &mut BufReader<ChildStdout>) -> Option<Value> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await.ok()? == 0 {
+            return None;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let mut body = vec![0; content_length?];
+    reader.read_exact(&mut body).await.ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+async fn read_messages(
+    name: String,
+    stdout: ChildStdout,
+    connection: Arc<Connection>,
+    notifications: UnboundedSender<AppTask>,
+) {
+    let mut reader = BufReader::new(stdout);
+    while let Some(message) = read_message(&mut reader).await {
+        let id = message.get("id").cloned();
+        let method = message
+            .get("method")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        match (id, method) {
+            // Response to one of our requests
+            (Some(id), None) => {
+                let Some(sender) = id
+                    .as_i64()
+                    .and_then(|id| connection.pending.lock().unwrap().remove(&id))
+                else {
+                    continue;
+                };
+                let result = match message.get("error") {
+                    Some(error) => Err(serde_json::from_value::<ResponseError>(error.clone())
+                        .map(|err| LspError::Response {
+                            code: err.code,
+                            message: err.message,
+                        })
+                        .unwrap_or_else(|err| LspError::InvalidResponse(err.to_string()))),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+                let _ = sender.send(result);
+            }
+            // Request from the server, reply so it doesn't wait forever
+            (Some(id), Some(method)) => {
+                let result = match method.as_str() {
+                    "workspace/configuration" => {
+                        let items = message
+                            .pointer("/params/items")
+                            .and_then(Value::as_array)
+                            .map(Vec::len)
+                            .unwrap_or_default();
+                        Value::Array(vec![Value::Null; items])
+                    }
+                    _ => Value::Null,
+                };
+                connection
+                    .send_raw(json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string());
+            }
+            // Notification from the server
+            (None, Some(method)) => {
+                let params = message.get("params").cloned().unwrap_or(Value::Null);
+                let _ = notifications.unbounded_send(AppTask::LspNotification(LspNotification {
+                    server: name.clone(),
+                    method,
+                    params,
+                }));
+            }
+            (None, None) => {}
+        }
+    }
+
+    info!("Language server [{name}] disconnected");
+    connection.close();
+}
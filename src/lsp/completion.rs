@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use freya::code_editor::Rope;
+use lsp_types::{
+    CompletionParams, CompletionResponse, CompletionTextEdit, Documentation, InsertTextFormat,
+    TextDocumentIdentifier, TextDocumentPositionParams, request::Completion,
+};
+
+use crate::views::panels::tabs::editor::completion::CompletionItem;
+
+use super::{LspClient, LspError, char_to_position, path_to_uri};
+
+/// Characters that trigger the completion even if no word was typed yet.
+pub fn completion_trigger_characters(client: &LspClient) -> Vec<String> {
+    client
+        .capabilities()
+        .and_then(|capabilities| capabilities.completion_provider)
+        .and_then(|provider| provider.trigger_characters)
+        .unwrap_or_default()
+}
+
+/// Ask a language server for the completions at a char index of a document.
+pub async fn request_completion(
+    client: &LspClient,
+    path: &Path,
+    rope: &Rope,
+    cursor: usize,
+) -> Result<Vec<CompletionItem>, LspError> {
+    let Some(uri) = path_to_uri(path) else {
+        return Ok(Vec::new());
+    };
+    let response = client
+        .request::<Completion>(CompletionParams {
+            text_document_position: TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(uri),
+                char_to_position(rope, cursor),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        })
+        .await?;
+
+    let items = match response {
+        Some(CompletionResponse::Array(items)) => items,
+        Some(CompletionResponse::List(list)) => list.items,
+        None => Vec::new(),
+    };
+
+    Ok(items
+        .into_iter()
+        .map(|item| {
+            let insert_text = match item.text_edit {
+                Some(CompletionTextEdit::Edit(edit)) => edit.new_text,
+                Some(CompletionTextEdit::InsertAndReplace(edit)) => edit.new_text,
+                None => item.insert_text.unwrap_or_else(|| item.label.clone()),
+            };
+            let documentation = item.documentation.map(|documentation| match documentation {
+                Documentation::String(text) => text,
+                Documentation::MarkupContent(content) => content.value,
+            });
+            CompletionItem {
+                filter_text: item.filter_text.unwrap_or_else(|| item.label.clone()),
+                sort_text: item.sort_text.unwrap_or_else(|| item.label.clone()),
+                label: item.label,
+                detail: item.detail,
+                documentation,
+                insert_text,
+                is_snippet: item.insert_text_format == Some(InsertTextFormat::SNIPPET),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,58 @@
+use lsp_types::{DiagnosticSeverity, Position, PublishDiagnosticsParams};
+use tracing::warn;
+
+use crate::{
+    state::AppState,
+    views::problems::problems_state::{Problem, Severity},
+};
+
+use super::position_to_char;
+
+/// Store the diagnostics a language server published for a document as problems.
+pub fn publish_diagnostics(
+    app_state: &mut AppState,
+    server: &str,
+    params: PublishDiagnosticsParams,
+) {
+    let Ok(path) = params.uri.to_file_path() else {
+        warn!(
+            "Language server [{server}] sent diagnostics for {}",
+            params.uri
+        );
+        return;
+    };
+
+    // Columns are UTF-16 based, so they need the text to be converted properly.
+    // Files that aren't opened use them as they are.
+    let rope = app_state
+        .find_tab_by_content_id(&path.to_string_lossy())
+        .and_then(|tab_id| app_state.tab(&tab_id).as_text_editor())
+        .map(|editor_tab| editor_tab.data.rope.clone());
+    let to_line_col = |position: Position| match &rope {
+        Some(rope) => {
+            let char_idx = position_to_char(rope, position);
+            let line = rope.char_to_line(char_idx);
+            (line, char_idx - rope.line_to_char(line))
+        }
+        None => (position.line as usize, position.character as usize),
+    };
+
+    let problems = params
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| Problem {
+            severity: match diagnostic.severity {
+                Some(DiagnosticSeverity::WARNING) => Severity::Warning,
+                Some(DiagnosticSeverity::INFORMATION) => Severity::Information,
+                Some(DiagnosticSeverity::HINT) => Severity::Hint,
+                _ => Severity::Error,
+            },
+            message: diagnostic.message,
+            source: diagnostic.source,
+            start: to_line_col(diagnostic.range.start),
+            end: to_line_col(diagnostic.range.end),
+        })
+        .collect();
+
+    app_state.problems.set_problems(server, path, problems);
+}
@@ -0,0 +1,214 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use freya::code_editor::{LanguageId, Rope};
+use futures_channel::mpsc::UnboundedSender;
+use lsp_types::{
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, TextDocumentContentChangeEvent, TextDocumentIdentifier,
+    TextDocumentItem, VersionedTextDocumentIdentifier,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    },
+};
+use tracing::{info, warn};
+
+use crate::state::{AppTask, LspSettings};
+
+use super::{LanguageServerCommand, LspClient, language_key, path_to_uri};
+
+/// A document the language server knows about.
+struct LspDocument {
+    server: String,
+    version: i32,
+    text: Rope,
+}
+
+#[derive(Default)]
+struct LspManagerInner {
+    /// Running servers by language key.
+    clients: HashMap<String, LspClient>,
+    /// Opened documents.
+    documents: HashMap<PathBuf, LspDocument>,
+}
+
+/// Keeps the language servers running and the opened documents in sync with them.
+/// Cheap to clone, all the clones share the same servers.
+#[derive(Clone)]
+pub struct LspManager {
+    inner: Arc<Mutex<LspManagerInner>>,
+    notifications: UnboundedSender<AppTask>,
+}
+
+impl LspManager {
+    pub fn new(notifications: UnboundedSender<AppTask>) -> Self {
+        Self {
+            inner: Arc::default(),
+            notifications,
+        }
+    }
+
+    /// The server handling a document, if it was opened with one.
+    pub fn client_for(&self, path: &Path) -> Option<LspClient> {
+        let inner = self.inner.lock().unwrap();
+        let document = inner.documents.get(path)?;
+        inner.clients.get(&document.server).cloned()
+    }
+
+    /// The running server of a language, started on demand. The server is spawned
+    /// without holding the lock so other documents aren't blocked meanwhile.
+    fn client_for_language(
+        &self,
+        key: &str,
+        command: &LanguageServerCommand,
+        root: &Path,
+    ) -> Option<LspClient> {
+        if let Some(client) = self.inner.lock().unwrap().clients.get(key)
+            && !client.is_closed()
+        {
+            return Some(client.clone());
+        }
+
+        let client =
+            match LspClient::start(key.to_string(), command, root, self.notifications.clone()) {
+                Ok(client) => client,
+                Err(err) => {
+                    warn!(
+                        "Failed to start language server [{key}] `{}`: {err}",
+                        command.command
+                    );
+                    return None;
+                }
+            };
+
+        let mut inner = self.inner.lock().unwrap();
+        // Another document might have started the same server meanwhile
+        if let Some(running) = inner.clients.get(key)
+            && !running.is_closed()
+        {
+            let running = running.clone();
+            drop(inner);
+            smol::spawn(async move { client.shutdown().await }).detach();
+            return Some(running);
+        }
+        info!(
+            "Started language server [{key}] `{}` in {root:?}",
+            command.command
+        );
+        inner.clients.insert(key.to_string(), client.clone());
+        Some(client)
+    }
+
+    /// Notify the server of the language that a document was opened,
+    /// starting the server first if needed.
+    pub fn did_open(
+        &self,
+        settings: &LspSettings,
+        root: &Path,
+        path: &Path,
+        language_id: &LanguageId,
+        text: &Rope,
+    ) {
+        let key = language_key(language_id);
+        let Some(command) = settings.command_for(&key) else {
+            return;
+        };
+        let Some(uri) = path_to_uri(path) else {
+            return;
+        };
+
+        if self.inner.lock().unwrap().documents.contains_key(path) {
+            return;
+        }
+        let Some(client) = self.client_for_language(&key, &command, root) else {
+            return;
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.documents.contains_key(path) {
+            return;
+        }
+        client.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri,
+                language_id: key.clone(),
+                version: 0,
+                text: text.to_string(),
+            },
+        });
+        inner.documents.insert(
+            path.to_path_buf(),
+            LspDocument {
+                server: key,
+                version: 0,
+                text: text.clone(),
+            },
+        );
+    }
+
+    /// Send the new content of a document if it changed since the last sync.
+    pub fn did_change(&self, path: &Path, text: &Rope) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(document) = inner.documents.get_mut(path) else {
+            return;
+        };
+        if document.text == *text {
+            return;
+        }
+        document.version += 1;
+        document.text = text.clone();
+        let version = document.version;
+        let server = document.server.clone();
+
+        let (Some(client), Some(uri)) = (inner.clients.get(&server), path_to_uri(path)) else {
+            return;
+        };
+        client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier::new(uri, version),
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: text.to_string(),
+            }],
+        });
+    }
+
+    pub fn did_save(&self, path: &Path, text: &Rope) {
+        self.did_change(path, text);
+        let (Some(client), Some(uri)) = (self.client_for(path), path_to_uri(path)) else {
+            return;
+        };
+        client.notify::<DidSaveTextDocument>(DidSaveTextDocumentParams {
+            text_document: TextDocumentIdentifier::new(uri),
+            text: None,
+        });
+    }
+
+    pub fn did_close(&self, path: &Path) {
+        let client = self.client_for(path);
+        self.inner.lock().unwrap().documents.remove(path);
+        let (Some(client), Some(uri)) = (client, path_to_uri(path)) else {
+            return;
+        };
+        client.notify::<DidCloseTextDocument>(DidCloseTextDocumentParams {
+            text_document: TextDocumentIdentifier::new(uri),
+        });
+    }
+
+    /// Shut down every running server, they are started again on demand.
+    pub async fn shutdown(&self) {
+        let clients = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.documents.clear();
+            inner
+                .clients
+                .drain()
+                .map(|(_, client)| client)
+                .collect::<Vec<_>>()
+        };
+        futures::future::join_all(clients.iter().map(|client| client.shutdown())).await;
+    }
+}
@@ -0,0 +1,44 @@
+mod client;
+mod completion;
+mod diagnostics;
+mod manager;
+mod symbols;
+#[cfg(test)]
+mod tests;
+
+pub use client::*;
+pub use completion::*;
+pub use diagnostics::*;
+pub use manager::*;
+pub use symbols::*;
+
+use std::path::Path;
+
+use freya::code_editor::{LanguageId, Rope};
+use lsp_types::{Position, Url};
+
+/// Key used to look up the language server settings of a language, e.g. `rust`.
+pub fn language_key(language_id: &LanguageId) -> String {
+    language_id.to_string().to_lowercase()
+}
+
+pub fn path_to_uri(path: &Path) -> Option<Url> {
+    Url::from_file_path(path).ok()
+}
+
+/// Convert an LSP position (UTF-16 columns) into a char index of the rope.
+pub fn position_to_char(rope: &Rope, position: Position) -> usize {
+    let row = (position.line as usize).min(rope.len_lines().saturating_sub(1));
+    let line = rope.line(row);
+    let col = (position.character as usize).min(line.len_utf16_cu());
+    rope.line_to_char(row) + line.utf16_cu_to_char(col)
+}
+
+/// Convert a char index of the rope into an LSP position (UTF-16 columns).
+pub fn char_to_position(rope: &Rope, char_idx: usize) -> Position {
+    let char_idx = char_idx.min(rope.len_chars());
+    let row = rope.char_to_line(char_idx);
+    let line = rope.line(row);
+    let col = line.char_to_utf16_cu(char_idx - rope.line_to_char(row));
+    Position::new(row as u32, col as u32)
+}
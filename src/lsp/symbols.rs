@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use freya::code_editor::Rope;
+use lsp_types::{
+    GotoDefinitionParams, GotoDefinitionResponse, Location, ReferenceContext, ReferenceParams,
+    TextDocumentIdentifier, TextDocumentPositionParams,
+    request::{GotoDefinition, References},
+};
+
+use crate::symbols::{SymbolLocation, SymbolProvider};
+
+use super::{LspManager, char_to_position, path_to_uri, position_to_char};
+
+/// Resolves symbols through the language server of each document.
+pub struct LspSymbolProvider(pub LspManager);
+
+impl LspSymbolProvider {
+    fn position_params(
+        path: &Path,
+        rope: &Rope,
+        cursor: usize,
+    ) -> Result<TextDocumentPositionParams, String> {
+        let uri = path_to_uri(path).ok_or_else(|| format!("Invalid path {path:?}"))?;
+        Ok(TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(uri),
+            char_to_position(rope, cursor),
+        ))
+    }
+}
+
+/// Convert LSP locations, using the rope of the requesting document for its own
+/// UTF-16 columns. Columns of other files are used as they are.
+fn to_symbol_locations(locations: Vec<Location>, path: &Path, rope: &Rope) -> Vec<SymbolLocation> {
+    locations
+        .into_iter()
+        .filter_map(|location| {
+            let target = location.uri.to_file_path().ok()?;
+            let to_line_col = |position: lsp_types::Position| {
+                if target == path {
+                    let char_idx = position_to_char(rope, position);
+                    let line = rope.char_to_line(char_idx);
+                    (line, char_idx - rope.line_to_char(line))
+                } else {
+                    (position.line as usize, position.character as usize)
+                }
+            };
+            Some(SymbolLocation {
+                start: to_line_col(location.range.start),
+                end: to_line_col(location.range.end),
+                path: target,
+            })
+        })
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl SymbolProvider for LspSymbolProvider {
+    fn supports(&self, path: &Path) -> bool {
+        self.0.client_for(path).is_some()
+    }
+
+    async fn definition(
+        &self,
+        path: &Path,
+        rope: &Rope,
+        cursor: usize,
+    ) -> Result<Vec<SymbolLocation>, String> {
+        let client = self.0.client_for(path).ok_or("No language server")?;
+        self.0.did_change(path, rope);
+        let response = client
+            .request::<GotoDefinition>(GotoDefinitionParams {
+                text_document_position_params: Self::position_params(path, rope, cursor)?,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            })
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let locations = match response {
+            Some(GotoDefinitionResponse::Scalar(location)) => vec![location],
+            Some(GotoDefinitionResponse::Array(locations)) => locations,
+            Some(GotoDefinitionResponse::Link(links)) => links
+                .into_iter()
+                .map(|link| Location::new(link.target_uri, link.target_selection_range))
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok(to_symbol_locations(locations, path, rope))
+    }
+
+    async fn references(
+        &self,
+        path: &Path,
+        rope: &Rope,
+        cursor: usize,
+    ) -> Result<Vec<SymbolLocation>, String> {
+        let client = self.0.client_for(path).ok_or("No language server")?;
+        self.0.did_change(path, rope);
+        let locations = client
+            .request::<References>(ReferenceParams {
+                text_document_position: Self::position_params(path, rope, cursor)?,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+                context: ReferenceContext {
+                    include_declaration: true,
+                },
+            })
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(to_symbol_locations(
+            locations.unwrap_or_default(),
+            path,
+            rope,
+        ))
+    }
+}
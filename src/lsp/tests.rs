@@ -0,0 +1,146 @@
+use std::{collections::HashMap, path::Path, time::Duration};
+
+use freya::code_editor::{LanguageId, Rope};
+use futures::StreamExt;
+use futures_channel::mpsc::UnboundedReceiver;
+use lsp_types::{
+    PublishDiagnosticsParams,
+    notification::{Notification, PublishDiagnostics},
+};
+use smol::Timer;
+
+use crate::state::{AppTask, LanguageServerSettings, LspSettings};
+
+use super::{LspManager, path_to_uri};
+
+/// A language server speaking over stdio that logs the method of every message it
+/// receives to the file passed as first argument, and publishes a diagnostic with
+/// the version of every document opened or changed.
+const FAKE_SERVER: &str = r#"
+log="$1"
+send() {
+    printf 'Content-Length: %s\r\n\r\n%s' "${#1}" "$1"
+}
+field() {
+    printf '%s' "$body" | sed -n "s/.*\"$1\":\"\{0,1\}\([^\",}]*\).*/\1/p"
+}
+while :; do
+    length=
+    while IFS= read -r header; do
+        header=$(printf '%s' "$header" | tr -d '\r')
+        [ -z "$header" ] && break
+        case "$header" in
+            Content-Length:*) length=${header#Content-Length: } ;;
+        esac
+    done
+    [ -z "$length" ] && exit 1
+    body=$(dd bs=1 count="$length" 2>/dev/null)
+    method=$(field method)
+    echo "$method" >> "$log"
+    case "$method" in
+        initialize)
+            result="{\"capabilities\":{\"textDocumentSync\":1}}"
+            send "{\"jsonrpc\":\"2.0\",\"id\":$(field id),\"result\":$result}"
+            ;;
+        textDocument/didOpen|textDocument/didChange)
+            version=$(field version)
+            range="{\"start\":{\"line\":0,\"character\":0},\"end\":{\"line\":0,\"character\":2}}"
+            diagnostic="{\"range\":$range,\"message\":\"version $version\"}"
+            params="{\"uri\":\"$(field uri)\",\"version\":$version,\"diagnostics\":[$diagnostic]}"
+            method="textDocument/publishDiagnostics"
+            send "{\"jsonrpc\":\"2.0\",\"method\":\"$method\",\"params\":$params}"
+            ;;
+        shutdown)
+            send "{\"jsonrpc\":\"2.0\",\"id\":$(field id),\"result\":null}"
+            ;;
+        exit)
+            exit 0
+            ;;
+    esac
+done
+"#;
+
+fn fake_server_settings(folder: &Path) -> LspSettings {
+    let script = folder.join("server.sh");
+    std::fs::write(&script, FAKE_SERVER).unwrap();
+    LspSettings {
+        enabled: true,
+        servers: HashMap::from([(
+            "rust".to_string(),
+            LanguageServerSettings {
+                command: "sh".to_string(),
+                args: vec![
+                    script.to_string_lossy().into_owned(),
+                    folder.join("log").to_string_lossy().into_owned(),
+                ],
+            },
+        )]),
+    }
+}
+
+/// Wait for the next diagnostics published by the server.
+fn next_diagnostics(tasks: &mut UnboundedReceiver<AppTask>) -> PublishDiagnosticsParams {
+    smol::block_on(smol::future::or(
+        async {
+            while let Some(task) = tasks.next().await {
+                if let AppTask::LspNotification(notification) = task
+                    && notification.method == PublishDiagnostics::METHOD
+                {
+                    return serde_json::from_value(notification.params).unwrap();
+                }
+            }
+            panic!("The language server disconnected");
+        },
+        async {
+            Timer::after(Duration::from_secs(10)).await;
+            panic!("The language server didn't publish diagnostics");
+        },
+    ))
+}
+
+#[test]
+fn syncs_documents_with_the_server() {
+    let folder = tempfile::tempdir().unwrap();
+    let settings = fake_server_settings(folder.path());
+    let (sender, mut tasks) = futures_channel::mpsc::unbounded::<AppTask>();
+    let lsp = LspManager::new(sender);
+    let path = folder.path().join("main.rs");
+    let uri = path_to_uri(&path).unwrap();
+
+    lsp.did_open(
+        &settings,
+        folder.path(),
+        &path,
+        &LanguageId::Rust,
+        &Rope::from_str("fn main() {}"),
+    );
+    let diagnostics = next_diagnostics(&mut tasks);
+    assert_eq!(diagnostics.uri, uri);
+    assert_eq!(diagnostics.version, Some(0));
+    assert_eq!(diagnostics.diagnostics[0].message, "version 0");
+    let client = lsp.client_for(&path).unwrap();
+    assert!(client.capabilities().is_some());
+
+    // Unchanged text isn't sent again
+    lsp.did_change(&path, &Rope::from_str("fn main() {}"));
+    lsp.did_change(&path, &Rope::from_str("fn main() { }"));
+    let diagnostics = next_diagnostics(&mut tasks);
+    assert_eq!(diagnostics.version, Some(1));
+
+    smol::block_on(lsp.shutdown());
+    assert!(client.is_closed());
+    assert!(lsp.client_for(&path).is_none());
+
+    let log = std::fs::read_to_string(folder.path().join("log")).unwrap();
+    assert_eq!(
+        log.lines().collect::<Vec<_>>(),
+        [
+            "initialize",
+            "initialized",
+            "textDocument/didOpen",
+            "textDocument/didChange",
+            "shutdown",
+            "exit"
+        ]
+    );
+}
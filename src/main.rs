@@ -4,17 +4,26 @@
 )]
 
 mod app;
+mod backup;
 mod components;
+mod diff;
+mod file_watcher;
 mod fs;
+mod git;
 mod global_defaults;
+mod lsp;
+mod session;
 mod settings;
 mod state;
+mod symbols;
+mod system;
 mod theme;
 mod views;
 
 use std::path::PathBuf;
 
 use crate::app::AppView;
+use crate::state::WindowCloseHandle;
 use clap::Parser;
 use freya::prelude::*;
 use freya_performance_plugin::PerformanceOverlayPlugin;
@@ -52,10 +61,19 @@ fn fix_flatpak_cursor_theme() {
 #[derive(Parser, Debug, PartialEq, Clone)]
 #[command(version, about, long_about = None)]
 struct Args {
-    // Open certain folders or files.
+    // Open certain folders or files, files can end with `:line` or `:line:col`.
     #[arg(num_args(0..))]
     paths: Vec<PathBuf>,
 
+    /// Restore the previous session of the given folders.
+    /// Without paths the last session is always restored.
+    #[arg(long)]
+    restore: bool,
+
+    /// Show the changes between two files, without restoring the last session.
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"])]
+    diff: Option<Vec<PathBuf>>,
+
     /// Enable the FPS overlay.
     #[arg(long)]
     fps: bool,
@@ -81,6 +99,8 @@ fn main() {
 
     info!("Starting valin. \n{args:#?}");
 
+    let close_handle = WindowCloseHandle::default();
+
     launch(
         LaunchConfig::default()
             .with_plugin(
@@ -92,9 +112,10 @@ fn main() {
                     .with_visible(args.fps),
             )
             .with_window(
-                WindowConfig::new_app(AppView(args.clone()))
+                WindowConfig::new_app(AppView(args.clone(), close_handle.clone()))
                     .with_size(1100.0, 800.0)
-                    .with_title("Valin"),
+                    .with_title("Valin")
+                    .with_on_close(move |_, _| close_handle.request()),
             ),
     );
 }
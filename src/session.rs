@@ -0,0 +1,333 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{create_dir_all, read_to_string, write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use freya::prelude::{Direction, DockNode, DockPanel};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{
+    settings::home_dir,
+    state::{AppState, Channel, EditorSidePanel, JumpLocation, PanelId, RadioAppState, TabId},
+    views::{
+        file_explorer::file_explorer_ui::{ExplorerItem, FolderState, read_folder_tree},
+        panels::tabs::editor::EditorTab,
+    },
+};
+
+/// How often the session is checked for changes to save.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+enum SplitDirection {
+    Vertical,
+    Horizontal,
+}
+
+impl From<Direction> for SplitDirection {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Vertical => Self::Vertical,
+            Direction::Horizontal => Self::Horizontal,
+        }
+    }
+}
+
+impl From<SplitDirection> for Direction {
+    fn from(direction: SplitDirection) -> Self {
+        match direction {
+            SplitDirection::Vertical => Self::Vertical,
+            SplitDirection::Horizontal => Self::Horizontal,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct SessionTab {
+    #[serde(flatten)]
+    location: JumpLocation,
+    /// Horizontal and vertical scroll of the editor.
+    scroll: (i32, i32),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct SessionPanel {
+    tabs: Vec<SessionTab>,
+    active_tab: Option<usize>,
+    is_focused: bool,
+}
+
+/// A node of the panels tree, with the files opened in each panel instead of its tabs.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+enum SessionNode {
+    Split {
+        direction: SplitDirection,
+        children: Vec<SessionNode>,
+    },
+    Panel(SessionPanel),
+}
+
+impl SessionNode {
+    fn capture(app_state: &AppState, node: &DockNode<TabId, PanelId>) -> Self {
+        match node {
+            DockNode::Split {
+                direction,
+                children,
+            } => Self::Split {
+                direction: (*direction).into(),
+                children: children
+                    .iter()
+                    .map(|child| Self::capture(app_state, child))
+                    .collect(),
+            },
+            DockNode::Panel(panel) => {
+                let tabs = panel
+                    .tabs
+                    .iter()
+                    .filter_map(|tab_id| {
+                        let editor_tab = app_state.tabs.get(tab_id)?.as_text_editor()?;
+                        let tab = SessionTab {
+                            location: editor_tab.jump_location(),
+                            scroll: editor_tab.scroll,
+                        };
+                        Some((*tab_id, tab))
+                    })
+                    .collect::<Vec<_>>();
+                let active_tab = tabs
+                    .iter()
+                    .position(|(tab_id, _)| Some(*tab_id) == panel.active_tab_id);
+                Self::Panel(SessionPanel {
+                    tabs: tabs.into_iter().map(|(_, tab)| tab).collect(),
+                    active_tab,
+                    is_focused: app_state.focused_panel == Some(panel.panel_id),
+                })
+            }
+        }
+    }
+
+    /// A tree with new empty panels, each of them paired with the one it stands for.
+    fn build(self, panels: &mut Vec<(PanelId, SessionPanel)>) -> DockNode<TabId, PanelId> {
+        match self {
+            Self::Split {
+                direction,
+                children,
+            } => DockNode::Split {
+                direction: direction.into(),
+                children: children
+                    .into_iter()
+                    .map(|child| child.build(panels))
+                    .collect(),
+            },
+            Self::Panel(panel) => {
+                let panel_id = PanelId::new();
+                panels.push((panel_id, panel));
+                DockNode::Panel(DockPanel::new(panel_id, vec![]))
+            }
+        }
+    }
+
+    fn panels(&self) -> Vec<&SessionPanel> {
+        match self {
+            Self::Split { children, .. } => children.iter().flat_map(Self::panels).collect(),
+            Self::Panel(panel) => vec![panel],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct SessionFolder {
+    path: PathBuf,
+    /// Expanded folders, the root included.
+    opened: Vec<PathBuf>,
+}
+
+/// The panels, tabs and folders of a workspace.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Session {
+    panels: Option<SessionNode>,
+    folders: Vec<SessionFolder>,
+    side_panel: Option<EditorSidePanel>,
+}
+
+/// Sessions of every workspace, by their folders.
+#[derive(Serialize, Deserialize, Default)]
+struct Sessions {
+    last: Option<String>,
+    workspaces: HashMap<String, Session>,
+}
+
+impl Session {
+    pub fn capture(app_state: &AppState) -> Self {
+        let folders = app_state
+            .file_explorer
+            .folders
+            .iter()
+            .map(|folder| SessionFolder {
+                path: folder.path().clone(),
+                opened: folder.opened_folders(),
+            })
+            .collect();
+
+        Self {
+            panels: app_state
+                .panel_tree
+                .as_ref()
+                .map(|tree| SessionNode::capture(app_state, tree)),
+            folders,
+            side_panel: app_state.side_panel,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.folders.is_empty()
+            && self
+                .panels
+                .iter()
+                .flat_map(SessionNode::panels)
+                .all(|panel| panel.tabs.is_empty())
+    }
+}
+
+fn sessions_path() -> Option<PathBuf> {
+    Some(home_dir()?.join(".valin").join("sessions.json"))
+}
+
+fn workspace_key<'a>(folders: impl Iterator<Item = &'a Path>) -> String {
+    let mut folders = folders
+        .map(|folder| folder.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    folders.sort();
+    folders.join("\n")
+}
+
+fn read_sessions() -> Sessions {
+    sessions_path()
+        .and_then(|path| read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The session of the workspace with these folders, or else the last one.
+pub fn load_session(folders: Option<&[PathBuf]>) -> Option<Session> {
+    let mut sessions = read_sessions();
+    let key = match folders {
+        Some(folders) => workspace_key(folders.iter().map(PathBuf::as_path)),
+        None => sessions.last.clone()?,
+    };
+    sessions
+        .workspaces
+        .remove(&key)
+        .filter(|session| !session.is_empty())
+}
+
+/// Save the session of a workspace, unless no folder is open, as there would be no
+/// workspace to restore it for.
+pub fn save_session(session: &Session) -> Option<()> {
+    if session.folders.is_empty() {
+        return Some(());
+    }
+    let path = sessions_path()?;
+    let mut sessions = read_sessions();
+    let key = workspace_key(session.folders.iter().map(|folder| folder.path.as_path()));
+    sessions.last = Some(key.clone());
+    sessions.workspaces.insert(key, session.clone());
+    create_dir_all(path.parent()?).ok()?;
+    write(&path, serde_json::to_string_pretty(&sessions).ok()?).ok()
+}
+
+/// Rebuild the layout and reopen the tabs and folders of a session.
+pub async fn restore_session(mut radio_app_state: RadioAppState, session: Session) {
+    {
+        let transport = radio_app_state.read().default_transport.clone();
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+
+        if let Some(node) = session.panels {
+            let mut panels = Vec::new();
+            app_state.panel_tree = Some(node.build(&mut panels));
+            app_state.focused_panel = panels.first().map(|(panel_id, _)| *panel_id);
+
+            let mut focused_panel = None;
+            for (panel_id, panel) in panels {
+                app_state.focused_panel = Some(panel_id);
+                let mut active_tab = None;
+                for (index, SessionTab { location, scroll }) in panel.tabs.into_iter().enumerate() {
+                    let JumpLocation { path, line, col } = location;
+                    if !path.is_file() {
+                        continue;
+                    }
+                    let content_id = path.to_string_lossy().into_owned();
+                    EditorTab::open_location(
+                        radio_app_state,
+                        &mut app_state,
+                        path,
+                        transport.as_read(),
+                        Some((line, col)),
+                    );
+                    let Some(tab_id) = app_state.find_tab_by_content_id(&content_id) else {
+                        continue;
+                    };
+                    if let Some(editor_tab) = app_state
+                        .tabs
+                        .get_mut(&tab_id)
+                        .and_then(|tab| tab.as_text_editor_mut())
+                    {
+                        editor_tab.scroll = scroll;
+                    }
+                    if panel.active_tab == Some(index) {
+                        active_tab = Some(tab_id);
+                    }
+                }
+                if active_tab.is_some() {
+                    app_state.focus_tab(panel_id, active_tab);
+                }
+                if panel.is_focused {
+                    focused_panel = Some((panel_id, active_tab));
+                }
+            }
+
+            if let Some((panel_id, active_tab)) = focused_panel {
+                app_state.focused_panel = Some(panel_id);
+                app_state.focus_tab(panel_id, active_tab);
+            }
+        }
+        app_state.side_panel = session.side_panel;
+    }
+
+    let transport = radio_app_state.read().default_transport.clone();
+    for SessionFolder { path, opened } in session.folders {
+        let opened = opened.into_iter().collect::<HashSet<_>>();
+        match read_folder_tree(&path, &opened, &transport).await {
+            Ok(items) => {
+                radio_app_state
+                    .write_channel(Channel::FileExplorer)
+                    .file_explorer
+                    .open_folder(ExplorerItem::Folder {
+                        path,
+                        state: FolderState::Opened(items),
+                        is_ignored: false,
+                    });
+            }
+            Err(err) => warn!("Failed to restore folder {path:?}: {err}"),
+        }
+    }
+
+    info!("Restored the previous session");
+}
+
+/// Save the session every time it changes.
+pub async fn autosave_session(radio_app_state: RadioAppState) {
+    let mut saved = None;
+    loop {
+        smol::Timer::after(AUTOSAVE_INTERVAL).await;
+        let session = Session::capture(&radio_app_state.read());
+        if saved.as_ref() != Some(&session) {
+            if save_session(&session).is_none() {
+                warn!("Failed to save the session.");
+            }
+            saved = Some(session);
+        }
+    }
+}
@@ -6,39 +6,45 @@ use std::{
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
 use tracing::info;
 
-use crate::state::{AppSettings, Channel, RadioAppState};
+use crate::{
+    state::{AppSettings, Channel, RadioAppState},
+    views::notifications::notifications_state::Notification,
+};
 
-pub fn settings_path() -> Option<PathBuf> {
+pub fn home_dir() -> Option<PathBuf> {
     // Inside Flatpak, home::home_dir() returns the sandboxed home
-    // (~/.var/app/<id>/). Use the real $HOME so the settings file
-    // stays at ~/valin.toml on the host (requires --filesystem=host).
+    // (~/.var/app/<id>/). Use the real $HOME so the files valin
+    // writes stay on the host (requires --filesystem=host).
     if std::env::var("FLATPAK_ID").is_ok() {
         let home = std::env::var("HOME").ok()?;
-        return Some(PathBuf::from(home).join("valin.toml"));
+        return Some(PathBuf::from(home));
     }
 
-    let home_dir = home::home_dir()?;
+    home::home_dir()
+}
 
-    let settings_path = home_dir.join("valin.toml");
+pub fn settings_path() -> Option<PathBuf> {
+    let settings_path = home_dir()?.join("valin.toml");
 
     Some(settings_path)
 }
 
-pub fn load_settings() -> Option<AppSettings> {
-    let settings_path = settings_path()?;
+pub fn load_settings() -> Result<AppSettings, String> {
+    let settings_path = settings_path().ok_or("Couldn't find the home directory")?;
 
     // Create if it doesn't exist
     if std::fs::metadata(&settings_path).is_err() {
         let default_settings_content = toml::to_string(&AppSettings::default()).unwrap();
-        write(&settings_path, default_settings_content).ok()?;
+        write(&settings_path, default_settings_content)
+            .map_err(|err| format!("Couldn't create {}: {err}", settings_path.display()))?;
         info!("Settings file didn't exist, so one was created.");
     }
 
-    let settings_content = read_to_string(&settings_path).ok()?;
-
-    let settings: AppSettings = toml::from_str(&settings_content).ok()?;
+    let settings_content = read_to_string(&settings_path)
+        .map_err(|err| format!("Couldn't read {}: {err}", settings_path.display()))?;
 
-    Some(settings)
+    toml::from_str(&settings_content)
+        .map_err(|err| format!("Invalid {}: {err}", settings_path.display()))
 }
 
 pub async fn watch_settings(mut radio_app_state: RadioAppState) -> Option<()> {
@@ -63,12 +69,18 @@ pub async fn watch_settings(mut radio_app_state: RadioAppState) -> Option<()> {
         .ok()?;
 
     while rx.recv().await.is_ok() {
-        let settings = load_settings();
-        if let Some(settings) = settings {
-            let mut app_state = radio_app_state.write_channel(Channel::Settings);
-            app_state.set_settings(settings);
-        } else {
-            info!("Failed to update in-memory settings with the newest changes.")
+        match load_settings() {
+            Ok(settings) => {
+                let mut app_state = radio_app_state.write_channel(Channel::Settings);
+                app_state.set_settings(settings);
+            }
+            Err(err) => {
+                radio_app_state
+                    .write_channel(Channel::Notifications)
+                    .notify(Notification::error(format!(
+                        "Failed to reload the settings: {err}"
+                    )));
+            }
         }
     }
 
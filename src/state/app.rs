@@ -1,13 +1,34 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use freya::prelude::*;
 use freya::radio::{Radio, RadioChannel};
 use futures_channel::mpsc::UnboundedSender;
-use tracing::info;
-
-use crate::{fs::FSTransport, views::file_explorer::file_explorer_state::FileExplorerState};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use crate::{
+    backup::{RecoveredFile, load_recovered_files},
+    fs::FSTransport,
+    git::GitState,
+    lsp::{LspManager, LspNotification, LspSymbolProvider},
+    session::{Session, save_session},
+    settings::load_settings,
+    symbols::SymbolProviders,
+    views::{
+        context_menu::context_menu_state::{ContextMenuEntry, ContextMenuState},
+        file_explorer::file_explorer_state::FileExplorerState,
+        notifications::notifications_state::{Notification, NotificationsState},
+        problems::problems_state::{ProblemsState, Severity},
+        references::references_state::ReferencesState,
+        source_control::source_control_state::SourceControlState,
+        workspace_search::workspace_search_state::WorkspaceSearchState,
+    },
+};
 
-use super::{AppSettings, EditorView, FileIcons, PanelId, PanelTab, TabId, TabSwitcherState};
+use super::{
+    AppSettings, CloseRequest, EditorView, FileIcons, JumpList, PanelId, PanelTab, TabId,
+    TabSwitcherState,
+};
 
 pub type RadioAppState = Radio<AppState, Channel>;
 
@@ -43,6 +64,22 @@ pub enum Channel {
     Settings,
     // Only affects the file explorer
     FileExplorer,
+    /// Only affects the workspace search
+    WorkspaceSearch,
+    /// Affects the problems panel and the tabs showing them
+    Problems,
+    /// Only affects the references panel
+    References,
+    /// Only affects whoever depends on the pressed modifier keys
+    Modifiers,
+    /// Only affects the jump list
+    Navigation,
+    /// Only affects the notifications
+    Notifications,
+    /// Affects the git status shown in the file explorer, the tabs and the source control panel
+    Git,
+    /// Only affects the source control panel
+    SourceControl,
 }
 
 impl RadioChannel<AppState> for Channel {
@@ -64,10 +101,17 @@ impl RadioChannel<AppState> for Channel {
                     vec![self]
                 }
             }
-            Self::Settings => vec![self, Self::Global],
+            Self::Settings => vec![self, Self::Global, Self::FileExplorer],
+            Self::Problems => std::iter::once(self)
+                .chain(Self::AllTabs.derive_channel(app_state))
+                .collect(),
             Self::Global => std::iter::once(self)
                 .chain(Self::AllTabs.derive_channel(app_state))
                 .collect(),
+            Self::Git => [self, Self::FileExplorer, Self::SourceControl]
+                .into_iter()
+                .chain(Self::AllTabs.derive_channel(app_state))
+                .collect(),
             _ => vec![self],
         }
     }
@@ -79,10 +123,15 @@ impl Channel {
     }
 }
 
-#[derive(Clone, Default, PartialEq, Copy)]
+#[derive(Clone, Default, PartialEq, Copy, Debug, Serialize, Deserialize)]
 pub enum EditorSidePanel {
     #[default]
     FileExplorer,
+    Search,
+    Problems,
+    References,
+    Notifications,
+    SourceControl,
 }
 
 pub struct AppState {
@@ -95,12 +144,29 @@ pub struct AppState {
     pub tabs: HashMap<TabId, Box<dyn PanelTab>>,
     pub tab_history: Vec<TabId>,
     pub tab_switcher: Option<TabSwitcherState>,
+    /// Waiting for the user to confirm closing unsaved changes.
+    pub close_request: Option<CloseRequest>,
+    pub context_menu: Option<ContextMenuState>,
+    /// File to compare with the one picked next in the file search.
+    pub compare_with: Option<std::path::PathBuf>,
+    pub jump_list: JumpList,
+    /// Unsaved buffers left by a previous run.
+    pub recovered_files: Vec<RecoveredFile>,
     pub settings: AppSettings,
     pub side_panel: Option<EditorSidePanel>,
     pub default_transport: FSTransport,
 
     pub file_explorer: FileExplorerState,
     pub file_icons: FileIcons,
+    pub workspace_search: WorkspaceSearchState,
+    pub problems: ProblemsState,
+    pub references: ReferencesState,
+    pub notifications: NotificationsState,
+    pub git: GitState,
+    pub source_control: SourceControlState,
+    pub lsp: LspManager,
+    pub symbol_providers: SymbolProviders,
+    pub is_ctrl_pressed: bool,
 
     pub task_sender: UnboundedSender<AppTask>,
 }
@@ -111,14 +177,20 @@ pub enum AppTask {
         path: std::path::PathBuf,
         panel_id: PanelId,
     },
+    /// A language server sent a notification.
+    LspNotification(LspNotification),
+    /// The window is about to close.
+    CloseWindow,
 }
 
 /// What a drag can carry onto the docking area: an existing tab, or a file path
-/// (dragged in from the file explorer) to open as a new tab.
+/// (dragged in from the file explorer) to open as a new tab. Folders can only be
+/// dropped onto other folders of the file explorer.
 #[derive(Clone, PartialEq)]
 pub enum DropValue {
     Tab(TabId),
     File(std::path::PathBuf),
+    Folder(std::path::PathBuf),
 }
 
 impl From<TabId> for DropValue {
@@ -130,7 +202,12 @@ impl From<TabId> for DropValue {
 impl AppState {
     pub fn new(default_transport: FSTransport, task_sender: UnboundedSender<AppTask>) -> Self {
         let panel_id = PanelId::new();
-        Self {
+        let lsp = LspManager::new(task_sender.clone());
+        let (settings, settings_error) = match load_settings() {
+            Ok(settings) => (settings, None),
+            Err(err) => (AppSettings::default(), Some(err)),
+        };
+        let mut app_state = Self {
             previous_focused_view: None,
             focused_view: EditorView::default(),
             focused_panel: Some(panel_id),
@@ -138,13 +215,33 @@ impl AppState {
             tabs: HashMap::new(),
             tab_history: Vec::new(),
             tab_switcher: None,
-            settings: AppSettings::load(),
+            close_request: None,
+            context_menu: None,
+            compare_with: None,
+            jump_list: JumpList::default(),
+            recovered_files: load_recovered_files(),
+            settings,
             side_panel: Some(EditorSidePanel::default()),
             default_transport,
             file_explorer: FileExplorerState::new(),
             file_icons: FileIcons::new(),
+            workspace_search: WorkspaceSearchState::new(),
+            problems: ProblemsState::new(),
+            references: ReferencesState::default(),
+            notifications: NotificationsState::new(),
+            git: GitState::default(),
+            source_control: SourceControlState::default(),
+            lsp: lsp.clone(),
+            symbol_providers: vec![Arc::new(LspSymbolProvider(lsp))],
+            is_ctrl_pressed: false,
             task_sender,
+        };
+        if let Some(err) = settings_error {
+            app_state.notify(Notification::error(format!(
+                "Failed to load the settings, using the defaults: {err}"
+            )));
         }
+        app_state
     }
 
     pub fn toggle_side_panel(&mut self, side_panel: EditorSidePanel) {
@@ -153,6 +250,23 @@ impl AppState {
         } else {
             Some(side_panel)
         };
+        if self.side_panel == Some(EditorSidePanel::Notifications) {
+            self.notifications.unread = 0;
+        }
+    }
+
+    /// Show a notification to the user, returning its id.
+    /// Write through [Channel::Notifications] so the toasts update.
+    pub fn notify(&mut self, notification: Notification) -> usize {
+        match notification.severity {
+            Severity::Error => error!("{}", notification.message),
+            Severity::Warning => warn!("{}", notification.message),
+            _ => info!("{}", notification.message),
+        }
+        if self.side_panel != Some(EditorSidePanel::Notifications) {
+            self.notifications.unread += 1;
+        }
+        self.notifications.push(notification)
     }
 
     pub fn set_settings(&mut self, settings: AppSettings) {
@@ -184,6 +298,7 @@ impl AppState {
                 }
             }
             EditorView::FilesExplorer => self.file_explorer.focus(),
+            EditorView::Problems => self.problems.focus(),
             _ => {}
         }
     }
@@ -200,6 +315,15 @@ impl AppState {
         if self.focused_view == EditorView::TabSwitcher {
             self.tab_switcher = None;
         }
+        if self.focused_view == EditorView::ConfirmClose {
+            self.close_request = None;
+        }
+        if self.focused_view == EditorView::FilePrompt {
+            self.file_explorer.prompt = None;
+        }
+        if self.focused_view == EditorView::FileSearch {
+            self.compare_with = None;
+        }
         if let Some(previous_focused_view) = self.previous_focused_view {
             self.focused_view = previous_focused_view;
             self.previous_focused_view = None;
@@ -207,6 +331,18 @@ impl AppState {
         self.focus_view_inner(self.focused_view);
     }
 
+    /// Close the popup that is focused, like the Commander running a command, before
+    /// focusing another view, so the popup doesn't steal the focus back once it closes.
+    pub fn close_popup_before_focus(&mut self) {
+        if self.focused_view.is_popup() {
+            self.focus_previous_view();
+        }
+    }
+
+    pub fn open_context_menu(&mut self, position: CursorPoint, entries: Vec<ContextMenuEntry>) {
+        self.context_menu = Some(ContextMenuState { position, entries });
+    }
+
     pub fn font_size(&self) -> f32 {
         self.settings.editor.font_size
     }
@@ -224,7 +360,7 @@ impl AppState {
     }
 
     /// Find an open tab by its content id.
-    fn find_tab_by_content_id(&self, content_id: &str) -> Option<TabId> {
+    pub fn find_tab_by_content_id(&self, content_id: &str) -> Option<TabId> {
         self.tabs
             .iter()
             .find_map(|(id, tab)| (tab.get_data().content_id == content_id).then_some(*id))
@@ -377,13 +513,144 @@ impl AppState {
             .and_then(|pid| self.panel_tree.as_ref()?.panel(&pid))
             .and_then(|panel| panel.active_tab_id);
         if let Some(tab_id) = active_tab {
+            self.request_close(CloseRequest::Tab(tab_id));
+        }
+    }
+
+    /// Close the tabs of the focused panel without unsaved changes.
+    pub fn close_saved_tabs(&mut self) {
+        let saved_tabs = self
+            .focused_panel
+            .and_then(|pid| self.panel_tree.as_ref()?.panel(&pid))
+            .map(|panel| panel.tabs.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|tab_id| {
+                self.tabs
+                    .get(tab_id)
+                    .is_some_and(|tab| !tab.get_data().edited)
+            })
+            .collect::<Vec<_>>();
+        for tab_id in saved_tabs {
             self.close_tab(tab_id);
         }
     }
 
+    /// Move the active tab of the focused panel to a new panel on `side`.
+    pub fn split_active_tab(&mut self, side: Side) {
+        let Some(panel_id) = self.focused_panel else {
+            return;
+        };
+        let active_tab = self
+            .panel_tree
+            .as_ref()
+            .and_then(|tree| tree.panel(&panel_id))
+            .and_then(|panel| panel.active_tab_id);
+        match active_tab {
+            Some(tab_id) => {
+                self.move_tab(tab_id, DropTarget::Split { panel_id, side });
+            }
+            None => {
+                self.split_panel_side(panel_id, side);
+            }
+        }
+    }
+
     pub fn close_active_panel(&mut self) {
         if let Some(panel_id) = self.focused_panel {
-            self.close_panel(panel_id);
+            self.request_close(CloseRequest::Panel(panel_id));
+        }
+    }
+
+    /// Tabs that closing would close.
+    fn closing_tabs(&self, request: CloseRequest) -> Vec<TabId> {
+        match request {
+            CloseRequest::Tab(tab_id) => vec![tab_id],
+            CloseRequest::OtherTabs(tab_id) | CloseRequest::TabsToTheRight(tab_id) => {
+                let Some((panel_id, tab_pos)) = self
+                    .panel_tree
+                    .as_ref()
+                    .and_then(|tree| tree.find_tab(&tab_id))
+                else {
+                    return Vec::new();
+                };
+                let tabs = self
+                    .panel_tree
+                    .as_ref()
+                    .and_then(|tree| tree.panel(&panel_id))
+                    .map(|panel| panel.tabs.clone())
+                    .unwrap_or_default();
+                if matches!(request, CloseRequest::OtherTabs(_)) {
+                    tabs.into_iter().filter(|tab| *tab != tab_id).collect()
+                } else {
+                    tabs.into_iter().skip(tab_pos + 1).collect()
+                }
+            }
+            CloseRequest::Panel(panel_id) => self
+                .panel_tree
+                .as_ref()
+                .and_then(|tree| tree.panel(&panel_id))
+                .map(|panel| panel.tabs.clone())
+                .unwrap_or_default(),
+            CloseRequest::Window => self
+                .panels_in_order()
+                .into_iter()
+                .filter_map(|panel_id| self.panel_tree.as_ref()?.panel(&panel_id))
+                .flat_map(|panel| panel.tabs.clone())
+                .collect(),
+        }
+    }
+
+    /// Tabs with unsaved changes that closing would lose.
+    pub fn edited_tabs(&self, request: CloseRequest) -> Vec<TabId> {
+        self.closing_tabs(request)
+            .into_iter()
+            .filter(|tab_id| {
+                self.tabs
+                    .get(tab_id)
+                    .is_some_and(|tab| tab.get_data().edited)
+            })
+            .collect()
+    }
+
+    /// Close, asking the user first if there are unsaved changes.
+    pub fn request_close(&mut self, request: CloseRequest) {
+        // The last panel is never closed
+        if matches!(request, CloseRequest::Panel(_)) && self.panels_in_order().len() <= 1 {
+            return;
+        }
+        if self.edited_tabs(request).is_empty() {
+            self.close(request);
+        } else {
+            self.close_request = Some(request);
+            self.focus_view(EditorView::ConfirmClose);
+        }
+    }
+
+    /// Close without asking.
+    pub fn close(&mut self, request: CloseRequest) {
+        match request {
+            CloseRequest::Tab(tab_id) => self.close_tab(tab_id),
+            CloseRequest::OtherTabs(_) | CloseRequest::TabsToTheRight(_) => {
+                for tab_id in self.closing_tabs(request) {
+                    self.close_tab(tab_id);
+                }
+            }
+            CloseRequest::Panel(panel_id) => self.close_panel(panel_id),
+            // The window close hook already kept the window open, so exit the event loop
+            // instead, once the language servers are shut down
+            CloseRequest::Window => {
+                info!("Closing valin");
+                if save_session(&Session::capture(self)).is_none() {
+                    warn!("Failed to save the session.");
+                }
+                let lsp = self.lsp.clone();
+                let platform = Platform::get();
+                spawn(async move {
+                    lsp.shutdown().await;
+                    drop(platform.post_callback(|_, renderer| renderer.exit()));
+                });
+            }
         }
     }
 
@@ -619,6 +886,7 @@ impl DockingModel for AppState {
         match value {
             DropValue::Tab(tab) => self.move_tab(tab, target),
             DropValue::File(path) => self.open_file_at(path, target),
+            DropValue::Folder(_) => false,
         }
     }
 
@@ -0,0 +1,45 @@
+use std::sync::{Arc, Mutex};
+
+use freya::prelude::CloseDecision;
+use futures_channel::mpsc::UnboundedSender;
+
+use super::{AppTask, PanelId, TabId};
+
+/// Something to close once the user decides what to do with its unsaved changes.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CloseRequest {
+    Tab(TabId),
+    /// Every tab of the panel of this one, except itself.
+    OtherTabs(TabId),
+    /// The tabs after this one in its panel.
+    TabsToTheRight(TabId),
+    Panel(PanelId),
+    Window,
+}
+
+/// Lets the window close hook, which runs outside of the app, ask the app to
+/// confirm the close first.
+#[derive(Clone, Default)]
+pub struct WindowCloseHandle(Arc<Mutex<Option<UnboundedSender<AppTask>>>>);
+
+impl PartialEq for WindowCloseHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl WindowCloseHandle {
+    pub fn connect(&self, task_sender: UnboundedSender<AppTask>) {
+        *self.0.lock().unwrap() = Some(task_sender);
+    }
+
+    /// Close right away only if the app isn't running yet, otherwise leave it to the app.
+    pub fn request(&self) -> CloseDecision {
+        match self.0.lock().unwrap().as_ref() {
+            Some(task_sender) if task_sender.unbounded_send(AppTask::CloseWindow).is_ok() => {
+                CloseDecision::KeepOpen
+            }
+            _ => CloseDecision::Close,
+        }
+    }
+}
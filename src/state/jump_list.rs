@@ -0,0 +1,86 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Most locations remembered in each direction.
+const MAX_JUMPS: usize = 100;
+
+/// A cursor position in a file, kept by path so closed files can be reopened.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct JumpLocation {
+    pub path: PathBuf,
+    /// 0-based line.
+    pub line: usize,
+    /// 0-based column, in chars.
+    pub col: usize,
+}
+
+impl JumpLocation {
+    /// Whether both are on the same line, columns aside.
+    fn is_near(&self, other: &Self) -> bool {
+        self.path == other.path && self.line == other.line
+    }
+}
+
+/// Cursor positions the user jumped away from, to go back and forward between them.
+#[derive(Default)]
+pub struct JumpList {
+    back: Vec<JumpLocation>,
+    forward: Vec<JumpLocation>,
+}
+
+impl JumpList {
+    /// Record the location a jump started from, forgetting the forward history.
+    pub fn push(&mut self, from: JumpLocation) {
+        self.forward.clear();
+        if self.back.last().is_some_and(|last| last.is_near(&from)) {
+            return;
+        }
+        self.back.push(from);
+        if self.back.len() > MAX_JUMPS {
+            self.back.remove(0);
+        }
+    }
+
+    /// Follow the files moved to other paths.
+    pub fn rename(&mut self, moved: impl Fn(&Path) -> Option<PathBuf>) {
+        for location in self.back.iter_mut().chain(&mut self.forward) {
+            if let Some(path) = moved(&location.path) {
+                location.path = path;
+            }
+        }
+    }
+
+    /// The previous location, remembering `current` to come forward again.
+    pub fn back(&mut self, current: Option<JumpLocation>) -> Option<JumpLocation> {
+        Self::step(&mut self.back, &mut self.forward, current)
+    }
+
+    /// The next location, remembering `current` to go back again.
+    pub fn forward(&mut self, current: Option<JumpLocation>) -> Option<JumpLocation> {
+        Self::step(&mut self.forward, &mut self.back, current)
+    }
+
+    fn step(
+        from: &mut Vec<JumpLocation>,
+        to: &mut Vec<JumpLocation>,
+        current: Option<JumpLocation>,
+    ) -> Option<JumpLocation> {
+        let mut target = from.pop()?;
+        // Going to where the cursor already is wouldn't do anything
+        if let Some(current) = &current
+            && target.is_near(current)
+        {
+            let Some(next) = from.pop() else {
+                // Nowhere else to go, keep it for later
+                from.push(target);
+                return None;
+            };
+            target = next;
+        }
+        if let Some(current) = current {
+            to.push(current);
+        }
+        Some(target)
+    }
+}
@@ -1,14 +1,18 @@
 mod app;
+mod close_request;
 mod commands;
 pub mod file_icons;
+mod jump_list;
 mod keyboard_shortcuts;
 mod panels_tabs;
 mod settings;
 mod views;
 
 pub use app::*;
+pub use close_request::*;
 pub use commands::*;
 pub use file_icons::FileIcons;
+pub use jump_list::{JumpList, JumpLocation};
 pub use keyboard_shortcuts::*;
 pub use panels_tabs::{PanelId, PanelTab, PanelTabData, TabId, TabProps, TabSwitcherState};
 pub use settings::*;
@@ -1,7 +1,8 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize, Serializer};
-use tracing::info;
 
-use crate::settings::load_settings;
+use crate::lsp::LanguageServerCommand;
 
 fn human_number_serializer<S>(value: &f32, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -26,16 +27,101 @@ impl Default for EditorSettings {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
-pub struct AppSettings {
-    pub(crate) editor: EditorSettings,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LanguageServerSettings {
+    pub(crate) command: String,
+    #[serde(default)]
+    pub(crate) args: Vec<String>,
+}
+
+/// Language servers to launch, by language (e.g. `rust`).
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LspSettings {
+    #[serde(default = "default_true")]
+    pub(crate) enabled: bool,
+    #[serde(default)]
+    pub(crate) servers: HashMap<String, LanguageServerSettings>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for LspSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            servers: HashMap::from([(
+                "rust".to_string(),
+                LanguageServerSettings {
+                    command: "rust-analyzer".to_string(),
+                    args: Vec::new(),
+                },
+            )]),
+        }
+    }
+}
+
+impl LspSettings {
+    /// How to launch the server of a language, if there is one configured.
+    pub fn command_for(&self, language: &str) -> Option<LanguageServerCommand> {
+        if !self.enabled {
+            return None;
+        }
+        self.servers
+            .get(language)
+            .map(|server| LanguageServerCommand {
+                command: server.command.clone(),
+                args: server.args.clone(),
+            })
+    }
+}
+
+fn default_exclude() -> Vec<String> {
+    vec![".git".to_string(), ".DS_Store".to_string()]
+}
+
+/// Which files the explorer lists.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExplorerSettings {
+    /// List the files ignored by git, like `target/`.
+    #[serde(default)]
+    pub(crate) show_ignored: bool,
+    /// Dim the ignored files when they are listed.
+    #[serde(default = "default_true")]
+    pub(crate) dim_ignored: bool,
+    /// List the files whose name starts with a dot.
+    #[serde(default = "default_true")]
+    pub(crate) show_hidden: bool,
+    /// List the files matched by `exclude` anyway.
+    #[serde(default)]
+    pub(crate) show_excluded: bool,
+    /// Gitignore-style globs of the files to leave out, like `*.log`.
+    #[serde(default = "default_exclude")]
+    pub(crate) exclude: Vec<String>,
+    /// Reveal the file of the active tab whenever it changes.
+    #[serde(default)]
+    pub(crate) follow_active_file: bool,
 }
 
-impl AppSettings {
-    pub fn load() -> Self {
-        load_settings().unwrap_or_else(|| {
-            info!("Failed to load settings, using defaults.");
-            Self::default()
-        })
+impl Default for ExplorerSettings {
+    fn default() -> Self {
+        Self {
+            show_ignored: false,
+            dim_ignored: true,
+            show_hidden: true,
+            show_excluded: false,
+            exclude: default_exclude(),
+            follow_active_file: false,
+        }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AppSettings {
+    pub(crate) editor: EditorSettings,
+    #[serde(default)]
+    pub(crate) lsp: LspSettings,
+    #[serde(default)]
+    pub(crate) explorer: ExplorerSettings,
+}
@@ -8,11 +8,23 @@ pub enum EditorView {
     Commander,
     FileSearch,
     TabSwitcher,
+    Problems,
+    GoToLine,
+    ConfirmClose,
+    FilePrompt,
 }
 
 impl EditorView {
     pub fn is_popup(&self) -> bool {
-        matches!(self, Self::Commander | Self::FileSearch | Self::TabSwitcher)
+        matches!(
+            self,
+            Self::Commander
+                | Self::FileSearch
+                | Self::TabSwitcher
+                | Self::GoToLine
+                | Self::ConfirmClose
+                | Self::FilePrompt
+        )
     }
 }
 
@@ -24,6 +36,10 @@ impl Display for EditorView {
             Self::Commander => f.write_str("Commander"),
             Self::FileSearch => f.write_str("File Search"),
             Self::TabSwitcher => f.write_str("Tab Switcher"),
+            Self::Problems => f.write_str("Problems"),
+            Self::GoToLine => f.write_str("Go to Line"),
+            Self::ConfirmClose => f.write_str("Confirm Close"),
+            Self::FilePrompt => f.write_str("File Prompt"),
         }
     }
 }
@@ -0,0 +1,42 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use freya::code_editor::Rope;
+
+pub type SymbolProviders = Vec<Arc<dyn SymbolProvider>>;
+
+/// A range of a file a symbol resolved to.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SymbolLocation {
+    pub path: PathBuf,
+    /// 0-based `(line, column)`, in chars.
+    pub start: (usize, usize),
+    /// 0-based `(line, column)`, in chars.
+    pub end: (usize, usize),
+}
+
+/// Something that understands the symbols of some files, like a language server
+/// or a tags file.
+#[async_trait::async_trait]
+pub trait SymbolProvider {
+    /// Whether it can resolve the symbols of a file.
+    fn supports(&self, path: &Path) -> bool;
+
+    /// Where the symbol at a char index of a document is defined.
+    async fn definition(
+        &self,
+        path: &Path,
+        rope: &Rope,
+        cursor: usize,
+    ) -> Result<Vec<SymbolLocation>, String>;
+
+    /// Everywhere the symbol at a char index of a document is used, its declaration included.
+    async fn references(
+        &self,
+        path: &Path,
+        rope: &Rope,
+        cursor: usize,
+    ) -> Result<Vec<SymbolLocation>, String>;
+}
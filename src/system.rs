@@ -0,0 +1,66 @@
+use std::{io, path::Path, process::Command};
+
+use freya::prelude::Clipboard;
+use tracing::warn;
+
+/// Put some text in the system clipboard.
+pub fn copy_to_clipboard(text: String) {
+    if let Err(err) = Clipboard::set(text) {
+        warn!("Failed to copy to the clipboard: {err:?}");
+    }
+}
+
+/// Text in the system clipboard, if any.
+pub fn read_clipboard() -> Option<String> {
+    match Clipboard::get() {
+        Ok(text) => Some(text),
+        Err(err) => {
+            warn!("Failed to read the clipboard: {err:?}");
+            None
+        }
+    }
+}
+
+/// Start a program without waiting for it, but still reaping it once it exits.
+fn launch(command: &mut Command) -> io::Result<()> {
+    let mut child = command.spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Show a file or a folder in the file manager of the system.
+pub fn reveal_in_file_manager(path: &Path) -> io::Result<()> {
+    if cfg!(target_os = "macos") {
+        launch(Command::new("open").arg("-R").arg(path))?;
+    } else if cfg!(target_os = "windows") {
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path);
+        launch(Command::new("explorer").arg(select))?;
+    } else {
+        // Not every file manager can select an item, so open its folder instead
+        let folder = if path.is_dir() {
+            path
+        } else {
+            path.parent().unwrap_or(path)
+        };
+        launch(Command::new("xdg-open").arg(folder))?;
+    }
+    Ok(())
+}
+
+/// Open a terminal of the system in a folder. On Linux `$TERMINAL` is tried first.
+pub fn open_terminal(folder: &Path) -> io::Result<()> {
+    if cfg!(target_os = "macos") {
+        launch(Command::new("open").args(["-a", "Terminal"]).arg(folder))?;
+    } else if cfg!(target_os = "windows") {
+        launch(
+            Command::new("cmd")
+                .args(["/C", "start", "cmd"])
+                .current_dir(folder),
+        )?;
+    } else {
+        let terminal = std::env::var("TERMINAL").unwrap_or_else(|_| "x-terminal-emulator".into());
+        launch(Command::new(terminal).current_dir(folder))?;
+    }
+    Ok(())
+}
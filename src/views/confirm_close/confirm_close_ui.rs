@@ -0,0 +1,130 @@
+use freya::prelude::*;
+
+use crate::{
+    backup::remove_backup,
+    components::Overlay,
+    state::{Channel, CloseRequest, RadioAppState},
+    views::panels::tabs::editor::save_editor_tab,
+};
+
+/// Asks what to do with the unsaved changes of whatever is being closed.
+#[derive(PartialEq)]
+pub struct ConfirmClose {
+    pub radio_app_state: RadioAppState,
+}
+
+impl Component for ConfirmClose {
+    fn render(&self) -> impl IntoElement {
+        let mut radio_app_state = self.radio_app_state;
+        let focus_id = use_hook(AccessibilityId::new_unique);
+        use_hook(move || focus_id.request_focus());
+
+        let app_state = radio_app_state.read();
+        let Some(request) = app_state.close_request else {
+            return rect().into_element();
+        };
+        let edited = app_state.edited_tabs(request);
+        let titles = edited
+            .iter()
+            .map(|tab_id| app_state.tab(tab_id).get_data().title)
+            .collect::<Vec<_>>();
+        drop(app_state);
+
+        let message = match titles.as_slice() {
+            [title] => format!("Do you want to save the changes made to {title}?"),
+            titles => format!("{} files have unsaved changes.", titles.len()),
+        };
+        let save_text = if edited.len() > 1 { "Save All" } else { "Save" };
+
+        let on_save = {
+            let edited = edited.clone();
+            move || {
+                let edited = edited.clone();
+                spawn(async move {
+                    for tab_id in edited {
+                        // Failures are notified, keep the tabs open
+                        if save_editor_tab(radio_app_state, tab_id).await.is_err() {
+                            radio_app_state
+                                .write_channel(Channel::Global)
+                                .focus_previous_view();
+                            return;
+                        }
+                    }
+                    let mut app_state = radio_app_state.write_channel(Channel::Global);
+                    app_state.focus_previous_view();
+                    app_state.close(request);
+                });
+            }
+        };
+
+        let on_discard = move |_| {
+            let mut app_state = radio_app_state.write_channel(Channel::Global);
+            // Backups are forgotten once their tab closes, except when valin exits
+            if request == CloseRequest::Window {
+                for tab_id in &edited {
+                    if let Some(editor_tab) = app_state.tab(tab_id).as_text_editor() {
+                        remove_backup(&editor_tab.path);
+                    }
+                }
+            }
+            app_state.focus_previous_view();
+            app_state.close(request);
+        };
+
+        let on_cancel = move |_| {
+            radio_app_state
+                .write_channel(Channel::Global)
+                .focus_previous_view();
+        };
+
+        let on_key_down = {
+            let on_save = on_save.clone();
+            move |e: Event<KeyboardEventData>| {
+                if e.code == Code::Enter {
+                    on_save();
+                }
+            }
+        };
+
+        Overlay::new()
+            .child(
+                rect()
+                    .width(Size::fill())
+                    .padding(12.)
+                    .spacing(12.)
+                    .a11y_id(focus_id)
+                    .on_key_down(on_key_down)
+                    .child(label().text(message))
+                    .maybe_child((titles.len() > 1).then(|| {
+                        rect().children(
+                            titles
+                                .into_iter()
+                                .map(|title| {
+                                    label()
+                                        .max_lines(1)
+                                        .color((125, 133, 144))
+                                        .text(title)
+                                        .into_element()
+                                })
+                                .collect::<Vec<_>>(),
+                        )
+                    }))
+                    .child(
+                        rect()
+                            .horizontal()
+                            .width(Size::fill())
+                            .main_align(Alignment::End)
+                            .spacing(4.)
+                            .child(Button::new().flat().on_press(on_cancel).child("Cancel"))
+                            .child(
+                                Button::new()
+                                    .flat()
+                                    .on_press(on_discard)
+                                    .child("Don't Save"),
+                            )
+                            .child(Button::new().on_press(move |_| on_save()).child(save_text)),
+                    ),
+            )
+            .into_element()
+    }
+}
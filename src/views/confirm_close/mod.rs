@@ -0,0 +1 @@
+pub mod confirm_close_ui;
@@ -0,0 +1,20 @@
+use freya::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContextMenuEntry {
+    /// Id of the command to run.
+    Command(&'static str),
+    Separator,
+}
+
+/// Commands offered by a secondary click, shown where it happened.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContextMenuState {
+    pub position: CursorPoint,
+    pub entries: Vec<ContextMenuEntry>,
+}
+
+/// Where a secondary click happened, or `None` for any other button.
+pub fn secondary_press_location(e: &Event<PointerEventData>) -> Option<CursorPoint> {
+    (e.button() == Some(MouseButton::Right)).then(|| e.global_location())
+}
@@ -0,0 +1,123 @@
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    components::ButtonStatus,
+    state::{AppState, Channel, EditorCommands},
+};
+
+use super::context_menu_state::ContextMenuEntry;
+
+#[derive(Clone, PartialEq)]
+struct ContextMenuItem {
+    text: String,
+    on_press: EventHandler<()>,
+}
+
+impl Component for ContextMenuItem {
+    fn render(&self) -> impl IntoElement {
+        let mut status = use_state(|| ButtonStatus::Idle);
+        let on_press = self.on_press.clone();
+
+        let background = match *status.read() {
+            ButtonStatus::Hovering => (110, 118, 129, 0.1).into(),
+            ButtonStatus::Idle => Color::TRANSPARENT,
+        };
+
+        rect()
+            .width(Size::fill())
+            .padding((6., 10.))
+            .corner_radius(4.)
+            .background(background)
+            .on_pointer_enter(move |_| status.set(ButtonStatus::Hovering))
+            .on_pointer_leave(move |_| status.set(ButtonStatus::Idle))
+            .on_press(move |_| on_press.call(()))
+            .child(label().max_lines(1).text(self.text.clone()))
+    }
+}
+
+/// Commands offered by a secondary click on an item, next to the pointer.
+#[derive(PartialEq)]
+pub struct ContextMenu {
+    pub editor_commands: State<EditorCommands>,
+}
+
+impl Component for ContextMenu {
+    fn render(&self) -> impl IntoElement {
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::Global);
+        let Some(menu) = radio_app_state.read().context_menu.clone() else {
+            return rect().into_element();
+        };
+        let editor_commands = self.editor_commands;
+        let commands = editor_commands.read();
+
+        let on_global_pointer_down = move |_: Event<PointerEventData>| {
+            radio_app_state.write_channel(Channel::Global).context_menu = None;
+        };
+
+        let on_pointer_down = move |e: Event<PointerEventData>| {
+            e.stop_propagation();
+        };
+
+        let entries = menu
+            .entries
+            .iter()
+            .filter_map(|entry| match entry {
+                ContextMenuEntry::Separator => Some(
+                    rect()
+                        .width(Size::fill())
+                        .height(Size::px(1.))
+                        .margin((4., 0.))
+                        .background((48, 54, 61))
+                        .into_element(),
+                ),
+                ContextMenuEntry::Command(id) => {
+                    let id = *id;
+                    let command = commands.commands.get(id)?;
+                    Some(
+                        ContextMenuItem {
+                            text: command.text().to_string(),
+                            on_press: (move |_| {
+                                radio_app_state.write_channel(Channel::Global).context_menu = None;
+                                editor_commands.read().trigger(id);
+                            })
+                            .into(),
+                        }
+                        .into_element(),
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+
+        rect()
+            .width(Size::px(0.))
+            .height(Size::px(0.))
+            .layer(Layer::Overlay)
+            .on_global_pointer_down(on_global_pointer_down)
+            .child(
+                rect()
+                    .width(Size::px(240.))
+                    .position(
+                        Position::new_global()
+                            .left(menu.position.x as f32)
+                            .top(menu.position.y as f32),
+                    )
+                    .padding(4.)
+                    .corner_radius(8.)
+                    .background((22, 27, 34))
+                    .border(Border::new().width(1.).fill((48, 54, 61)))
+                    .shadow(
+                        Shadow::default()
+                            .x(0.)
+                            .y(3.)
+                            .blur(12.)
+                            .spread(4.)
+                            .color((1, 4, 9, 140)),
+                    )
+                    .font_size(13.)
+                    .on_pointer_down(on_pointer_down)
+                    .children(entries),
+            )
+            .into_element()
+    }
+}
@@ -0,0 +1,4 @@
+pub mod context_menu_state;
+pub mod context_menu_ui;
+
+pub use context_menu_ui::ContextMenu;
@@ -0,0 +1,669 @@
+use freya::prelude::spawn;
+
+use crate::{
+    state::{Channel, CommandRunContext, EditorCommand, EditorView, RadioAppState},
+    system::{copy_to_clipboard, open_terminal, reveal_in_file_manager},
+    views::{
+        notifications::notifications_state::Notification,
+        panels::tabs::editor::{AppStateEditorUtils, RadioEditorUtils},
+        workspace_search::workspace_search_ui::search_in_folder,
+    },
+};
+
+use super::{
+    file_explorer_state::{ExplorerClipboard, FilePrompt},
+    file_explorer_ui::reveal_in_explorer,
+    file_operations::{delete, duplicate, paste},
+};
+
+fn open_prompt(radio_app_state: &mut RadioAppState, prompt: FilePrompt) {
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    app_state.file_explorer.prompt = Some(prompt);
+    app_state.focus_view(EditorView::FilePrompt);
+}
+
+fn notify_error(mut radio_app_state: RadioAppState, message: String) {
+    radio_app_state
+        .write_channel(Channel::Notifications)
+        .notify(Notification::error(message));
+}
+
+fn set_clipboard(mut radio_app_state: RadioAppState, is_cut: bool) {
+    let mut app_state = radio_app_state.write_channel(Channel::FileExplorer);
+    if let Some(path) = app_state.file_explorer.focused_entry() {
+        app_state.file_explorer.clipboard = Some(ExplorerClipboard { path, is_cut });
+    }
+}
+
+#[derive(Clone)]
+pub struct NewFileCommand(pub RadioAppState);
+
+impl NewFileCommand {
+    pub fn id() -> &'static str {
+        "explorer-new-file"
+    }
+}
+
+impl EditorCommand for NewFileCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "New File"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let Some(folder) = radio_app_state.read().file_explorer.target_folder() else {
+            return;
+        };
+        open_prompt(&mut radio_app_state, FilePrompt::NewFile { folder });
+        ctx.focus_previous_view = false;
+    }
+}
+
+#[derive(Clone)]
+pub struct NewFolderCommand(pub RadioAppState);
+
+impl NewFolderCommand {
+    pub fn id() -> &'static str {
+        "explorer-new-folder"
+    }
+}
+
+impl EditorCommand for NewFolderCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "New Folder"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let Some(folder) = radio_app_state.read().file_explorer.target_folder() else {
+            return;
+        };
+        open_prompt(&mut radio_app_state, FilePrompt::NewFolder { folder });
+        ctx.focus_previous_view = false;
+    }
+}
+
+#[derive(Clone)]
+pub struct RenameFileCommand(pub RadioAppState);
+
+impl RenameFileCommand {
+    pub fn id() -> &'static str {
+        "explorer-rename"
+    }
+}
+
+impl EditorCommand for RenameFileCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Rename File"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let Some(path) = radio_app_state.read().file_explorer.focused_entry() else {
+            return;
+        };
+        open_prompt(&mut radio_app_state, FilePrompt::Rename { path });
+        ctx.focus_previous_view = false;
+    }
+}
+
+#[derive(Clone)]
+pub struct DuplicateFileCommand(pub RadioAppState);
+
+impl DuplicateFileCommand {
+    pub fn id() -> &'static str {
+        "explorer-duplicate"
+    }
+}
+
+impl EditorCommand for DuplicateFileCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Duplicate File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio_app_state = self.0;
+        let Some(path) = radio_app_state.read().file_explorer.focused_entry() else {
+            return;
+        };
+        spawn(duplicate(radio_app_state, path));
+    }
+}
+
+#[derive(Clone)]
+pub struct TrashFileCommand(pub RadioAppState);
+
+impl TrashFileCommand {
+    pub fn id() -> &'static str {
+        "explorer-trash"
+    }
+}
+
+impl EditorCommand for TrashFileCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Move File to Trash"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio_app_state = self.0;
+        let Some(path) = radio_app_state.read().file_explorer.focused_entry() else {
+            return;
+        };
+        spawn(delete(radio_app_state, path, false));
+    }
+}
+
+#[derive(Clone)]
+pub struct DeleteFileCommand(pub RadioAppState);
+
+impl DeleteFileCommand {
+    pub fn id() -> &'static str {
+        "explorer-delete"
+    }
+}
+
+impl EditorCommand for DeleteFileCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Delete File Permanently"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let Some(path) = radio_app_state.read().file_explorer.focused_entry() else {
+            return;
+        };
+        open_prompt(&mut radio_app_state, FilePrompt::Delete { path });
+        ctx.focus_previous_view = false;
+    }
+}
+
+#[derive(Clone)]
+pub struct CopyFileCommand(pub RadioAppState);
+
+impl CopyFileCommand {
+    pub fn id() -> &'static str {
+        "explorer-copy"
+    }
+}
+
+impl EditorCommand for CopyFileCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Copy File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        set_clipboard(self.0, false);
+    }
+}
+
+#[derive(Clone)]
+pub struct CutFileCommand(pub RadioAppState);
+
+impl CutFileCommand {
+    pub fn id() -> &'static str {
+        "explorer-cut"
+    }
+}
+
+impl EditorCommand for CutFileCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Cut File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        set_clipboard(self.0, true);
+    }
+}
+
+#[derive(Clone)]
+pub struct PasteFileCommand(pub RadioAppState);
+
+impl PasteFileCommand {
+    pub fn id() -> &'static str {
+        "explorer-paste"
+    }
+}
+
+impl EditorCommand for PasteFileCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Paste File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio_app_state = self.0;
+        let Some(folder) = radio_app_state.read().file_explorer.target_folder() else {
+            return;
+        };
+        spawn(paste(radio_app_state, folder));
+    }
+}
+
+#[derive(Clone)]
+pub struct CopyPathCommand(pub RadioAppState);
+
+impl CopyPathCommand {
+    pub fn id() -> &'static str {
+        "explorer-copy-path"
+    }
+}
+
+impl EditorCommand for CopyPathCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Copy Path"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio_app_state = self.0;
+        if let Some(path) = radio_app_state.read().file_explorer.focused_path.clone() {
+            copy_to_clipboard(path.to_string_lossy().into_owned());
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CopyRelativePathCommand(pub RadioAppState);
+
+impl CopyRelativePathCommand {
+    pub fn id() -> &'static str {
+        "explorer-copy-relative-path"
+    }
+}
+
+impl EditorCommand for CopyRelativePathCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Copy Relative Path"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio_app_state = self.0;
+        let app_state = radio_app_state.read();
+        let Some(path) = app_state.file_explorer.focused_path.as_deref() else {
+            return;
+        };
+        let relative_path = app_state
+            .file_explorer
+            .folder_of(path)
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        copy_to_clipboard(relative_path.to_string_lossy().into_owned());
+    }
+}
+
+#[derive(Clone)]
+pub struct RevealInFileManagerCommand(pub RadioAppState);
+
+impl RevealInFileManagerCommand {
+    pub fn id() -> &'static str {
+        "explorer-reveal-in-file-manager"
+    }
+}
+
+impl EditorCommand for RevealInFileManagerCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Reveal in File Manager"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio_app_state = self.0;
+        let Some(path) = radio_app_state.read().file_explorer.focused_path.clone() else {
+            return;
+        };
+        if let Err(err) = reveal_in_file_manager(&path) {
+            notify_error(
+                radio_app_state,
+                format!("Failed to open the file manager: {err}"),
+            );
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OpenInTerminalCommand(pub RadioAppState);
+
+impl OpenInTerminalCommand {
+    pub fn id() -> &'static str {
+        "explorer-open-in-terminal"
+    }
+}
+
+impl EditorCommand for OpenInTerminalCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Open in Terminal"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio_app_state = self.0;
+        let Some(folder) = radio_app_state.read().file_explorer.target_folder() else {
+            return;
+        };
+        if let Err(err) = open_terminal(&folder) {
+            notify_error(radio_app_state, format!("Failed to open a terminal: {err}"));
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FindInFolderCommand(pub RadioAppState);
+
+impl FindInFolderCommand {
+    pub fn id() -> &'static str {
+        "explorer-find-in-folder"
+    }
+}
+
+impl EditorCommand for FindInFolderCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Find in Folder"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let radio_app_state = self.0;
+        let Some(folder) = radio_app_state.read().file_explorer.target_folder() else {
+            return;
+        };
+        search_in_folder(radio_app_state, &folder);
+        ctx.focus_previous_view = false;
+    }
+}
+
+#[derive(Clone)]
+pub struct CompareFileWithCommand(pub RadioAppState);
+
+impl CompareFileWithCommand {
+    pub fn id() -> &'static str {
+        "explorer-compare-with"
+    }
+}
+
+impl EditorCommand for CompareFileWithCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Compare File With…"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let Some(path) = radio_app_state.read().file_explorer.focused_path.clone() else {
+            return;
+        };
+        if !path.is_file() {
+            return;
+        }
+        // The file search picks the other file
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        app_state.compare_with = Some(path);
+        app_state.focus_view(EditorView::FileSearch);
+        ctx.focus_previous_view = false;
+    }
+}
+
+#[derive(Clone)]
+pub struct RevealInExplorerCommand(pub RadioAppState);
+
+impl RevealInExplorerCommand {
+    pub fn id() -> &'static str {
+        "reveal-in-explorer"
+    }
+}
+
+impl EditorCommand for RevealInExplorerCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Reveal in Explorer"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio_app_state = self.0;
+        let Some(tab_id) = radio_app_state.get_active_editor_tab() else {
+            return;
+        };
+        let path = radio_app_state.read().editor_tab(tab_id).path.clone();
+        spawn(reveal_in_explorer(radio_app_state, path, true));
+    }
+}
+
+#[derive(Clone)]
+pub struct ToggleIgnoredFilesCommand(pub RadioAppState);
+
+impl ToggleIgnoredFilesCommand {
+    pub fn id() -> &'static str {
+        "explorer-toggle-ignored"
+    }
+}
+
+impl EditorCommand for ToggleIgnoredFilesCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Toggle Ignored Files"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Settings);
+        let explorer = &mut app_state.settings.explorer;
+        explorer.show_ignored = !explorer.show_ignored;
+    }
+}
+
+#[derive(Clone)]
+pub struct ToggleHiddenFilesCommand(pub RadioAppState);
+
+impl ToggleHiddenFilesCommand {
+    pub fn id() -> &'static str {
+        "explorer-toggle-hidden"
+    }
+}
+
+impl EditorCommand for ToggleHiddenFilesCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Toggle Hidden Files"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Settings);
+        let explorer = &mut app_state.settings.explorer;
+        explorer.show_hidden = !explorer.show_hidden;
+    }
+}
+
+#[derive(Clone)]
+pub struct ToggleExcludedFilesCommand(pub RadioAppState);
+
+impl ToggleExcludedFilesCommand {
+    pub fn id() -> &'static str {
+        "explorer-toggle-excluded"
+    }
+}
+
+impl EditorCommand for ToggleExcludedFilesCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Toggle Excluded Files"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Settings);
+        let explorer = &mut app_state.settings.explorer;
+        explorer.show_excluded = !explorer.show_excluded;
+    }
+}
+
+#[derive(Clone)]
+pub struct ToggleFollowActiveFileCommand(pub RadioAppState);
+
+impl ToggleFollowActiveFileCommand {
+    pub fn id() -> &'static str {
+        "explorer-toggle-follow-active-file"
+    }
+}
+
+impl EditorCommand for ToggleFollowActiveFileCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Toggle Follow Active File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio_app_state = self.0;
+        let mut app_state = radio_app_state.write_channel(Channel::Settings);
+        let explorer = &mut app_state.settings.explorer;
+        explorer.follow_active_file = !explorer.follow_active_file;
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use ignore::{
+    WalkBuilder,
+    gitignore::{Gitignore, GitignoreBuilder},
+};
+
+use crate::state::ExplorerSettings;
+
+/// Entries of a folder that aren't ignored by the `.gitignore` files, `.git/info/exclude`
+/// or the global gitignore.
+pub async fn unignored_entries(folder: &Path) -> HashSet<PathBuf> {
+    let folder = folder.to_path_buf();
+    smol::unblock(move || {
+        WalkBuilder::new(&folder)
+            .max_depth(Some(1))
+            .hidden(false)
+            .build()
+            .filter_map(Result::ok)
+            .map(|entry| entry.into_path())
+            .filter(|path| path != &folder)
+            .collect()
+    })
+    .await
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemVisibility {
+    Shown,
+    Dimmed,
+    Hidden,
+}
+
+/// Decides how the items of an opened folder are listed, following the explorer settings.
+pub struct ExplorerFilter<'a> {
+    settings: &'a ExplorerSettings,
+    exclude: Option<Gitignore>,
+}
+
+impl<'a> ExplorerFilter<'a> {
+    pub fn new(settings: &'a ExplorerSettings, root_path: &Path) -> Self {
+        let exclude = (!settings.show_excluded && !settings.exclude.is_empty())
+            .then(|| {
+                let mut builder = GitignoreBuilder::new(root_path);
+                // Invalid globs are left out rather than hiding everything
+                for glob in &settings.exclude {
+                    let _ = builder.add_line(None, glob);
+                }
+                builder.build().ok()
+            })
+            .flatten();
+        Self { settings, exclude }
+    }
+
+    pub fn visibility(&self, path: &Path, is_folder: bool, is_ignored: bool) -> ItemVisibility {
+        let is_hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if is_hidden && !self.settings.show_hidden {
+            return ItemVisibility::Hidden;
+        }
+        if let Some(exclude) = &self.exclude
+            && exclude.matched(path, is_folder).is_ignore()
+        {
+            return ItemVisibility::Hidden;
+        }
+        match (is_ignored, self.settings.show_ignored) {
+            (false, _) => ItemVisibility::Shown,
+            (true, false) => ItemVisibility::Hidden,
+            (true, true) if self.settings.dim_ignored => ItemVisibility::Dimmed,
+            (true, true) => ItemVisibility::Shown,
+        }
+    }
+}
@@ -1,10 +1,37 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
 use freya::prelude::*;
 
-use super::file_explorer_ui::ExplorerItem;
+use super::file_explorer_ui::{ExplorerItem, FolderState};
+
+/// A file or a folder copied or cut, waiting to be pasted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExplorerClipboard {
+    pub path: PathBuf,
+    pub is_cut: bool,
+}
+
+/// A file operation waiting for a name, or for a confirmation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilePrompt {
+    NewFile { folder: PathBuf },
+    NewFolder { folder: PathBuf },
+    Rename { path: PathBuf },
+    Delete { path: PathBuf },
+}
 
 pub struct FileExplorerState {
     pub folders: Vec<ExplorerItem>,
     pub focus_id: AccessibilityId,
+    /// Item under the keyboard focus, the target of the file operations.
+    pub focused_path: Option<PathBuf>,
+    /// Item to scroll to once it's listed.
+    pub scroll_to: Option<PathBuf>,
+    pub clipboard: Option<ExplorerClipboard>,
+    pub prompt: Option<FilePrompt>,
 }
 
 impl FileExplorerState {
@@ -12,6 +39,40 @@ impl FileExplorerState {
         Self {
             folders: Vec::new(),
             focus_id: AccessibilityId::new_unique(),
+            focused_path: None,
+            scroll_to: None,
+            clipboard: None,
+            prompt: None,
+        }
+    }
+
+    /// Item at a path, if it's shown in the tree.
+    pub fn item(&self, path: &Path) -> Option<&ExplorerItem> {
+        self.folders.iter().find_map(|folder| folder.find(path))
+    }
+
+    /// The focused item, unless it's one of the opened folders themselves,
+    /// which can't be renamed, moved or deleted from the explorer.
+    pub fn focused_entry(&self) -> Option<PathBuf> {
+        let focused_path = self.focused_path.as_ref()?;
+        let is_root = self
+            .folders
+            .iter()
+            .any(|folder| folder.path() == focused_path);
+        (!is_root && self.item(focused_path).is_some()).then(|| focused_path.clone())
+    }
+
+    /// Folder where new files go: the focused folder, the folder of the focused file,
+    /// or else the first opened folder.
+    pub fn target_folder(&self) -> Option<PathBuf> {
+        match self
+            .focused_path
+            .as_deref()
+            .and_then(|path| self.item(path))
+        {
+            Some(ExplorerItem::Folder { path, .. }) => Some(path.clone()),
+            Some(ExplorerItem::File { path, .. }) => path.parent().map(Path::to_path_buf),
+            None => self.folders.first().map(|folder| folder.path().clone()),
         }
     }
 
@@ -22,4 +83,50 @@ impl FileExplorerState {
     pub fn open_folder(&mut self, item: ExplorerItem) {
         self.folders.push(item)
     }
+
+    /// Replace the items of an opened folder with a fresh read of it, keeping its
+    /// subfolders opened. Returns false if the folder isn't opened anymore.
+    pub fn refresh_folder(&mut self, folder_path: &Path, fresh_items: Vec<ExplorerItem>) -> bool {
+        let Some(FolderState::Opened(items)) = self
+            .folders
+            .iter_mut()
+            .find_map(|folder| folder.folder_state_mut(folder_path))
+        else {
+            return false;
+        };
+
+        let mut opened = std::mem::take(items)
+            .into_iter()
+            .filter_map(|item| match item {
+                ExplorerItem::Folder {
+                    path,
+                    state: state @ FolderState::Opened(_),
+                    ..
+                } => Some((path, state)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        *items = fresh_items
+            .into_iter()
+            .map(|mut item| {
+                if let ExplorerItem::Folder { path, state, .. } = &mut item
+                    && let Some(opened_state) = opened.remove(path)
+                {
+                    *state = opened_state;
+                }
+                item
+            })
+            .collect();
+        true
+    }
+
+    /// The opened folder containing a path, if any.
+    pub fn folder_of(&self, path: &Path) -> Option<&Path> {
+        self.folders
+            .iter()
+            .map(|folder| folder.path().as_path())
+            .filter(|folder| path.starts_with(folder))
+            .max_by_key(|folder| folder.components().count())
+    }
 }
@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
 use freya::prelude::*;
 use freya::radio::use_radio;
@@ -8,8 +11,29 @@ use futures_channel::mpsc::UnboundedSender;
 use crate::{
     components::ButtonStatus,
     fs::FSTransport,
-    state::{AppState, Channel, DropValue, EditorView, RadioAppState},
-    views::panels::tabs::editor::EditorTab,
+    git::GitStatus,
+    state::{
+        AppState, Channel, DropValue, EditorCommands, EditorSidePanel, EditorView,
+        KeyboardShortcuts, RadioAppState,
+    },
+    views::{
+        context_menu::context_menu_state::{ContextMenuEntry, secondary_press_location},
+        notifications::notifications_state::Notification,
+        panels::tabs::editor::EditorTab,
+    },
+};
+
+use super::{
+    commands::{
+        CompareFileWithCommand, CopyFileCommand, CopyPathCommand, CopyRelativePathCommand,
+        CutFileCommand, DeleteFileCommand, DuplicateFileCommand, FindInFolderCommand,
+        NewFileCommand, NewFolderCommand, OpenInTerminalCommand, PasteFileCommand,
+        RenameFileCommand, RevealInExplorerCommand, RevealInFileManagerCommand,
+        ToggleExcludedFilesCommand, ToggleFollowActiveFileCommand, ToggleHiddenFilesCommand,
+        ToggleIgnoredFilesCommand, TrashFileCommand,
+    },
+    file_explorer_filter::{ExplorerFilter, ItemVisibility, unignored_entries},
+    file_operations::move_into,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -20,20 +44,28 @@ pub enum FolderState {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExplorerItem {
-    Folder { path: PathBuf, state: FolderState },
-    File { path: PathBuf },
+    Folder {
+        path: PathBuf,
+        state: FolderState,
+        /// Ignored by git, like `target/`.
+        is_ignored: bool,
+    },
+    File {
+        path: PathBuf,
+        is_ignored: bool,
+    },
 }
 
 impl ExplorerItem {
     pub fn path(&self) -> &PathBuf {
         match self {
             Self::Folder { path, .. } => path,
-            Self::File { path } => path,
+            Self::File { path, .. } => path,
         }
     }
 
     pub fn set_folder_state(&mut self, folder_path: &PathBuf, folder_state: FolderState) {
-        let ExplorerItem::Folder { path, state } = self else {
+        let ExplorerItem::Folder { path, state, .. } = self else {
             return;
         };
 
@@ -54,34 +86,127 @@ impl ExplorerItem {
         }
     }
 
-    pub fn flat(&self, depth: usize, root_path: &PathBuf) -> Vec<FlatItem> {
-        let mut flat_items = vec![self.clone().into_flat(depth, root_path)];
+    /// State of the folder at `folder_path`, this one or a nested one.
+    pub fn folder_state_mut(&mut self, folder_path: &Path) -> Option<&mut FolderState> {
+        let ExplorerItem::Folder { path, state, .. } = self else {
+            return None;
+        };
+
+        if path == folder_path {
+            return Some(state);
+        }
+
+        if !folder_path.starts_with(path.as_path()) {
+            return None;
+        }
+
+        let FolderState::Opened(items) = state else {
+            return None;
+        };
+        items
+            .iter_mut()
+            .find_map(|item| item.folder_state_mut(folder_path))
+    }
+
+    /// This item or a nested one, if it's at `item_path`.
+    pub fn find(&self, item_path: &Path) -> Option<&ExplorerItem> {
+        if self.path() == item_path {
+            return Some(self);
+        }
+        let ExplorerItem::Folder {
+            path,
+            state: FolderState::Opened(items),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        if !item_path.starts_with(path) {
+            return None;
+        }
+        items.iter().find_map(|item| item.find(item_path))
+    }
+
+    /// Paths of the opened folders, this one included.
+    pub fn opened_folders(&self) -> Vec<PathBuf> {
+        let ExplorerItem::Folder {
+            path,
+            state: FolderState::Opened(items),
+            ..
+        } = self
+        else {
+            return Vec::new();
+        };
+        std::iter::once(path.clone())
+            .chain(items.iter().flat_map(|item| item.opened_folders()))
+            .collect()
+    }
+
+    /// The items to list, this one included. Everything inside of an ignored folder
+    /// counts as ignored too.
+    pub fn flat(
+        &self,
+        depth: usize,
+        root_path: &PathBuf,
+        filter: &ExplorerFilter,
+        is_parent_ignored: bool,
+    ) -> Vec<FlatItem> {
+        let is_ignored = is_parent_ignored || self.is_ignored();
+        // The opened folders themselves are always listed
+        let visibility = if depth == 0 {
+            ItemVisibility::Shown
+        } else {
+            filter.visibility(self.path(), !self.is_file(), is_ignored)
+        };
+        if visibility == ItemVisibility::Hidden {
+            return Vec::new();
+        }
+
+        let mut flat_items =
+            vec![
+                self.clone()
+                    .into_flat(depth, root_path, visibility == ItemVisibility::Dimmed),
+            ];
         if let ExplorerItem::Folder {
             state: FolderState::Opened(items),
             ..
         } = self
         {
             for item in items {
-                let inner_items = item.flat(depth + 1, root_path);
+                let inner_items = item.flat(depth + 1, root_path, filter, is_ignored);
                 flat_items.extend(inner_items);
             }
         }
         flat_items
     }
 
-    fn into_flat(self, depth: usize, root_path: &Path) -> FlatItem {
+    pub fn is_file(&self) -> bool {
+        matches!(self, Self::File { .. })
+    }
+
+    pub fn is_ignored(&self) -> bool {
         match self {
-            ExplorerItem::File { path } => FlatItem {
+            Self::Folder { is_ignored, .. } | Self::File { is_ignored, .. } => *is_ignored,
+        }
+    }
+
+    fn into_flat(self, depth: usize, root_path: &Path, is_dimmed: bool) -> FlatItem {
+        match self {
+            ExplorerItem::File { path, .. } => FlatItem {
                 path,
                 is_file: true,
                 is_opened: false,
+                is_dimmed,
+                git_status: None,
                 depth,
                 root_path: root_path.to_path_buf(),
             },
-            ExplorerItem::Folder { path, state } => FlatItem {
+            ExplorerItem::Folder { path, state, .. } => FlatItem {
                 path,
                 is_file: false,
                 is_opened: state != FolderState::Closed,
+                is_dimmed,
+                git_status: None,
                 depth,
                 root_path: root_path.to_path_buf(),
             },
@@ -94,15 +219,27 @@ pub struct FlatItem {
     path: PathBuf,
     is_opened: bool,
     is_file: bool,
+    is_dimmed: bool,
+    git_status: Option<GitStatus>,
     depth: usize,
     root_path: PathBuf,
 }
 
+fn notify_read_error(mut radio_app_state: RadioAppState, path: &Path, err: std::io::Error) {
+    radio_app_state
+        .write_channel(Channel::Notifications)
+        .notify(Notification::error(format!(
+            "Failed to read {}: {err}",
+            path.display()
+        )));
+}
+
 pub async fn read_folder_as_items(
     dir: &Path,
     transport: &FSTransport,
 ) -> smol::io::Result<Vec<ExplorerItem>> {
     let mut paths = transport.read_dir(dir).await?;
+    let unignored = unignored_entries(dir).await;
     let mut folder_items = Vec::default();
     let mut files_items = Vec::default();
 
@@ -110,13 +247,15 @@ pub async fn read_folder_as_items(
         let file_type = entry.file_type().await?;
         let is_file = file_type.is_file();
         let path = entry.path();
+        let is_ignored = !unignored.contains(&path);
 
         if is_file {
-            files_items.push(ExplorerItem::File { path })
+            files_items.push(ExplorerItem::File { path, is_ignored })
         } else {
             folder_items.push(ExplorerItem::Folder {
                 path,
                 state: FolderState::Closed,
+                is_ignored,
             })
         }
     }
@@ -126,6 +265,116 @@ pub async fn read_folder_as_items(
     Ok(folder_items)
 }
 
+/// Read a folder along with the subfolders in `opened`, recursively.
+pub async fn read_folder_tree(
+    dir: &Path,
+    opened: &HashSet<PathBuf>,
+    transport: &FSTransport,
+) -> smol::io::Result<Vec<ExplorerItem>> {
+    let mut items = read_folder_as_items(dir, transport).await?;
+    for item in &mut items {
+        if let ExplorerItem::Folder { path, state, .. } = item
+            && opened.contains(path)
+            && let Ok(children) = Box::pin(read_folder_tree(path, opened, transport)).await
+        {
+            *state = FolderState::Opened(children);
+        }
+    }
+    Ok(items)
+}
+
+/// Open every folder down to `path`, then select it and scroll to it. With `focus`, the
+/// explorer is shown and focused as well.
+pub async fn reveal_in_explorer(mut radio_app_state: RadioAppState, path: PathBuf, focus: bool) {
+    let Some(root) = radio_app_state
+        .read()
+        .file_explorer
+        .folder_of(&path)
+        .map(Path::to_path_buf)
+    else {
+        return;
+    };
+    let transport = radio_app_state.read().default_transport.clone();
+
+    let mut folders = path
+        .ancestors()
+        .skip(1)
+        .take_while(|folder| folder.starts_with(&root))
+        .collect::<Vec<_>>();
+    folders.reverse();
+    for folder in folders {
+        let is_closed = matches!(
+            radio_app_state.read().file_explorer.item(folder),
+            Some(ExplorerItem::Folder {
+                state: FolderState::Closed,
+                ..
+            })
+        );
+        if !is_closed {
+            continue;
+        }
+        match read_folder_as_items(folder, &transport).await {
+            Ok(items) => {
+                let mut app_state = radio_app_state.write_channel(Channel::FileExplorer);
+                if let Some(state) = app_state
+                    .file_explorer
+                    .folders
+                    .iter_mut()
+                    .find_map(|item| item.folder_state_mut(folder))
+                {
+                    *state = FolderState::Opened(items);
+                }
+            }
+            Err(err) => {
+                notify_read_error(radio_app_state, folder, err);
+                return;
+            }
+        }
+    }
+
+    let mut app_state = radio_app_state.write_channel(Channel::FileExplorer);
+    app_state.file_explorer.focused_path = Some(path.clone());
+    app_state.file_explorer.scroll_to = Some(path);
+    drop(app_state);
+
+    if focus {
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        app_state.side_panel = Some(EditorSidePanel::FileExplorer);
+        app_state.focus_view(EditorView::FilesExplorer);
+    }
+}
+
+/// Height of the rows of the explorer.
+const ITEM_SIZE: f32 = 25.;
+/// Rows left above an item scrolled to, so it isn't stuck to the top edge.
+const SCROLL_MARGIN: usize = 5;
+
+/// What dragging an item drops, a file to open or a folder.
+fn drop_value_of(item: &FlatItem) -> DropValue {
+    if item.is_file {
+        DropValue::File(item.path.clone())
+    } else {
+        DropValue::Folder(item.path.clone())
+    }
+}
+
+/// Items listed in the explorer, in order.
+fn listed_items(app_state: &AppState) -> Vec<FlatItem> {
+    app_state
+        .file_explorer
+        .folders
+        .iter()
+        .flat_map(|tree| {
+            let filter = ExplorerFilter::new(&app_state.settings.explorer, tree.path());
+            tree.flat(0, tree.path(), &filter, false)
+        })
+        .map(|mut item| {
+            item.git_status = app_state.git.status(&item.path);
+            item
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum TreeTask {
     OpenFolder {
@@ -145,26 +394,124 @@ enum TreeTask {
 #[derive(Clone, PartialEq)]
 pub struct FileExplorer;
 
+impl FileExplorer {
+    /// Initialize the FileExplorer module.
+    pub fn init(
+        keyboard_shorcuts: &mut KeyboardShortcuts,
+        commands: &mut EditorCommands,
+        radio_app_state: RadioAppState,
+    ) {
+        // Register Commands
+        commands.register(NewFileCommand(radio_app_state));
+        commands.register(NewFolderCommand(radio_app_state));
+        commands.register(RenameFileCommand(radio_app_state));
+        commands.register(DuplicateFileCommand(radio_app_state));
+        commands.register(TrashFileCommand(radio_app_state));
+        commands.register(DeleteFileCommand(radio_app_state));
+        commands.register(CopyFileCommand(radio_app_state));
+        commands.register(CutFileCommand(radio_app_state));
+        commands.register(PasteFileCommand(radio_app_state));
+        commands.register(CopyPathCommand(radio_app_state));
+        commands.register(CopyRelativePathCommand(radio_app_state));
+        commands.register(RevealInFileManagerCommand(radio_app_state));
+        commands.register(OpenInTerminalCommand(radio_app_state));
+        commands.register(FindInFolderCommand(radio_app_state));
+        commands.register(CompareFileWithCommand(radio_app_state));
+        commands.register(RevealInExplorerCommand(radio_app_state));
+        commands.register(ToggleIgnoredFilesCommand(radio_app_state));
+        commands.register(ToggleHiddenFilesCommand(radio_app_state));
+        commands.register(ToggleExcludedFilesCommand(radio_app_state));
+        commands.register(ToggleFollowActiveFileCommand(radio_app_state));
+
+        // Register Shortcuts
+        keyboard_shorcuts.register(
+            |data: &KeyboardEventData,
+             commands: &mut EditorCommands,
+             radio_app_state: RadioAppState| {
+                // Only while the explorer is focused, so they don't clash with the editor
+                if radio_app_state.read().focused_view != EditorView::FilesExplorer {
+                    return false;
+                }
+                let is_pressing_ctrl = data.modifiers == Modifiers::CONTROL;
+                let is_pressing_shift = data.modifiers == Modifiers::SHIFT;
+                match data.code {
+                    // Pressing `F2`
+                    Code::F2 if data.modifiers.is_empty() => {
+                        commands.trigger(RenameFileCommand::id());
+                    }
+                    // Pressing `Delete`
+                    Code::Delete if data.modifiers.is_empty() => {
+                        commands.trigger(TrashFileCommand::id());
+                    }
+                    // Pressing `Shift Delete`
+                    Code::Delete if is_pressing_shift => {
+                        commands.trigger(DeleteFileCommand::id());
+                    }
+                    // Pressing `Ctrl D`
+                    Code::KeyD if is_pressing_ctrl => {
+                        commands.trigger(DuplicateFileCommand::id());
+                    }
+                    // Pressing `Ctrl C`
+                    Code::KeyC if is_pressing_ctrl => {
+                        commands.trigger(CopyFileCommand::id());
+                    }
+                    // Pressing `Ctrl X`
+                    Code::KeyX if is_pressing_ctrl => {
+                        commands.trigger(CutFileCommand::id());
+                    }
+                    // Pressing `Ctrl V`
+                    Code::KeyV if is_pressing_ctrl => {
+                        commands.trigger(PasteFileCommand::id());
+                    }
+                    _ => return false,
+                }
+                true
+            },
+        )
+    }
+}
+
 impl Component for FileExplorer {
     fn render(&self) -> impl IntoElement {
         let mut radio_app_state = use_radio::<AppState, Channel>(Channel::FileExplorer);
+        let mut scroll_controller = use_scroll_controller(ScrollConfig::default);
+
+        // Scroll to the item revealed with `reveal_in_explorer`
+        use_side_effect(move || {
+            let index = {
+                let app_state = radio_app_state.read();
+                let Some(path) = &app_state.file_explorer.scroll_to else {
+                    return;
+                };
+                listed_items(&app_state)
+                    .iter()
+                    .position(|item| &item.path == path)
+            };
+            if let Some(index) = index {
+                let y = index.saturating_sub(SCROLL_MARGIN) as f32 * ITEM_SIZE;
+                scroll_controller.scroll_to_y(-y as i32);
+            }
+            radio_app_state.write().file_explorer.scroll_to = None;
+        });
+
         let app_state = radio_app_state.read();
         let focus_id = app_state.file_explorer.focus_id;
-        let mut focused_item_index = use_state(|| 0);
+        let focused_path = app_state.file_explorer.focused_path.clone();
 
-        let items = app_state
-            .file_explorer
-            .folders
+        let items = listed_items(&app_state);
+        let item_paths = items
             .iter()
-            .flat_map(|tree| tree.flat(0, tree.path()))
-            .collect::<Vec<FlatItem>>();
-        let items_len = items.len();
-        let focused_item = items.get(focused_item_index()).cloned();
+            .map(|item| item.path.clone())
+            .collect::<Vec<_>>();
+        let focused_index = focused_path
+            .as_ref()
+            .and_then(|path| item_paths.iter().position(|item_path| item_path == path));
+        let focused_item = focused_index.and_then(|index| items.get(index)).cloned();
 
         let channel = use_hook(move || {
             let (tx, mut rx) = futures_channel::mpsc::unbounded();
             spawn(async move {
-                while let Some((task, item_index)) = rx.next().await {
+                while let Some((task, item_path)) = rx.next().await {
                     // Focus the FilesExplorer view if it wasn't focused already
                     if radio_app_state.read().focused_view != EditorView::FilesExplorer {
                         radio_app_state
@@ -178,16 +525,19 @@ impl Component for FileExplorer {
                             root_path,
                         } => {
                             let transport = radio_app_state.read().default_transport.clone();
-                            if let Ok(items) = read_folder_as_items(&folder_path, &transport).await
-                            {
-                                let mut app_state = radio_app_state.write();
-                                let folder = app_state
-                                    .file_explorer
-                                    .folders
-                                    .iter_mut()
-                                    .find(|folder| folder.path() == &root_path)
-                                    .unwrap();
-                                folder.set_folder_state(&folder_path, FolderState::Opened(items));
+                            match read_folder_as_items(&folder_path, &transport).await {
+                                Ok(items) => {
+                                    let mut app_state = radio_app_state.write();
+                                    let folder = app_state
+                                        .file_explorer
+                                        .folders
+                                        .iter_mut()
+                                        .find(|folder| folder.path() == &root_path)
+                                        .unwrap();
+                                    folder
+                                        .set_folder_state(&folder_path, FolderState::Opened(items));
+                                }
+                                Err(err) => notify_read_error(radio_app_state, &folder_path, err),
                             }
                         }
                         TreeTask::CloseFolder {
@@ -214,7 +564,7 @@ impl Component for FileExplorer {
                             );
                         }
                     }
-                    focused_item_index.set(item_index);
+                    radio_app_state.write().file_explorer.focused_path = Some(item_path);
                 }
             });
             tx
@@ -228,15 +578,20 @@ impl Component for FileExplorer {
                     let transport = radio_app_state.read().default_transport.clone();
 
                     let path = folder.path().to_owned();
-                    let items = read_folder_as_items(&path, &transport)
-                        .await
-                        .unwrap_or_default();
+                    let items = match read_folder_as_items(&path, &transport).await {
+                        Ok(items) => items,
+                        Err(err) => {
+                            notify_read_error(radio_app_state, &path, err);
+                            return;
+                        }
+                    };
 
                     let mut app_state = radio_app_state.write();
 
                     app_state.file_explorer.open_folder(ExplorerItem::Folder {
                         path,
                         state: FolderState::Opened(items),
+                        is_ignored: false,
                     });
 
                     app_state.focus_view(EditorView::FilesExplorer);
@@ -252,18 +607,16 @@ impl Component for FileExplorer {
                 if is_focused_files_explorer {
                     match ev.code {
                         Code::ArrowDown => {
-                            focused_item_index.with_mut(|mut i| {
-                                if *i < items_len - 1 {
-                                    *i += 1
-                                }
+                            let index = focused_index.map_or(0, |index| {
+                                (index + 1).min(item_paths.len().saturating_sub(1))
                             });
+                            radio_app_state.write().file_explorer.focused_path =
+                                item_paths.get(index).cloned();
                         }
                         Code::ArrowUp => {
-                            focused_item_index.with_mut(|mut i| {
-                                if *i > 0 {
-                                    *i -= 1
-                                }
-                            });
+                            let index = focused_index.map_or(0, |index| index.saturating_sub(1));
+                            radio_app_state.write().file_explorer.focused_path =
+                                item_paths.get(index).cloned();
                         }
                         Code::Enter => {
                             if let Some(item) = &focused_item {
@@ -281,7 +634,7 @@ impl Component for FileExplorer {
                                         root_path: item.root_path.clone(),
                                     },
                                 };
-                                let _ = channel.unbounded_send((task, focused_item_index()));
+                                let _ = channel.unbounded_send((task, item.path.clone()));
                             }
                         }
                         _ => {}
@@ -309,22 +662,55 @@ impl Component for FileExplorer {
                 .on_pointer_press(on_press)
                 .a11y_id(focus_id)
                 .child(
-                    VirtualScrollView::new_with_data(
-                        (items, focused_item_index, radio_app_state),
+                    VirtualScrollView::new_with_data_controlled(
+                        (items, focused_path, radio_app_state),
                         move |a, b| file_explorer_item_builder(a, channel.clone(), b),
+                        scroll_controller,
                     )
                     .length(length)
-                    .item_size(25.)
+                    .item_size(ITEM_SIZE)
                     .scroll_with_arrows(false),
                 )
         }
     }
 }
 
+/// Entries of the context menu of an item. The opened folders can't be renamed or deleted.
+fn context_menu_entries(is_root: bool) -> Vec<ContextMenuEntry> {
+    let mut entries = vec![
+        ContextMenuEntry::Command(NewFileCommand::id()),
+        ContextMenuEntry::Command(NewFolderCommand::id()),
+        ContextMenuEntry::Separator,
+    ];
+    if !is_root {
+        entries.extend([
+            ContextMenuEntry::Command(RenameFileCommand::id()),
+            ContextMenuEntry::Command(DuplicateFileCommand::id()),
+            ContextMenuEntry::Command(TrashFileCommand::id()),
+            ContextMenuEntry::Separator,
+            ContextMenuEntry::Command(CopyFileCommand::id()),
+            ContextMenuEntry::Command(CutFileCommand::id()),
+            ContextMenuEntry::Separator,
+            ContextMenuEntry::Command(CompareFileWithCommand::id()),
+        ]);
+    }
+    entries.extend([
+        ContextMenuEntry::Command(PasteFileCommand::id()),
+        ContextMenuEntry::Separator,
+        ContextMenuEntry::Command(CopyPathCommand::id()),
+        ContextMenuEntry::Command(CopyRelativePathCommand::id()),
+        ContextMenuEntry::Separator,
+        ContextMenuEntry::Command(RevealInFileManagerCommand::id()),
+        ContextMenuEntry::Command(OpenInTerminalCommand::id()),
+        ContextMenuEntry::Command(FindInFolderCommand::id()),
+    ]);
+    entries
+}
+
 fn file_explorer_item_builder(
     index: usize,
-    channel: UnboundedSender<(TreeTask, usize)>,
-    (items, focused_item, radio_app_state): &(Vec<FlatItem>, State<usize>, RadioAppState),
+    channel: UnboundedSender<(TreeTask, PathBuf)>,
+    (items, focused_path, radio_app_state): &(Vec<FlatItem>, Option<PathBuf>, RadioAppState),
 ) -> Element {
     let item: &FlatItem = &items[index];
 
@@ -336,11 +722,14 @@ fn file_explorer_item_builder(
         .to_str()
         .unwrap()
         .to_string();
-    let is_focused = *focused_item.read() == index;
+    let is_focused = focused_path.as_ref() == Some(&item.path);
 
     let item = item.clone();
     let is_file = item.is_file;
+    let is_dimmed = item.is_dimmed;
+    let git_status = item.git_status;
     let path = item.path.clone();
+    let drop_value = drop_value_of(&item);
     let icon_svg = {
         let app_state = radio_app_state.read();
         if item.is_file {
@@ -365,41 +754,95 @@ fn file_explorer_item_builder(
                 root_path: item.root_path.clone(),
             },
         };
-        let _ = channel.unbounded_send((task, index));
+        let _ = channel.unbounded_send((task, item.path.clone()));
+    };
+
+    let on_context_menu = {
+        let mut radio_app_state = *radio_app_state;
+        let path = path.clone();
+        let is_root = item.depth == 0;
+        move |position: CursorPoint| {
+            radio_app_state
+                .write_channel(Channel::FileExplorer)
+                .file_explorer
+                .focused_path = Some(path.clone());
+            radio_app_state
+                .write_channel(Channel::Global)
+                .open_context_menu(position, context_menu_entries(is_root));
+        }
     };
 
     let explorer_item = FileExplorerItem {
         depth: item.depth,
         radio_app_state: *radio_app_state,
         on_press: on_press.into(),
+        on_context_menu: on_context_menu.into(),
         is_focused,
+        is_dimmed,
+        git_status,
         children: rect()
             .horizontal()
             .cross_align(Alignment::Center)
+            .content(Content::Flex)
             .expanded()
             .child(
                 svg(icon_svg)
                     .width(Size::px(14.0))
                     .height(Size::px(14.0))
-                    .fill(Color::from_rgb(180, 180, 180))
+                    .fill(if is_dimmed {
+                        (180, 180, 180, 0.5).into()
+                    } else {
+                        Color::from_rgb(180, 180, 180)
+                    })
                     .margin(Gaps::new(0., 5., 0., 0.)),
             )
             .child(
                 label()
+                    .width(Size::flex(1.))
                     .max_lines(1)
                     .text_overflow(TextOverflow::Ellipsis)
                     .text(name.clone()),
             )
+            .maybe_child(git_status.and_then(|status| {
+                // Folders only tell there is something inside
+                let badge = if is_file { status.badge()? } else { "•" };
+                Some(
+                    label()
+                        .margin((0., 10., 0., 4.))
+                        .font_size(12.)
+                        .color(status.color())
+                        .text(badge),
+                )
+            }))
             .into(),
     }
     .into_element();
 
-    // Folders are not draggable.
-    if !is_file {
+    let explorer_item = if is_file {
+        explorer_item
+    } else {
+        // Files and folders dropped onto a folder are moved into it
+        let radio_app_state = *radio_app_state;
+        let folder_path = path.clone();
+        DropZone::<DockDrag<DropValue>>::new(explorer_item, move |drag: DockDrag<DropValue>| {
+            // The dragged value can't be read back, so find the listed item it was made of
+            let from = listed_items(&radio_app_state.read())
+                .into_iter()
+                .find(|item| drag == DockDrag::new(drop_value_of(item)))
+                .map(|item| item.path);
+            if let Some(from) = from {
+                spawn(move_into(radio_app_state, from, folder_path.clone()));
+            }
+        })
+        .into_element()
+    };
+
+    // The opened folders themselves stay where they are
+    if item.depth == 0 {
         return explorer_item;
     }
 
-    DragZone::<DockDrag<DropValue>>::new(DockDrag::new(DropValue::File(path)), explorer_item)
+    DragZone::<DockDrag<DropValue>>::new(DockDrag::new(drop_value), explorer_item)
         .drag_element(
             rect()
                 .interactive(false)
@@ -415,8 +858,12 @@ fn file_explorer_item_builder(
 pub struct FileExplorerItem {
     pub children: Element,
     pub on_press: EventHandler<()>,
+    pub on_context_menu: EventHandler<CursorPoint>,
     pub depth: usize,
     pub is_focused: bool,
+    /// Shown fainter, like the files ignored by git.
+    pub is_dimmed: bool,
+    pub git_status: Option<GitStatus>,
     pub radio_app_state: RadioAppState,
 }
 
@@ -433,16 +880,25 @@ impl Component for FileExplorerItem {
             on_press_handler.call(());
         };
 
+        let on_context_menu = self.on_context_menu.clone();
+        let on_pointer_press = move |e: Event<PointerEventData>| {
+            if let Some(position) = secondary_press_location(&e) {
+                on_context_menu.call(position);
+            }
+        };
+
         let background = match *status.read() {
             ButtonStatus::Idle | ButtonStatus::Hovering if self.is_focused => (22, 27, 34).into(),
             ButtonStatus::Hovering => (110, 118, 129, 0.1).into(),
             ButtonStatus::Idle => Color::TRANSPARENT,
         };
 
-        let color: Color = if self.is_focused {
-            (230, 237, 243).into()
-        } else {
-            (125, 133, 144).into()
+        let color: Color = match (self.is_focused, self.is_dimmed, self.git_status) {
+            (_, false, Some(status)) if status != GitStatus::Ignored => status.color(),
+            (true, false, _) => (230, 237, 243).into(),
+            (true, true, _) => (230, 237, 243, 0.6).into(),
+            (false, false, _) => (125, 133, 144).into(),
+            (false, true, _) => (125, 133, 144, 0.5).into(),
         };
 
         let padding_left = (self.depth * 10) + 10;
@@ -457,12 +913,13 @@ impl Component for FileExplorerItem {
             .on_pointer_enter(on_pointerenter)
             .on_pointer_leave(on_pointer_leave)
             .on_press(on_press)
+            .on_pointer_press(on_pointer_press)
             .background(background)
             .border(border)
             .width(Size::fill())
             .padding(Gaps::new(0., 0., 0., padding_left as f32))
             .main_align(Alignment::Center)
-            .height(Size::px(25.0))
+            .height(Size::px(ITEM_SIZE))
             .color(color)
             .font_size(14.0)
             .child(self.children.clone())
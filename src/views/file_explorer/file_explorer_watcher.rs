@@ -0,0 +1,119 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use notify::{
+    Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher, event::ModifyKind,
+};
+use tracing::warn;
+
+use crate::state::{Channel, RadioAppState};
+
+use super::file_explorer_ui::read_folder_as_items;
+
+/// How often the watched folders are synced with the opened ones.
+const SYNC_INTERVAL: Duration = Duration::from_secs(2);
+/// How long the folders must stay quiet before the tree is patched.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+/// Longest a burst of changes can delay the tree from being patched.
+const MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// Folders opened in the explorer, nested ones included.
+fn opened_folders(radio_app_state: RadioAppState) -> HashSet<PathBuf> {
+    radio_app_state
+        .read()
+        .file_explorer
+        .folders
+        .iter()
+        .flat_map(|folder| folder.opened_folders())
+        .collect()
+}
+
+/// Read the folders again and patch them into the explorer tree.
+pub async fn refresh_folders(mut radio_app_state: RadioAppState, folders: HashSet<PathBuf>) {
+    let transport = radio_app_state.read().default_transport.clone();
+    for folder in folders {
+        // Removed folders disappear from the refresh of their parent
+        let Ok(items) = read_folder_as_items(&folder, &transport).await else {
+            continue;
+        };
+        radio_app_state
+            .write_channel(Channel::FileExplorer)
+            .file_explorer
+            .refresh_folder(&folder, items);
+    }
+}
+
+/// Keep the folders opened in the explorer up to date with the files created, removed or
+/// renamed by other programs.
+pub async fn watch_explorer_folders(radio_app_state: RadioAppState) -> Option<()> {
+    let (tx, mut rx) = futures_channel::mpsc::unbounded::<PathBuf>();
+
+    let mut watcher = RecommendedWatcher::new(
+        move |ev: notify::Result<Event>| {
+            // Writes into files don't change the tree, renames do
+            if let Ok(ev) = ev
+                && (ev.kind.is_create()
+                    || ev.kind.is_remove()
+                    || matches!(ev.kind, EventKind::Modify(ModifyKind::Name(_))))
+            {
+                for path in ev.paths {
+                    if let Some(parent) = path.parent() {
+                        let _ = tx.unbounded_send(parent.to_path_buf());
+                    }
+                }
+            }
+        },
+        Config::default(),
+    )
+    .ok()?;
+
+    let mut watched = HashSet::<PathBuf>::new();
+    loop {
+        let folders = opened_folders(radio_app_state);
+        for folder in watched.difference(&folders) {
+            let _ = watcher.unwatch(folder);
+        }
+        let mut newly_watched = HashSet::new();
+        for folder in folders.difference(&watched) {
+            match watcher.watch(folder, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    newly_watched.insert(folder.clone());
+                }
+                Err(err) => warn!("Failed to watch {folder:?}: {err}"),
+            }
+        }
+        watched = folders;
+        // Catch up with what changed between opening the folders and watching them
+        refresh_folders(radio_app_state, newly_watched).await;
+
+        let changed = smol::future::or(async { rx.recv().await.ok() }, async {
+            smol::Timer::after(SYNC_INTERVAL).await;
+            None
+        })
+        .await;
+        let Some(folder) = changed else {
+            continue;
+        };
+
+        // Wait for bursts like a build writing into its output folder to settle
+        let mut changed = HashSet::from([folder]);
+        let deadline = Instant::now() + MAX_DELAY;
+        while Instant::now() < deadline {
+            let next = smol::future::or(async { rx.recv().await.ok() }, async {
+                smol::Timer::after(DEBOUNCE).await;
+                None
+            })
+            .await;
+            let Some(folder) = next else {
+                break;
+            };
+            changed.insert(folder);
+        }
+
+        changed.retain(|folder| watched.contains(folder));
+        refresh_folders(radio_app_state, changed).await;
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    state::{Channel, RadioAppState},
+    views::{
+        notifications::notifications_state::Notification,
+        panels::tabs::editor::{AppStateEditorUtils, EditorTab},
+    },
+};
+
+use super::{file_explorer_state::ExplorerClipboard, file_explorer_watcher::refresh_folders};
+
+fn notify_error(mut radio_app_state: RadioAppState, message: String) {
+    radio_app_state
+        .write_channel(Channel::Notifications)
+        .notify(Notification::error(message));
+}
+
+/// Show the changes right away instead of waiting for the watcher.
+async fn refresh_parents(radio_app_state: RadioAppState, paths: &[&Path]) {
+    let folders = paths
+        .iter()
+        .filter_map(|path| path.parent())
+        .map(Path::to_path_buf)
+        .collect::<HashSet<_>>();
+    refresh_folders(radio_app_state, folders).await;
+}
+
+fn set_focused_path(mut radio_app_state: RadioAppState, path: Option<PathBuf>) {
+    radio_app_state
+        .write_channel(Channel::FileExplorer)
+        .file_explorer
+        .focused_path = path;
+}
+
+/// `name copy.ext`, `name copy 2.ext`... whichever isn't taken in `folder`.
+async fn free_copy_path(radio_app_state: RadioAppState, folder: &Path, path: &Path) -> PathBuf {
+    let transport = radio_app_state.read().default_transport.clone();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut candidate = folder.join(format!("{stem} copy{extension}"));
+    let mut n = 2;
+    while transport.exists(&candidate).await {
+        candidate = folder.join(format!("{stem} copy {n}{extension}"));
+        n += 1;
+    }
+    candidate
+}
+
+/// Whether a name is of a single entry of a folder, so it can't reach other folders
+/// through separators, `..` or a root.
+fn is_valid_name(name: &str) -> bool {
+    let components = Path::new(name).components().collect::<Vec<_>>();
+    !name.contains(['/', '\\']) && matches!(components[..], [Component::Normal(_)])
+}
+
+/// Create an empty file in a folder and open it.
+pub async fn create_file(mut radio_app_state: RadioAppState, folder: PathBuf, name: String) {
+    if !is_valid_name(&name) {
+        notify_error(radio_app_state, format!("{name} isn't a valid file name."));
+        return;
+    }
+    let transport = radio_app_state.read().default_transport.clone();
+    let path = folder.join(&name);
+    if transport.exists(&path).await {
+        notify_error(radio_app_state, format!("{name} already exists."));
+        return;
+    }
+
+    if let Err(err) = transport.write(&path, b"").await {
+        notify_error(radio_app_state, format!("Failed to create {name}: {err}"));
+        return;
+    }
+
+    refresh_parents(radio_app_state, &[&path]).await;
+    set_focused_path(radio_app_state, Some(path.clone()));
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    EditorTab::open_with(radio_app_state, &mut app_state, path, transport.as_read());
+}
+
+pub async fn create_folder(radio_app_state: RadioAppState, folder: PathBuf, name: String) {
+    if !is_valid_name(&name) {
+        notify_error(
+            radio_app_state,
+            format!("{name} isn't a valid folder name."),
+        );
+        return;
+    }
+    let transport = radio_app_state.read().default_transport.clone();
+    let path = folder.join(&name);
+    if transport.exists(&path).await {
+        notify_error(radio_app_state, format!("{name} already exists."));
+        return;
+    }
+
+    if let Err(err) = transport.create_dir(&path).await {
+        notify_error(radio_app_state, format!("Failed to create {name}: {err}"));
+        return;
+    }
+
+    refresh_parents(radio_app_state, &[&path]).await;
+    set_focused_path(radio_app_state, Some(path));
+}
+
+/// Rename a file or a folder, keeping it in its folder.
+pub async fn rename(radio_app_state: RadioAppState, path: PathBuf, name: String) {
+    if !is_valid_name(&name) {
+        notify_error(radio_app_state, format!("{name} isn't a valid name."));
+        return;
+    }
+    let to = path.with_file_name(name);
+    move_path(radio_app_state, path, to).await;
+}
+
+/// Rename or move a file or a folder, along with the tabs of its files.
+pub async fn move_path(mut radio_app_state: RadioAppState, from: PathBuf, to: PathBuf) {
+    if from == to {
+        return;
+    }
+    let transport = radio_app_state.read().default_transport.clone();
+    let name = to
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    if to.starts_with(&from) {
+        notify_error(
+            radio_app_state,
+            format!("Can't move {name} inside of itself."),
+        );
+        return;
+    }
+    if transport.exists(&to).await {
+        notify_error(radio_app_state, format!("{name} already exists."));
+        return;
+    }
+
+    if let Err(err) = transport.rename(&from, &to).await {
+        let from_name = from.file_name().unwrap_or_default().to_string_lossy();
+        notify_error(
+            radio_app_state,
+            format!("Failed to move {from_name}: {err}"),
+        );
+        return;
+    }
+
+    radio_app_state
+        .write_channel(Channel::Global)
+        .rename_editor_paths(&from, &to);
+    refresh_parents(radio_app_state, &[&from, &to]).await;
+    set_focused_path(radio_app_state, Some(to));
+}
+
+/// Move a file or a folder into another folder.
+pub async fn move_into(radio_app_state: RadioAppState, from: PathBuf, folder: PathBuf) {
+    let Some(name) = from.file_name() else {
+        return;
+    };
+    let to = folder.join(name);
+    move_path(radio_app_state, from, to).await;
+}
+
+pub async fn duplicate(radio_app_state: RadioAppState, path: PathBuf) {
+    let Some(folder) = path.parent() else {
+        return;
+    };
+    let transport = radio_app_state.read().default_transport.clone();
+    let copy = free_copy_path(radio_app_state, folder, &path).await;
+
+    if let Err(err) = transport.copy(&path, &copy).await {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        notify_error(
+            radio_app_state,
+            format!("Failed to duplicate {name}: {err}"),
+        );
+        return;
+    }
+
+    refresh_parents(radio_app_state, &[&copy]).await;
+    set_focused_path(radio_app_state, Some(copy));
+}
+
+/// Move a file or a folder to the trash, or delete it for good if `permanently`.
+pub async fn delete(radio_app_state: RadioAppState, path: PathBuf, permanently: bool) {
+    let transport = radio_app_state.read().default_transport.clone();
+    let deleted = if permanently {
+        transport.remove(&path).await
+    } else {
+        transport.trash(&path).await
+    };
+
+    if let Err(err) = deleted {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        notify_error(radio_app_state, format!("Failed to delete {name}: {err}"));
+        return;
+    }
+
+    refresh_parents(radio_app_state, &[&path]).await;
+    set_focused_path(radio_app_state, path.parent().map(Path::to_path_buf));
+}
+
+/// Paste what was copied or cut into a folder. Cut items are moved and can only be pasted once.
+pub async fn paste(mut radio_app_state: RadioAppState, folder: PathBuf) {
+    let Some(clipboard) = radio_app_state.read().file_explorer.clipboard.clone() else {
+        return;
+    };
+    let ExplorerClipboard { path, is_cut } = clipboard;
+
+    if is_cut {
+        radio_app_state
+            .write_channel(Channel::FileExplorer)
+            .file_explorer
+            .clipboard = None;
+        move_into(radio_app_state, path, folder).await;
+        return;
+    }
+
+    let Some(name) = path.file_name() else {
+        return;
+    };
+    let transport = radio_app_state.read().default_transport.clone();
+    let mut to = folder.join(name);
+    if transport.exists(&to).await {
+        to = free_copy_path(radio_app_state, &folder, &path).await;
+    }
+    if to.starts_with(&path) {
+        let name = name.to_string_lossy();
+        notify_error(
+            radio_app_state,
+            format!("Can't copy {name} inside of itself."),
+        );
+        return;
+    }
+
+    if let Err(err) = transport.copy(&path, &to).await {
+        let name = name.to_string_lossy();
+        notify_error(radio_app_state, format!("Failed to paste {name}: {err}"));
+        return;
+    }
+
+    refresh_parents(radio_app_state, &[&to]).await;
+    set_focused_path(radio_app_state, Some(to));
+}
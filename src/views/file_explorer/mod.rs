@@ -1,4 +1,8 @@
+pub mod commands;
+pub mod file_explorer_filter;
 pub mod file_explorer_state;
 pub mod file_explorer_ui;
+pub mod file_explorer_watcher;
+pub mod file_operations;
 
 pub use file_explorer_ui::FileExplorer;
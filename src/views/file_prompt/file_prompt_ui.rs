@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use freya::prelude::*;
+
+use crate::{
+    components::Overlay,
+    state::{Channel, RadioAppState},
+    views::file_explorer::{
+        file_explorer_state::FilePrompt,
+        file_operations::{create_file, create_folder, delete, rename},
+    },
+};
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn close(mut radio_app_state: RadioAppState) {
+    radio_app_state
+        .write_channel(Channel::Global)
+        .focus_previous_view();
+}
+
+/// Asks for the name of a new or renamed file, or to confirm a permanent delete.
+#[derive(PartialEq)]
+pub struct FilePromptPopup {
+    pub radio_app_state: RadioAppState,
+}
+
+impl Component for FilePromptPopup {
+    fn render(&self) -> impl IntoElement {
+        let radio_app_state = self.radio_app_state;
+        let prompt = radio_app_state.read().file_explorer.prompt.clone();
+        let initial_value = match &prompt {
+            Some(FilePrompt::Rename { path }) => file_name(path),
+            _ => String::new(),
+        };
+        let value = use_state(move || initial_value);
+        let focus_id = use_hook(AccessibilityId::new_unique);
+        let is_delete = matches!(prompt, Some(FilePrompt::Delete { .. }));
+        use_hook(move || {
+            if is_delete {
+                focus_id.request_focus();
+            }
+        });
+
+        let Some(prompt) = prompt else {
+            return rect().into_element();
+        };
+
+        if let FilePrompt::Delete { path } = prompt {
+            let message = format!(
+                "Delete {} permanently? This can't be undone.",
+                file_name(&path)
+            );
+            let on_delete = move || {
+                spawn(delete(radio_app_state, path.clone(), true));
+                close(radio_app_state);
+            };
+            let on_key_down = {
+                let on_delete = on_delete.clone();
+                move |e: Event<KeyboardEventData>| {
+                    if e.code == Code::Enter {
+                        on_delete();
+                    }
+                }
+            };
+
+            return Overlay::new()
+                .child(
+                    rect()
+                        .width(Size::fill())
+                        .padding(12.)
+                        .spacing(12.)
+                        .a11y_id(focus_id)
+                        .on_key_down(on_key_down)
+                        .child(label().text(message))
+                        .child(
+                            rect()
+                                .horizontal()
+                                .width(Size::fill())
+                                .main_align(Alignment::End)
+                                .spacing(4.)
+                                .child(
+                                    Button::new()
+                                        .flat()
+                                        .on_press(move |_| close(radio_app_state))
+                                        .child("Cancel"),
+                                )
+                                .child(
+                                    Button::new().on_press(move |_| on_delete()).child("Delete"),
+                                ),
+                        ),
+                )
+                .into_element();
+        }
+
+        let placeholder = match &prompt {
+            FilePrompt::NewFile { .. } => "Name of the new file".to_string(),
+            FilePrompt::NewFolder { .. } => "Name of the new folder".to_string(),
+            FilePrompt::Rename { path } => format!("New name for {}", file_name(path)),
+            FilePrompt::Delete { .. } => unreachable!(),
+        };
+
+        let on_submit = move |_: String| {
+            let name = value.read().trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            match prompt.clone() {
+                FilePrompt::NewFile { folder } => {
+                    spawn(create_file(radio_app_state, folder, name));
+                }
+                FilePrompt::NewFolder { folder } => {
+                    spawn(create_folder(radio_app_state, folder, name));
+                }
+                FilePrompt::Rename { path } => {
+                    spawn(rename(radio_app_state, path, name));
+                }
+                FilePrompt::Delete { .. } => {}
+            }
+            close(radio_app_state);
+        };
+
+        Overlay::new()
+            .child(
+                Input::new(value)
+                    .width(Size::fill())
+                    .auto_focus(true)
+                    .inner_margin(12.)
+                    .placeholder(placeholder)
+                    .on_submit(on_submit)
+                    .on_pre_key_down(|e: Event<KeyboardEventData>| match &e.key {
+                        Key::Named(NamedKey::Enter) | Key::Named(NamedKey::Escape) => true,
+                        Key::Named(NamedKey::Tab) => false,
+                        _ => {
+                            e.stop_propagation();
+                            e.prevent_default();
+                            true
+                        }
+                    }),
+            )
+            .into_element()
+    }
+}
@@ -5,8 +5,9 @@ use ignore::WalkBuilder;
 
 use crate::{
     components::Overlay,
+    fs::split_position,
     state::{Channel, RadioAppState},
-    views::panels::tabs::editor::EditorTab,
+    views::panels::tabs::{diff::compare_files, editor::EditorTab},
 };
 
 const ITEM_HEIGHT: f32 = 28.;
@@ -17,6 +18,28 @@ struct FoundFile {
     display: String,
 }
 
+/// Line and column to go to in the picked file.
+type FilePosition = Option<(usize, usize)>;
+
+/// Open a picked file, or compare it with the file waiting for another one.
+fn pick_file(mut radio_app_state: RadioAppState, path: PathBuf, position: FilePosition) {
+    let transport = radio_app_state.read().default_transport.clone();
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    if let Some(compare_with) = app_state.compare_with.take() {
+        app_state.focus_previous_view();
+        drop(app_state);
+        spawn(compare_files(radio_app_state, compare_with, path));
+        return;
+    }
+    EditorTab::open_at(
+        radio_app_state,
+        &mut app_state,
+        path,
+        transport.as_read(),
+        position,
+    );
+}
+
 #[derive(PartialEq)]
 pub struct FileSearch {
     pub radio_app_state: RadioAppState,
@@ -24,7 +47,7 @@ pub struct FileSearch {
 
 impl Component for FileSearch {
     fn render(&self) -> impl IntoElement {
-        let mut radio_app_state = self.radio_app_state;
+        let radio_app_state = self.radio_app_state;
         let value = use_state(String::new);
         let mut selected = use_state(|| 0usize);
 
@@ -39,7 +62,12 @@ impl Component for FileSearch {
             smol::unblock(move || discover_files(&folders)).await
         });
 
-        let query = value.read().to_lowercase();
+        // `path:line:col` opens the file at that position
+        let (query, position) = {
+            let value = value.read();
+            let (query, position) = split_position(&value);
+            (query.to_lowercase(), position)
+        };
         let filtered_files: Vec<FoundFile> = match &*files_task.state() {
             FutureState::Fulfilled(files) => {
                 if query.is_empty() {
@@ -60,12 +88,16 @@ impl Component for FileSearch {
         let selected_path = filtered_files.get(*selected.read()).map(|f| f.path.clone());
 
         let on_submit = move |_: String| {
-            let Some(path) = selected_path.clone() else {
-                return;
-            };
-            let transport = radio_app_state.read().default_transport.clone();
-            let mut app_state = radio_app_state.write_channel(Channel::Global);
-            EditorTab::open_with(radio_app_state, &mut app_state, path, transport.as_read());
+            if let Some(path) = selected_path.clone() {
+                pick_file(radio_app_state, path, position);
+            }
+        };
+        let placeholder = match &radio_app_state.read().compare_with {
+            Some(path) => format!(
+                "Pick a file to compare {} with...",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            None => "Search files, add :line:col to jump...".to_string(),
         };
 
         let onkeydown = move |e: Event<KeyboardEventData>| {
@@ -95,7 +127,7 @@ impl Component for FileSearch {
                         .width(Size::fill())
                         .auto_focus(true)
                         .inner_margin(12.)
-                        .placeholder("Search files...")
+                        .placeholder(placeholder)
                         .on_submit(on_submit)
                         .on_pre_key_down(|e: Event<KeyboardEventData>| match e.code {
                             Code::ArrowUp | Code::ArrowDown => false,
@@ -118,7 +150,7 @@ impl Component for FileSearch {
                 } else {
                     rect().height(Size::px(list_height)).child(
                         VirtualScrollView::new_with_data(
-                            (filtered_files, selected, radio_app_state),
+                            (filtered_files, selected, position, radio_app_state),
                             file_search_item_builder,
                         )
                         .length(filtered_count)
@@ -132,11 +164,17 @@ impl Component for FileSearch {
 
 fn file_search_item_builder(
     index: usize,
-    (files, selected, radio_app_state): &(Vec<FoundFile>, State<usize>, RadioAppState),
+    (files, selected, position, radio_app_state): &(
+        Vec<FoundFile>,
+        State<usize>,
+        FilePosition,
+        RadioAppState,
+    ),
 ) -> Element {
     let file = files[index].clone();
+    let position = *position;
     let is_selected = *selected.read() == index;
-    let mut radio_app_state = *radio_app_state;
+    let radio_app_state = *radio_app_state;
 
     let icon_svg = radio_app_state
         .read()
@@ -147,16 +185,7 @@ fn file_search_item_builder(
 
     let on_press = {
         let path = file.path.clone();
-        move |_: Event<PressEventData>| {
-            let transport = radio_app_state.read().default_transport.clone();
-            let mut app_state = radio_app_state.write_channel(Channel::Global);
-            EditorTab::open_with(
-                radio_app_state,
-                &mut app_state,
-                path.clone(),
-                transport.as_read(),
-            );
-        }
+        move |_: Event<PressEventData>| pick_file(radio_app_state, path.clone(), position)
     };
 
     let display = file.display;
@@ -0,0 +1,66 @@
+use freya::prelude::*;
+use freya::text_edit::TextEditor;
+
+use crate::{
+    components::Overlay,
+    fs::parse_line_col,
+    state::{Channel, RadioAppState},
+    views::panels::tabs::editor::{AppStateEditorUtils, RadioEditorUtils},
+};
+
+#[derive(PartialEq)]
+pub struct GoToLine {
+    pub radio_app_state: RadioAppState,
+}
+
+impl Component for GoToLine {
+    fn render(&self) -> impl IntoElement {
+        let mut radio_app_state = self.radio_app_state;
+        let value = use_state(String::new);
+
+        let Some(tab_id) = radio_app_state.get_active_editor_tab() else {
+            return rect().into_element();
+        };
+        let placeholder = {
+            let app_state = radio_app_state.read();
+            let editor_tab = app_state.editor_tab(tab_id);
+            format!(
+                "Current line {} of {}. Type a line, optionally followed by :column",
+                editor_tab.data.cursor_row() + 1,
+                editor_tab.data.rope.len_lines()
+            )
+        };
+
+        let on_submit = move |_: String| {
+            let Some((line, col)) = parse_line_col(&value.read()) else {
+                return;
+            };
+            let mut app_state = radio_app_state.write_channel(Channel::Global);
+            if let Some(from) = app_state.active_jump_location() {
+                app_state.jump_list.push(from);
+            }
+            app_state.editor_tab_mut(tab_id).move_to_position(line, col);
+            app_state.focus_previous_view();
+        };
+
+        Overlay::new()
+            .child(
+                Input::new(value)
+                    .width(Size::fill())
+                    .auto_focus(true)
+                    .inner_margin(12.)
+                    .placeholder(placeholder)
+                    .on_submit(on_submit)
+                    .on_pre_key_down(|e: Event<KeyboardEventData>| match &e.key {
+                        Key::Named(NamedKey::Enter) | Key::Named(NamedKey::Escape) => true,
+                        Key::Named(NamedKey::Tab) => false,
+                        _ => {
+                            e.stop_propagation();
+                            e.prevent_default();
+                            true
+                        }
+                    }),
+            )
+            .into_element()
+    }
+}
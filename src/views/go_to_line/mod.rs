@@ -0,0 +1 @@
+pub mod go_to_line_ui;
@@ -1,5 +1,14 @@
 pub mod commander;
+pub mod confirm_close;
+pub mod context_menu;
 pub mod file_explorer;
+pub mod file_prompt;
 pub mod file_search;
+pub mod go_to_line;
+pub mod notifications;
 pub mod panels;
+pub mod problems;
+pub mod references;
+pub mod source_control;
 pub mod tab_switcher;
+pub mod workspace_search;
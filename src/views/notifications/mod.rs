@@ -0,0 +1,4 @@
+pub mod notifications_state;
+pub mod notifications_ui;
+
+pub use notifications_ui::{Notifications, NotificationsButton, Toasts};
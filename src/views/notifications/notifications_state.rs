@@ -0,0 +1,158 @@
+use std::rc::Rc;
+
+use crate::{state::RadioAppState, views::problems::problems_state::Severity};
+
+/// Most notifications kept in the history.
+const MAX_NOTIFICATIONS: usize = 100;
+
+/// A button of a notification.
+#[derive(Clone)]
+pub struct NotificationAction {
+    pub text: String,
+    pub on_press: Rc<dyn Fn(RadioAppState)>,
+    /// Whether pressing it dismisses the notification.
+    pub dismiss: bool,
+}
+
+/// A message for the user, shown as a toast and kept in the history.
+#[derive(Clone)]
+pub struct Notification {
+    pub id: usize,
+    pub severity: Severity,
+    pub message: String,
+    pub actions: Vec<NotificationAction>,
+    /// Whether it's still shown as a toast.
+    pub is_toast: bool,
+    /// Whether the toast stays until closed, like errors do.
+    pub is_sticky: bool,
+}
+
+impl PartialEq for Notification {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.is_toast == other.is_toast
+    }
+}
+
+impl Notification {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            severity,
+            message: message.into(),
+            actions: Vec::new(),
+            is_toast: true,
+            is_sticky: severity == Severity::Error,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    /// Keep the toast until it's closed.
+    pub fn sticky(mut self) -> Self {
+        self.is_sticky = true;
+        self
+    }
+
+    /// Add a button that runs `on_press` and dismisses the notification.
+    pub fn action(
+        mut self,
+        text: impl Into<String>,
+        on_press: impl Fn(RadioAppState) + 'static,
+    ) -> Self {
+        self.actions.push(NotificationAction {
+            text: text.into(),
+            on_press: Rc::new(on_press),
+            dismiss: true,
+        });
+        self
+    }
+
+    /// Add a button that runs `on_press`, keeping the notification.
+    pub fn persistent_action(
+        mut self,
+        text: impl Into<String>,
+        on_press: impl Fn(RadioAppState) + 'static,
+    ) -> Self {
+        self.actions.push(NotificationAction {
+            text: text.into(),
+            on_press: Rc::new(on_press),
+            dismiss: false,
+        });
+        self
+    }
+}
+
+pub struct NotificationsState {
+    /// Oldest first.
+    notifications: Vec<Notification>,
+    next_id: usize,
+    /// Notifications since the history was last seen.
+    pub unread: usize,
+}
+
+impl NotificationsState {
+    pub fn new() -> Self {
+        Self {
+            notifications: Vec::new(),
+            next_id: 1,
+            unread: 0,
+        }
+    }
+
+    pub fn push(&mut self, mut notification: Notification) -> usize {
+        notification.id = self.next_id;
+        self.next_id += 1;
+        self.notifications.push(notification);
+        if self.notifications.len() > MAX_NOTIFICATIONS {
+            self.notifications.remove(0);
+        }
+        self.next_id - 1
+    }
+
+    /// Newest first.
+    pub fn list(&self) -> impl Iterator<Item = &Notification> {
+        self.notifications.iter().rev()
+    }
+
+    /// Notifications still shown as toasts, oldest first.
+    pub fn toasts(&self) -> Vec<Notification> {
+        self.notifications
+            .iter()
+            .filter(|notification| notification.is_toast)
+            .cloned()
+            .collect()
+    }
+
+    /// Stop showing a notification as a toast, keeping it in the history.
+    pub fn hide_toast(&mut self, id: usize) {
+        if let Some(notification) = self
+            .notifications
+            .iter_mut()
+            .find(|notification| notification.id == id)
+        {
+            notification.is_toast = false;
+        }
+    }
+
+    pub fn hide_toasts(&mut self) {
+        for notification in &mut self.notifications {
+            notification.is_toast = false;
+        }
+    }
+
+    pub fn dismiss(&mut self, id: usize) {
+        self.notifications
+            .retain(|notification| notification.id != id);
+    }
+
+    pub fn clear(&mut self) {
+        self.notifications.clear();
+        self.unread = 0;
+    }
+}
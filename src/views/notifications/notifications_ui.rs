@@ -0,0 +1,284 @@
+use std::time::Duration;
+
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    components::CrossIcon,
+    state::{AppState, Channel, EditorSidePanel, RadioAppState},
+};
+
+use super::notifications_state::Notification;
+
+/// How long toasts that aren't sticky are shown.
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+/// Most toasts shown at once.
+const MAX_TOASTS: usize = 3;
+
+fn run_action(mut radio_app_state: RadioAppState, notification: &Notification, index: usize) {
+    let Some(action) = notification.actions.get(index) else {
+        return;
+    };
+    if action.dismiss {
+        radio_app_state
+            .write_channel(Channel::Notifications)
+            .notifications
+            .dismiss(notification.id);
+    }
+    (action.on_press)(radio_app_state);
+}
+
+/// Severity icon, message and actions of a notification.
+#[derive(Clone, PartialEq)]
+struct NotificationCard {
+    notification: Notification,
+    on_close: EventHandler<()>,
+}
+
+impl Component for NotificationCard {
+    fn render(&self) -> impl IntoElement {
+        let radio_app_state = use_radio::<AppState, Channel>(Channel::Notifications);
+        let notification = &self.notification;
+        let on_close = self.on_close.clone();
+
+        rect()
+            .width(Size::fill())
+            .padding(8.)
+            .spacing(6.)
+            .corner_radius(6.)
+            .background((22, 27, 34))
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .content(Content::Flex)
+                    .spacing(6.)
+                    .child(
+                        label()
+                            .color(notification.severity.color())
+                            .text(notification.severity.icon()),
+                    )
+                    .child(
+                        label()
+                            .width(Size::flex(1.))
+                            .text(notification.message.clone()),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .padding(4.)
+                            .rounded()
+                            .on_press(move |_| on_close.call(()))
+                            .child(CrossIcon {
+                                fill: (125, 133, 144).into(),
+                            }),
+                    ),
+            )
+            .maybe_child((!notification.actions.is_empty()).then(|| {
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .main_align(Alignment::End)
+                    .spacing(4.)
+                    .children(
+                        notification
+                            .actions
+                            .iter()
+                            .enumerate()
+                            .map(|(index, action)| {
+                                let notification = notification.clone();
+                                Button::new()
+                                    .compact()
+                                    .on_press(move |_| {
+                                        run_action(radio_app_state, &notification, index)
+                                    })
+                                    .child(action.text.clone())
+                                    .into_element()
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+            }))
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct Toast {
+    notification: Notification,
+}
+
+impl Component for Toast {
+    fn render_key(&self) -> DiffKey {
+        DiffKey::from(&self.notification.id)
+    }
+
+    fn render(&self) -> impl IntoElement {
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::Notifications);
+        let id = self.notification.id;
+
+        let is_sticky = self.notification.is_sticky;
+        use_hook(move || {
+            if !is_sticky {
+                spawn(async move {
+                    smol::Timer::after(TOAST_DURATION).await;
+                    radio_app_state
+                        .write_channel(Channel::Notifications)
+                        .notifications
+                        .hide_toast(id);
+                });
+            }
+        });
+
+        NotificationCard {
+            notification: self.notification.clone(),
+            on_close: (move |_| {
+                radio_app_state
+                    .write_channel(Channel::Notifications)
+                    .notifications
+                    .hide_toast(id);
+            })
+            .into(),
+        }
+    }
+}
+
+/// The latest notifications, stacked on the bottom right corner of the window.
+#[derive(Clone, PartialEq)]
+pub struct Toasts;
+
+impl Component for Toasts {
+    fn render(&self) -> impl IntoElement {
+        let radio_app_state = use_radio::<AppState, Channel>(Channel::Notifications);
+        let toasts = radio_app_state.read().notifications.toasts();
+        let skip = toasts.len().saturating_sub(MAX_TOASTS);
+
+        rect()
+            .width(Size::px(0.))
+            .height(Size::px(0.))
+            .layer(Layer::Overlay)
+            .child(
+                rect()
+                    .width(Size::px(360.))
+                    .position(Position::new_global().right(12.).bottom(44.))
+                    .spacing(6.)
+                    .children(
+                        toasts
+                            .into_iter()
+                            .skip(skip)
+                            .map(|notification| Toast { notification }.into_element())
+                            .collect::<Vec<_>>(),
+                    ),
+            )
+    }
+}
+
+/// The history of notifications.
+#[derive(Clone, PartialEq)]
+pub struct Notifications;
+
+impl Component for Notifications {
+    fn render(&self) -> impl IntoElement {
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::Notifications);
+        let notifications = radio_app_state
+            .read()
+            .notifications
+            .list()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let summary = match notifications.len() {
+            0 => "No notifications".to_string(),
+            1 => "1 notification".to_string(),
+            len => format!("{len} notifications"),
+        };
+
+        let on_clear = move |_| {
+            radio_app_state
+                .write_channel(Channel::Notifications)
+                .notifications
+                .clear();
+        };
+
+        rect()
+            .expanded()
+            .content(Content::Flex)
+            .padding(6.)
+            .spacing(6.)
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .child(
+                        label()
+                            .width(Size::flex(1.))
+                            .max_lines(1)
+                            .color((125, 133, 144))
+                            .font_size(12.)
+                            .text(summary),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_clear)
+                            .child("Clear All"),
+                    ),
+            )
+            .child(
+                ScrollView::new().height(Size::flex(1.)).child(
+                    rect().width(Size::fill()).spacing(6.).children(
+                        notifications
+                            .into_iter()
+                            .map(|notification| {
+                                let id = notification.id;
+                                NotificationCard {
+                                    notification,
+                                    on_close: (move |_| {
+                                        radio_app_state
+                                            .write_channel(Channel::Notifications)
+                                            .notifications
+                                            .dismiss(id);
+                                    })
+                                    .into(),
+                                }
+                                .into_element()
+                            })
+                            .collect::<Vec<_>>(),
+                    ),
+                ),
+            )
+    }
+}
+
+/// Status bar button that toggles the notifications history.
+#[derive(Clone, PartialEq)]
+pub struct NotificationsButton;
+
+impl Component for NotificationsButton {
+    fn render(&self) -> impl IntoElement {
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::Notifications);
+        let unread = radio_app_state.read().notifications.unread;
+
+        let on_press = move |_| {
+            radio_app_state
+                .write_channel(Channel::Global)
+                .toggle_side_panel(EditorSidePanel::Notifications);
+            // Already shown in the history
+            radio_app_state
+                .write_channel(Channel::Notifications)
+                .notifications
+                .hide_toasts();
+        };
+
+        Button::new()
+            .flat()
+            .compact()
+            .on_press(on_press)
+            .child(if unread > 0 {
+                format!("🔔 {unread}")
+            } else {
+                "🔔".to_string()
+            })
+    }
+}
@@ -0,0 +1,238 @@
+use freya::prelude::spawn;
+
+use crate::{
+    file_watcher::compare_with_disk,
+    state::{AppStateUtils, Channel, CommandRunContext, EditorCommand, EditorView, RadioAppState},
+    views::panels::tabs::editor::{AppStateEditorUtils, RadioEditorUtils},
+};
+
+use super::{DiffTab, compare_clipboard_with_selection, compare_with_head};
+
+/// Run an action on the active tab if it's a diff.
+fn with_active_diff_tab(mut radio: RadioAppState, action: impl FnOnce(&mut DiffTab)) {
+    let Some(tab_id) = radio.get_active_tab() else {
+        return;
+    };
+    if radio
+        .read()
+        .tab(&tab_id)
+        .as_any()
+        .downcast_ref::<DiffTab>()
+        .is_none()
+    {
+        return;
+    }
+    let mut app_state = radio.write_channel(Channel::follow_tab(tab_id));
+    if let Some(tab) = app_state
+        .tab_mut(&tab_id)
+        .as_any_mut()
+        .downcast_mut::<DiffTab>()
+    {
+        action(tab);
+    }
+}
+
+#[derive(Clone)]
+pub struct NextDifferenceCommand(pub RadioAppState);
+
+impl NextDifferenceCommand {
+    pub fn id() -> &'static str {
+        "go-to-next-difference"
+    }
+}
+
+impl EditorCommand for NextDifferenceCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Go to Next Difference"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        with_active_diff_tab(self.0, |tab| tab.go_to_difference(false));
+    }
+}
+
+#[derive(Clone)]
+pub struct PreviousDifferenceCommand(pub RadioAppState);
+
+impl PreviousDifferenceCommand {
+    pub fn id() -> &'static str {
+        "go-to-previous-difference"
+    }
+}
+
+impl EditorCommand for PreviousDifferenceCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Go to Previous Difference"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        with_active_diff_tab(self.0, |tab| tab.go_to_difference(true));
+    }
+}
+
+#[derive(Clone)]
+pub struct ToggleDiffLayoutCommand(pub RadioAppState);
+
+impl ToggleDiffLayoutCommand {
+    pub fn id() -> &'static str {
+        "toggle-diff-layout"
+    }
+}
+
+impl EditorCommand for ToggleDiffLayoutCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Toggle Inline Diff"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        with_active_diff_tab(self.0, DiffTab::toggle_layout);
+    }
+}
+
+#[derive(Clone)]
+pub struct CompareWithSavedCommand(pub RadioAppState);
+
+impl CompareWithSavedCommand {
+    pub fn id() -> &'static str {
+        "compare-with-saved"
+    }
+}
+
+impl EditorCommand for CompareWithSavedCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Compare With Saved File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(tab_id) = radio.get_active_editor_tab() {
+            let path = radio.read().editor_tab(tab_id).path.clone();
+            compare_with_disk(radio, path);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CompareWithHeadCommand(pub RadioAppState);
+
+impl CompareWithHeadCommand {
+    pub fn id() -> &'static str {
+        "compare-with-head"
+    }
+}
+
+impl EditorCommand for CompareWithHeadCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Compare With HEAD"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(tab_id) = radio.get_active_editor_tab() {
+            spawn(compare_with_head(radio, tab_id));
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CompareClipboardCommand(pub RadioAppState);
+
+impl CompareClipboardCommand {
+    pub fn id() -> &'static str {
+        "compare-clipboard-with-selection"
+    }
+}
+
+impl EditorCommand for CompareClipboardCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Compare Clipboard With Selection"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(tab_id) = radio.get_active_editor_tab() {
+            compare_clipboard_with_selection(radio, tab_id);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CompareWithCommand(pub RadioAppState);
+
+impl CompareWithCommand {
+    pub fn id() -> &'static str {
+        "compare-with"
+    }
+}
+
+impl EditorCommand for CompareWithCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Compare With…"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if let Some(tab_id) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::Global);
+            app_state.compare_with = Some(app_state.editor_tab(tab_id).path.clone());
+            app_state.focus_view(EditorView::FileSearch);
+            ctx.focus_previous_view = false;
+        }
+    }
+}
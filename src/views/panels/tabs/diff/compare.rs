@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    git::read_committed,
+    state::{Channel, RadioAppState, TabId},
+    system::read_clipboard,
+    views::{
+        notifications::notifications_state::Notification,
+        workspace_search::workspace_search_ui::display_path,
+    },
+};
+
+use super::DiffTab;
+
+fn notify_error(mut radio_app_state: RadioAppState, message: String) {
+    radio_app_state
+        .write_channel(Channel::Notifications)
+        .notify(Notification::error(message));
+}
+
+/// Title and content of a file, its path shown relative to the opened folders.
+async fn read_file(radio_app_state: RadioAppState, path: &Path) -> Option<(String, String)> {
+    let (transport, roots) = {
+        let app_state = radio_app_state.read();
+        let roots = app_state
+            .file_explorer
+            .folders
+            .iter()
+            .map(|item| item.path().clone())
+            .collect::<Vec<_>>();
+        (app_state.default_transport.clone(), roots)
+    };
+    match transport.read_to_string(path).await {
+        Ok(content) => Some((display_path(path, &roots), content)),
+        Err(err) => {
+            notify_error(
+                radio_app_state,
+                format!("Failed to read {}: {err}", path.display()),
+            );
+            None
+        }
+    }
+}
+
+/// Open the changes from the file `old` to the file `new`.
+pub async fn compare_files(mut radio_app_state: RadioAppState, old: PathBuf, new: PathBuf) {
+    let Some((old_title, old_text)) = read_file(radio_app_state, &old).await else {
+        return;
+    };
+    let Some((new_title, new_text)) = read_file(radio_app_state, &new).await else {
+        return;
+    };
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    DiffTab::open_with(
+        &mut app_state,
+        format!("{}:{}", old.display(), new.display()),
+        (old_title, &old_text),
+        (new_title, &new_text),
+    );
+}
+
+/// Open the changes of an editor tab since the last commit, its unsaved ones included.
+pub async fn compare_with_head(mut radio_app_state: RadioAppState, tab_id: TabId) {
+    let Some(path) = radio_app_state
+        .read()
+        .tabs
+        .get(&tab_id)
+        .and_then(|tab| tab.as_text_editor())
+        .map(|editor_tab| editor_tab.path.clone())
+    else {
+        return;
+    };
+    let Some(committed) = read_committed(&path).await else {
+        notify_error(
+            radio_app_state,
+            format!("{} isn't in the last commit", path.display()),
+        );
+        return;
+    };
+
+    // The tab might have been closed in the meantime
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    let Some(editor_tab) = app_state
+        .tabs
+        .get(&tab_id)
+        .and_then(|tab| tab.as_text_editor())
+    else {
+        return;
+    };
+    let file_name = editor_tab.file_name();
+    let buffer = editor_tab.data.rope.to_string();
+    DiffTab::open_with(
+        &mut app_state,
+        format!("head:{}", path.display()),
+        (format!("{file_name} (HEAD)"), &committed),
+        (file_name, &buffer),
+    );
+}
+
+/// Open the changes from the clipboard to the selection of an editor tab, or to all of
+/// its text when nothing is selected.
+pub fn compare_clipboard_with_selection(mut radio_app_state: RadioAppState, tab_id: TabId) {
+    let Some(clipboard) = read_clipboard() else {
+        notify_error(
+            radio_app_state,
+            "There is no text in the clipboard".to_string(),
+        );
+        return;
+    };
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    let Some(editor_tab) = app_state
+        .tabs
+        .get(&tab_id)
+        .and_then(|tab| tab.as_text_editor())
+    else {
+        return;
+    };
+    let sources = format!("clipboard:{}", editor_tab.path.display());
+    let file_name = editor_tab.file_name();
+    let (title, text) = match editor_tab.selected_text() {
+        Some(selection) => (format!("{file_name} (Selection)"), selection),
+        None => (file_name, editor_tab.data.rope.to_string()),
+    };
+    DiffTab::open_with(
+        &mut app_state,
+        sources,
+        ("Clipboard".to_string(), &clipboard),
+        (title, &text),
+    );
+}
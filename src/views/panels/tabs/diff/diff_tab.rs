@@ -0,0 +1,519 @@
+use std::{ops::Range, rc::Rc};
+
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    diff::{DiffLine, LineChange, SideBySideRow, diff_lines, inline_changes, side_by_side},
+    state::{
+        AppState, Channel, EditorCommands, KeyboardShortcuts, PanelTab, PanelTabData,
+        RadioAppState, TabId, TabProps,
+    },
+};
+
+use super::commands::{
+    CompareClipboardCommand, CompareWithCommand, CompareWithHeadCommand, CompareWithSavedCommand,
+    NextDifferenceCommand, PreviousDifferenceCommand, ToggleDiffLayoutCommand,
+};
+
+const ITEM_HEIGHT: f32 = 20.;
+/// Rows left above a difference scrolled to, so it isn't stuck to the top edge.
+const SCROLL_MARGIN: usize = 5;
+
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum DiffLayout {
+    #[default]
+    SideBySide,
+    Inline,
+}
+
+/// A line of the diff, with the chars changed inside of it when it replaces another line.
+#[derive(Clone, PartialEq)]
+struct ShownLine {
+    line: DiffLine,
+    highlights: Vec<Range<usize>>,
+}
+
+/// Shows the changes between two texts, side by side or inline.
+pub struct DiffTab {
+    id: TabId,
+    focus_id: AccessibilityId,
+    content_id: String,
+    title: String,
+    old_title: String,
+    new_title: String,
+    lines: Rc<Vec<ShownLine>>,
+    /// Indexes of `lines` shown on each row side by side.
+    rows: Rc<Vec<SideBySideRow>>,
+    layout: DiffLayout,
+    /// Row of the last difference navigated to.
+    difference: Option<usize>,
+    /// Row to scroll to once rendered.
+    scroll_to: Option<usize>,
+}
+
+impl PanelTab for DiffTab {
+    fn get_data(&self) -> PanelTabData {
+        PanelTabData {
+            id: self.id,
+            title: self.title.clone(),
+            edited: false,
+            focus_id: self.focus_id,
+            content_id: self.content_id.clone(),
+            icon: None,
+        }
+    }
+    fn render(&self) -> fn(&TabProps) -> Element {
+        render
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+/// The lines of the diff from `old` to `new` with the changes inside of the lines that
+/// replace others, and the rows to show them side by side.
+fn shown_lines(old: &str, new: &str) -> (Vec<ShownLine>, Vec<SideBySideRow>) {
+    let lines = diff_lines(old, new);
+    let rows = side_by_side(&lines);
+    let mut lines = lines
+        .into_iter()
+        .map(|line| ShownLine {
+            line,
+            highlights: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+    for &(old_index, new_index) in &rows {
+        if let (Some(old_index), Some(new_index)) = (old_index, new_index)
+            && old_index != new_index
+        {
+            let (old_highlights, new_highlights) =
+                inline_changes(&lines[old_index].line.text, &lines[new_index].line.text);
+            lines[old_index].highlights = old_highlights;
+            lines[new_index].highlights = new_highlights;
+        }
+    }
+    (lines, rows)
+}
+
+impl DiffTab {
+    /// Open the changes from `old` to `new`, each a title and a text. `sources` names
+    /// what is compared, like the full paths, so comparing the same sources again
+    /// refreshes the tab that's already open whatever their titles are.
+    pub fn open_with(
+        app_state: &mut AppState,
+        sources: String,
+        old: (String, &str),
+        new: (String, &str),
+    ) {
+        let (old_title, old_text) = old;
+        let (new_title, new_text) = new;
+        let content_id = format!("diff:{sources}");
+        let (lines, rows) = shown_lines(old_text, new_text);
+        let (lines, rows) = (Rc::new(lines), Rc::new(rows));
+
+        if let Some(tab_id) = app_state.find_tab_by_content_id(&content_id)
+            && let Some(tab) = app_state
+                .tab_mut(&tab_id)
+                .as_any_mut()
+                .downcast_mut::<Self>()
+        {
+            tab.lines = lines.clone();
+            tab.rows = rows.clone();
+            tab.difference = None;
+        }
+
+        // Focuses the tab instead if it's already open
+        let tab = Self {
+            id: TabId::new(),
+            focus_id: AccessibilityId::new_unique(),
+            title: format!("{old_title} ↔ {new_title}"),
+            content_id,
+            old_title,
+            new_title,
+            lines,
+            rows,
+            layout: DiffLayout::default(),
+            difference: None,
+            scroll_to: None,
+        };
+        app_state.push_tab(tab, app_state.focused_panel);
+    }
+
+    fn rows_len(&self) -> usize {
+        match self.layout {
+            DiffLayout::SideBySide => self.rows.len(),
+            DiffLayout::Inline => self.lines.len(),
+        }
+    }
+
+    /// Rows where each run of changed lines starts.
+    fn differences(&self) -> Vec<usize> {
+        let is_changed = match self.layout {
+            DiffLayout::SideBySide => self
+                .rows
+                .iter()
+                .map(|(old, new)| old.is_none() || old != new)
+                .collect::<Vec<_>>(),
+            DiffLayout::Inline => self
+                .lines
+                .iter()
+                .map(|shown| shown.line.change != LineChange::Equal)
+                .collect(),
+        };
+        (0..is_changed.len())
+            .filter(|&i| is_changed[i] && (i == 0 || !is_changed[i - 1]))
+            .collect()
+    }
+
+    /// Scroll to the next difference, or the previous one, wrapping around.
+    pub fn go_to_difference(&mut self, backwards: bool) {
+        let differences = self.differences();
+        let next = if backwards {
+            self.difference
+                .and_then(|current| differences.iter().rev().find(|row| **row < current))
+                .or(differences.last())
+        } else {
+            differences
+                .iter()
+                .find(|row| self.difference.is_none_or(|current| **row > current))
+                .or(differences.first())
+        };
+        self.difference = next.copied();
+        self.scroll_to = next.copied();
+    }
+
+    pub fn toggle_layout(&mut self) {
+        self.layout = match self.layout {
+            DiffLayout::SideBySide => DiffLayout::Inline,
+            DiffLayout::Inline => DiffLayout::SideBySide,
+        };
+        self.difference = None;
+    }
+
+    /// Initialize the DiffTab module.
+    pub fn init(
+        keyboard_shorcuts: &mut KeyboardShortcuts,
+        commands: &mut EditorCommands,
+        radio_app_state: RadioAppState,
+    ) {
+        // Register Commands
+        commands.register(NextDifferenceCommand(radio_app_state));
+        commands.register(PreviousDifferenceCommand(radio_app_state));
+        commands.register(ToggleDiffLayoutCommand(radio_app_state));
+        commands.register(CompareWithSavedCommand(radio_app_state));
+        commands.register(CompareWithHeadCommand(radio_app_state));
+        commands.register(CompareClipboardCommand(radio_app_state));
+        commands.register(CompareWithCommand(radio_app_state));
+
+        // Register Shortcuts
+        keyboard_shorcuts.register(
+            |data: &KeyboardEventData,
+             commands: &mut EditorCommands,
+             _radio_app_state: RadioAppState| {
+                match data.code {
+                    // Pressing `F7`
+                    Code::F7 if data.modifiers.is_empty() => {
+                        commands.trigger(NextDifferenceCommand::id());
+                    }
+                    // Pressing `Shift F7`
+                    Code::F7 if data.modifiers == Modifiers::SHIFT => {
+                        commands.trigger(PreviousDifferenceCommand::id());
+                    }
+                    _ => return false,
+                }
+                true
+            },
+        )
+    }
+}
+
+pub fn render(props: &TabProps) -> Element {
+    let tab_id = props.tab_id;
+    let mut radio_app_state = use_radio(Channel::follow_tab(tab_id));
+    let mut scroll_controller = use_scroll_controller(ScrollConfig::default);
+
+    // Scroll to the difference navigated to
+    use_side_effect(move || {
+        let scroll_to = radio_app_state
+            .read()
+            .tab(&tab_id)
+            .as_any()
+            .downcast_ref::<DiffTab>()
+            .and_then(|tab| tab.scroll_to);
+        if let Some(row) = scroll_to {
+            let y = row.saturating_sub(SCROLL_MARGIN) as f32 * ITEM_HEIGHT;
+            scroll_controller.scroll_to_y(-y as i32);
+            let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+            if let Some(tab) = app_state
+                .tab_mut(&tab_id)
+                .as_any_mut()
+                .downcast_mut::<DiffTab>()
+            {
+                tab.scroll_to = None;
+            }
+        }
+    });
+
+    let app_state = radio_app_state.read();
+    let Some(tab) = app_state.tab(&tab_id).as_any().downcast_ref::<DiffTab>() else {
+        return rect().into();
+    };
+    let count = |change: LineChange| {
+        tab.lines
+            .iter()
+            .filter(|shown| shown.line.change == change)
+            .count()
+    };
+    let (added, removed) = (count(LineChange::Added), count(LineChange::Removed));
+    let differences = tab.differences();
+    let position = tab
+        .difference
+        .and_then(|row| differences.iter().position(|difference| *difference == row))
+        .map(|i| format!("{} of ", i + 1))
+        .unwrap_or_default();
+    let header = format!(
+        "{} ↔ {}, +{added} −{removed}, {position}{} differences",
+        tab.old_title,
+        tab.new_title,
+        differences.len()
+    );
+    let layout = tab.layout;
+    let data = (tab.lines.clone(), tab.rows.clone(), layout);
+    let rows_len = tab.rows_len();
+    drop(app_state);
+
+    let mut with_tab = move |action: fn(&mut DiffTab)| {
+        let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+        if let Some(tab) = app_state
+            .tab_mut(&tab_id)
+            .as_any_mut()
+            .downcast_mut::<DiffTab>()
+        {
+            action(tab);
+        }
+    };
+    let layout_text = match layout {
+        DiffLayout::SideBySide => "Inline",
+        DiffLayout::Inline => "Side by Side",
+    };
+
+    rect()
+        .expanded()
+        .content(Content::Flex)
+        .background((13, 17, 23))
+        .child(
+            rect()
+                .horizontal()
+                .width(Size::fill())
+                .cross_align(Alignment::Center)
+                .content(Content::Flex)
+                .padding((4., 12.))
+                .spacing(4.)
+                .child(
+                    label()
+                        .width(Size::flex(1.))
+                        .max_lines(1)
+                        .text_overflow(TextOverflow::Ellipsis)
+                        .color((125, 133, 144))
+                        .font_size(12.)
+                        .text(header),
+                )
+                .child(
+                    Button::new()
+                        .flat()
+                        .compact()
+                        .on_press(move |_| with_tab(|tab| tab.go_to_difference(true)))
+                        .child("↑"),
+                )
+                .child(
+                    Button::new()
+                        .flat()
+                        .compact()
+                        .on_press(move |_| with_tab(|tab| tab.go_to_difference(false)))
+                        .child("↓"),
+                )
+                .child(
+                    Button::new()
+                        .flat()
+                        .compact()
+                        .on_press(move |_| with_tab(DiffTab::toggle_layout))
+                        .child(layout_text),
+                ),
+        )
+        .child(
+            rect().width(Size::fill()).height(Size::flex(1.)).child(
+                VirtualScrollView::new_with_data_controlled(
+                    data,
+                    diff_row_builder,
+                    scroll_controller,
+                )
+                .length(rows_len)
+                .item_size(ITEM_HEIGHT),
+            ),
+        )
+        .into()
+}
+
+fn line_colors(change: LineChange) -> (Color, Color, Color) {
+    match change {
+        LineChange::Equal => (
+            (230, 237, 243).into(),
+            Color::TRANSPARENT,
+            Color::TRANSPARENT,
+        ),
+        LineChange::Added => (
+            (63, 185, 80).into(),
+            (63, 185, 80, 0.1).into(),
+            (63, 185, 80, 0.35).into(),
+        ),
+        LineChange::Removed => (
+            (248, 81, 73).into(),
+            (248, 81, 73, 0.1).into(),
+            (248, 81, 73, 0.35).into(),
+        ),
+    }
+}
+
+/// The text of a line, with a stronger background behind the changed chars.
+fn highlighted_text(
+    text: &str,
+    highlights: &[Range<usize>],
+    color: Color,
+    highlight: Color,
+) -> Element {
+    if highlights.is_empty() {
+        return label()
+            .max_lines(1)
+            .color(color)
+            .text(text.to_string())
+            .into();
+    }
+    let chars = text.chars().collect::<Vec<_>>();
+    let segment = |range: Range<usize>| chars[range].iter().collect::<String>();
+    let mut segments = Vec::new();
+    let mut position = 0;
+    for range in highlights {
+        if position < range.start {
+            segments.push(
+                label()
+                    .color(color)
+                    .text(segment(position..range.start))
+                    .into(),
+            );
+        }
+        segments.push(
+            rect()
+                .background(highlight)
+                .corner_radius(2.)
+                .child(label().color(color).text(segment(range.clone())))
+                .into(),
+        );
+        position = range.end;
+    }
+    if position < chars.len() {
+        segments.push(
+            label()
+                .color(color)
+                .text(segment(position..chars.len()))
+                .into(),
+        );
+    }
+    rect().horizontal().children(segments).into()
+}
+
+fn line_number(line: Option<usize>) -> Element {
+    label()
+        .width(Size::px(48.))
+        .color((125, 133, 144))
+        .text(line.map(|line| (line + 1).to_string()).unwrap_or_default())
+        .into()
+}
+
+/// One side of a row side by side, blank where the other side has lines this one doesn't.
+fn diff_side(shown: Option<&ShownLine>, is_old: bool) -> Element {
+    let Some(shown) = shown else {
+        return rect()
+            .width(Size::flex(1.))
+            .height(Size::fill())
+            .background((110, 118, 129, 0.05))
+            .into();
+    };
+    let (color, background, highlight) = line_colors(shown.line.change);
+    let number = if is_old {
+        shown.line.old_line
+    } else {
+        shown.line.new_line
+    };
+    rect()
+        .horizontal()
+        .width(Size::flex(1.))
+        .height(Size::fill())
+        .cross_align(Alignment::Center)
+        .overflow(Overflow::Clip)
+        .background(background)
+        .child(line_number(number))
+        .child(highlighted_text(
+            &shown.line.text,
+            &shown.highlights,
+            color,
+            highlight,
+        ))
+        .into()
+}
+
+fn diff_row_builder(
+    index: usize,
+    (lines, rows, layout): &(Rc<Vec<ShownLine>>, Rc<Vec<SideBySideRow>>, DiffLayout),
+) -> Element {
+    let row = rect()
+        .horizontal()
+        .width(Size::fill())
+        .height(Size::px(ITEM_HEIGHT))
+        .cross_align(Alignment::Center)
+        .font_size(13.);
+
+    match layout {
+        DiffLayout::SideBySide => {
+            let (old, new) = rows[index];
+            row.content(Content::Flex)
+                .padding((0., 12.))
+                .spacing(12.)
+                .child(diff_side(old.map(|i| &lines[i]), true))
+                .child(
+                    rect()
+                        .width(Size::px(1.))
+                        .height(Size::fill())
+                        .background((48, 54, 61)),
+                )
+                .child(diff_side(new.map(|i| &lines[i]), false))
+                .into()
+        }
+        DiffLayout::Inline => {
+            let shown = &lines[index];
+            let (color, background, highlight) = line_colors(shown.line.change);
+            let prefix = match shown.line.change {
+                LineChange::Equal => " ",
+                LineChange::Added => "+",
+                LineChange::Removed => "-",
+            };
+            row.padding((0., 12.))
+                .background(background)
+                .child(line_number(shown.line.old_line))
+                .child(line_number(shown.line.new_line))
+                .child(label().width(Size::px(16.)).color(color).text(prefix))
+                .child(highlighted_text(
+                    &shown.line.text,
+                    &shown.highlights,
+                    color,
+                    highlight,
+                ))
+                .into()
+        }
+    }
+}
@@ -0,0 +1,6 @@
+mod commands;
+mod compare;
+mod diff_tab;
+
+pub use compare::{compare_clipboard_with_selection, compare_files, compare_with_head};
+pub use diff_tab::*;
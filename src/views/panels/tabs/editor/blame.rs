@@ -0,0 +1,409 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use freya::prelude::*;
+use freya::radio::use_radio;
+use freya::text_edit::TextEditor;
+
+use crate::{
+    git::{Blame, BlameCommit, commit_message, read_blame, read_revision},
+    state::{AppState, Channel, RadioAppState, TabId},
+    views::{notifications::notifications_state::Notification, panels::tabs::diff::DiffTab},
+};
+
+use super::{AppStateEditorUtils, editor_view::editor_scroll_controller};
+
+/// Width of the author, date and summary shown before each line.
+const GUTTER_WIDTH: f32 = 340.;
+
+/// Who last changed each line of an editor tab, shown beside the editor.
+pub struct BlameState {
+    pub blame: Blame,
+    /// Full messages of the commits, read once they are asked for.
+    pub messages: HashMap<String, String>,
+    /// Index of the commit whose details are shown.
+    pub popup: Option<usize>,
+}
+
+/// `YYYY-MM-DD` of a Unix time, in UTC.
+fn format_date(time: i64) -> String {
+    // Days since the epoch to a civil date, from Howard Hinnant's `civil_from_days`
+    let z = time.div_euclid(86_400) + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Roughly how long ago a Unix time was.
+fn time_ago(time: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(time);
+    let seconds = (now - time).max(0);
+    let (amount, unit) = match seconds {
+        0..60 => return "just now".to_string(),
+        60..3_600 => (seconds / 60, "minute"),
+        3_600..86_400 => (seconds / 3_600, "hour"),
+        86_400..2_592_000 => (seconds / 86_400, "day"),
+        2_592_000..31_536_000 => (seconds / 2_592_000, "month"),
+        _ => (seconds / 31_536_000, "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{amount} {unit}{plural} ago")
+}
+
+fn notify_error(mut radio_app_state: RadioAppState, message: String) {
+    radio_app_state
+        .write_channel(Channel::Notifications)
+        .notify(Notification::error(message));
+}
+
+/// Blame the lines of a tab as they are now, unsaved changes included.
+pub async fn load_blame(mut radio_app_state: RadioAppState, tab_id: TabId) {
+    let Some((path, content)) = radio_app_state
+        .read()
+        .tabs
+        .get(&tab_id)
+        .and_then(|tab| tab.as_text_editor())
+        .map(|editor_tab| (editor_tab.path.clone(), editor_tab.data.rope.to_string()))
+    else {
+        return;
+    };
+
+    match read_blame(&path, &content).await {
+        Ok(blame) => {
+            let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+            if let Some(editor_tab) = app_state.tab_mut(&tab_id).as_text_editor_mut() {
+                // Reloading keeps the messages already read and the commit shown
+                let previous = editor_tab.blame.take();
+                let popup = previous.as_ref().and_then(|previous| {
+                    let hash = &previous.blame.commits.get(previous.popup?)?.hash;
+                    blame.commits.iter().position(|commit| &commit.hash == hash)
+                });
+                editor_tab.blame = Some(BlameState {
+                    blame,
+                    messages: previous
+                        .map(|previous| previous.messages)
+                        .unwrap_or_default(),
+                    popup,
+                });
+            }
+        }
+        Err(err) => notify_error(
+            radio_app_state,
+            format!(
+                "Failed to blame {}: {err}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+        ),
+    }
+}
+
+/// Show the details of a commit of the blame of a tab, reading its full message.
+pub async fn show_commit(mut radio_app_state: RadioAppState, tab_id: TabId, index: usize) {
+    let (root, hash, is_read) = {
+        let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+        let Some(blame_state) = app_state.editor_tab_mut(tab_id).blame.as_mut() else {
+            return;
+        };
+        let Some(commit) = blame_state.blame.commits.get(index) else {
+            return;
+        };
+        let (root, hash) = (blame_state.blame.root.clone(), commit.hash.clone());
+        let is_read = !commit.is_committed() || blame_state.messages.contains_key(&hash);
+        blame_state.popup = Some(index);
+        (root, hash, is_read)
+    };
+    if is_read {
+        return;
+    }
+
+    match commit_message(&root, &hash).await {
+        Ok(message) => {
+            let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+            if let Some(blame_state) = app_state
+                .tab_mut(&tab_id)
+                .as_text_editor_mut()
+                .and_then(|editor_tab| editor_tab.blame.as_mut())
+            {
+                blame_state.messages.insert(hash, message);
+            }
+        }
+        Err(err) => notify_error(
+            radio_app_state,
+            format!("Failed to read the commit {hash}: {err}"),
+        ),
+    }
+}
+
+/// Open the changes a commit made to the file it was blamed on.
+pub async fn open_commit_diff(
+    mut radio_app_state: RadioAppState,
+    root: PathBuf,
+    commit: BlameCommit,
+) {
+    if !commit.is_committed() {
+        notify_error(radio_app_state, "This line isn't committed yet".to_string());
+        return;
+    }
+    let (old_title, old) = match &commit.previous {
+        Some((hash, path)) => (
+            format!("{path} ({})", &hash[..hash.len().min(8)]),
+            read_revision(&root, hash, path).await,
+        ),
+        None => (format!("{} (new file)", commit.path), None),
+    };
+    let new = read_revision(&root, &commit.hash, &commit.path).await;
+
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    DiffTab::open_with(
+        &mut app_state,
+        format!(
+            "commit:{}:{}",
+            commit.hash,
+            root.join(&commit.path).display()
+        ),
+        (old_title, old.as_deref().unwrap_or_default()),
+        (
+            format!("{} ({})", commit.path, commit.short_hash()),
+            new.as_deref().unwrap_or_default(),
+        ),
+    );
+}
+
+/// Commit that last changed the line under the cursor of a tab, if its blame is shown.
+pub fn commit_at_cursor(app_state: &AppState, tab_id: TabId) -> Option<(PathBuf, BlameCommit)> {
+    let editor_tab = app_state.editor_tab(tab_id);
+    let blame = &editor_tab.blame.as_ref()?.blame;
+    let commit = blame.line(editor_tab.data.cursor_row())?;
+    Some((blame.root.clone(), commit.clone()))
+}
+
+/// The commit that last changed each line of an editor tab, in a column beside the
+/// editor that scrolls along with it.
+#[derive(Clone, PartialEq)]
+pub struct BlameColumn {
+    pub tab_id: TabId,
+}
+
+impl Component for BlameColumn {
+    fn render(&self) -> impl IntoElement {
+        let tab_id = self.tab_id;
+        let radio_app_state = use_radio::<AppState, Channel>(Channel::follow_tab(tab_id));
+        let scroll_controller =
+            use_hook(|| editor_scroll_controller(radio_app_state, tab_id, false));
+
+        let (lines_len, font_size, item_height) = {
+            let app_state = radio_app_state.read();
+            let editor_tab = app_state.editor_tab(tab_id);
+            (
+                editor_tab.data.rope.len_lines(),
+                app_state.font_size(),
+                // Same height as the lines of the editor, so they stay side by side
+                (app_state.font_size() * app_state.line_height()).floor(),
+            )
+        };
+
+        rect()
+            .width(Size::px(GUTTER_WIDTH + 1.))
+            .height(Size::fill())
+            .horizontal()
+            .background((13, 17, 23))
+            .font_size(font_size)
+            .font_family("Jetbrains Mono")
+            .child(
+                VirtualScrollView::new_with_data_controlled(
+                    (radio_app_state, tab_id, item_height),
+                    blame_row_builder,
+                    scroll_controller,
+                )
+                .width(Size::px(GUTTER_WIDTH))
+                .show_scrollbar(false)
+                .length(lines_len)
+                .item_size(item_height),
+            )
+            .child(
+                rect()
+                    .width(Size::px(1.))
+                    .height(Size::fill())
+                    .background((48, 54, 61)),
+            )
+    }
+}
+
+fn blame_row_builder(
+    index: usize,
+    (radio_app_state, tab_id, item_height): &(RadioAppState, TabId, f32),
+) -> Element {
+    let (radio_app_state, tab_id) = (*radio_app_state, *tab_id);
+    let app_state = radio_app_state.read();
+    let editor_tab = app_state.editor_tab(tab_id);
+    let Some(blame) = editor_tab.blame.as_ref().map(|blame| &blame.blame) else {
+        return rect().into();
+    };
+    let is_cursor_row = editor_tab.data.cursor_row() == index;
+    let commit = blame.line(index);
+    // Only the first line of each run of lines of the same commit is labeled
+    let is_first = index == 0 || blame.lines.get(index - 1) != blame.lines.get(index);
+
+    let gutter = match commit.filter(|_| is_first) {
+        Some(commit) if commit.is_committed() => {
+            let commit_index = blame.lines[index];
+            rect()
+                .horizontal()
+                .width(Size::fill())
+                .height(Size::fill())
+                .cross_align(Alignment::Center)
+                .content(Content::Flex)
+                .spacing(8.)
+                .padding((0., 8.))
+                .font_size(12.)
+                .color((125, 133, 144))
+                .on_press(move |_| {
+                    spawn(show_commit(radio_app_state, tab_id, commit_index));
+                })
+                .child(
+                    label()
+                        .width(Size::px(100.))
+                        .max_lines(1)
+                        .text_overflow(TextOverflow::Ellipsis)
+                        .text(commit.author.clone()),
+                )
+                .child(label().width(Size::px(76.)).text(format_date(commit.time)))
+                .child(
+                    label()
+                        .width(Size::flex(1.))
+                        .max_lines(1)
+                        .text_overflow(TextOverflow::Ellipsis)
+                        .color((230, 237, 243))
+                        .text(commit.summary.clone()),
+                )
+        }
+        Some(_) => rect()
+            .width(Size::fill())
+            .height(Size::fill())
+            .main_align(Alignment::Center)
+            .padding((0., 8.))
+            .font_size(12.)
+            .color((125, 133, 144))
+            .child(label().text("Not Committed Yet")),
+        None => rect().width(Size::fill()),
+    };
+    let background = if is_cursor_row {
+        Color::from((110, 118, 129, 0.15))
+    } else {
+        Color::TRANSPARENT
+    };
+    drop(app_state);
+
+    rect()
+        .width(Size::fill())
+        .height(Size::px(*item_height))
+        .background(background)
+        .child(gutter)
+        .into()
+}
+
+/// Details of a commit of the blame, below the editor.
+#[derive(Clone, PartialEq)]
+pub struct CommitPopup {
+    pub tab_id: TabId,
+    pub index: usize,
+}
+
+impl Component for CommitPopup {
+    fn render(&self) -> impl IntoElement {
+        let (tab_id, index) = (self.tab_id, self.index);
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::follow_tab(tab_id));
+
+        let (root, commit, message) = {
+            let app_state = radio_app_state.read();
+            let Some(blame_state) = app_state.editor_tab(tab_id).blame.as_ref() else {
+                return rect().into_element();
+            };
+            let Some(commit) = blame_state.blame.commits.get(index).cloned() else {
+                return rect().into_element();
+            };
+            let message = blame_state
+                .messages
+                .get(&commit.hash)
+                .cloned()
+                .unwrap_or_else(|| commit.summary.clone());
+            (blame_state.blame.root.clone(), commit, message)
+        };
+        let title = format!(
+            "{} · {} · {} ({})",
+            commit.short_hash(),
+            commit.author,
+            format_date(commit.time),
+            time_ago(commit.time)
+        );
+
+        let on_open_diff = {
+            let commit = commit.clone();
+            move |_| {
+                spawn(open_commit_diff(
+                    radio_app_state,
+                    root.clone(),
+                    commit.clone(),
+                ));
+            }
+        };
+
+        let on_close = move |_| {
+            let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+            if let Some(blame_state) = app_state.editor_tab_mut(tab_id).blame.as_mut() {
+                blame_state.popup = None;
+            }
+        };
+
+        rect()
+            .width(Size::fill())
+            .padding((4., 8.))
+            .spacing(4.)
+            .background((22, 27, 34))
+            .color((230, 237, 243))
+            .font_size(13.)
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .spacing(4.)
+                    .child(
+                        label()
+                            .width(Size::flex(1.))
+                            .max_lines(1)
+                            .text_overflow(TextOverflow::Ellipsis)
+                            .color((125, 133, 144))
+                            .text(title),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_open_diff)
+                            .child("Open Diff"),
+                    )
+                    .child(Button::new().flat().compact().on_press(on_close).child("✕")),
+            )
+            .child(label().max_lines(12).text(message))
+            .into_element()
+    }
+}
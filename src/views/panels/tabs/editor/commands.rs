@@ -1,10 +1,25 @@
-use freya::prelude::spawn;
-use smol::{fs::OpenOptions, io::AsyncWriteExt};
+use freya::prelude::{AccessibilityIdExt, spawn};
+use std::io;
 
-use crate::state::{AppStateUtils, Channel, CommandRunContext, EditorCommand, RadioAppState};
+use crate::backup::forget_backup;
+use crate::file_watcher::content_hash;
+use crate::state::{
+    AppStateUtils, Channel, CommandRunContext, EditorCommand, EditorView, RadioAppState, TabId,
+};
+use crate::system::copy_to_clipboard;
 use freya::code_editor::{BASE_FONT_SIZE, MAX_FONT_SIZE};
 
-use crate::views::panels::tabs::editor::utils::AppStateEditorUtils;
+use crate::views::{
+    notifications::notifications_state::Notification,
+    panels::tabs::editor::{
+        EditorTab,
+        blame::{commit_at_cursor, load_blame, open_commit_diff},
+        completion::CompletionTrigger,
+        git_changes::stage_change,
+        utils::{AppStateEditorUtils, RadioEditorUtils},
+    },
+    references::navigation::{find_references, go_to_definition, peek_definition},
+};
 
 #[derive(Clone)]
 pub struct IncreaseFontSizeCommand(pub RadioAppState);
@@ -88,28 +103,623 @@ impl EditorCommand for SaveFileCommand {
         "Save File"
     }
 
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(active_tab) = radio.get_active_tab() {
+            spawn_save_editor_tab(radio, active_tab);
+        }
+    }
+}
+
+fn spawn_save_editor_tab(radio: RadioAppState, tab_id: TabId) {
+    spawn(async move {
+        let _ = save_editor_tab(radio, tab_id).await;
+    });
+}
+
+/// Write the content of an editor tab to its file. If it fails the tab stays edited
+/// and the user is notified.
+pub async fn save_editor_tab(mut radio: RadioAppState, tab_id: TabId) -> io::Result<()> {
+    let Some((file_path, rope, transport)) = radio.read().editor_tab_data(tab_id) else {
+        return Ok(());
+    };
+    let bytes: Vec<u8> = rope.bytes().collect();
+    if let Err(err) = transport.write_atomic(&file_path, &bytes).await {
+        radio.write_channel(Channel::Notifications).notify(
+            Notification::error(format!("Failed to save {}: {err}", file_path.display()))
+                .action("Retry", move |radio| spawn_save_editor_tab(radio, tab_id)),
+        );
+        return Err(err);
+    }
+    let mut app_state = radio.write_channel(Channel::follow_tab(tab_id));
+    // The tab might have been closed while writing
+    let Some(editor_tab) = app_state
+        .tabs
+        .get_mut(&tab_id)
+        .and_then(|tab| tab.as_text_editor_mut())
+    else {
+        return Ok(());
+    };
+    // Edits made while writing aren't in the file yet, so the tab stays edited
+    let is_saved = editor_tab.data.rope == rope;
+    if is_saved {
+        editor_tab.data.mark_as_saved();
+    }
+    editor_tab.disk_hash = content_hash(rope.chunks());
+    let current = editor_tab.data.rope.clone();
+    if is_saved {
+        forget_backup(&app_state, &file_path);
+    }
+    app_state.lsp.did_save(&file_path, &current);
+    app_state.git.request_refresh(&file_path);
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct FindCommand(pub RadioAppState);
+
+impl FindCommand {
+    pub fn id() -> &'static str {
+        "find-in-file"
+    }
+}
+
+impl EditorCommand for FindCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Find in File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            app_state.editor_tab_mut(active_tab).open_find(false);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ReplaceCommand(pub RadioAppState);
+
+impl ReplaceCommand {
+    pub fn id() -> &'static str {
+        "replace-in-file"
+    }
+}
+
+impl EditorCommand for ReplaceCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Replace in File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            app_state.editor_tab_mut(active_tab).open_find(true);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FindNextCommand(pub RadioAppState);
+
+impl FindNextCommand {
+    pub fn id() -> &'static str {
+        "find-next"
+    }
+}
+
+impl EditorCommand for FindNextCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Find Next"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            app_state.editor_tab_mut(active_tab).find_next();
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FindPreviousCommand(pub RadioAppState);
+
+impl FindPreviousCommand {
+    pub fn id() -> &'static str {
+        "find-previous"
+    }
+}
+
+impl EditorCommand for FindPreviousCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Find Previous"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            app_state.editor_tab_mut(active_tab).find_previous();
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ReplaceAllCommand(pub RadioAppState);
+
+impl ReplaceAllCommand {
+    pub fn id() -> &'static str {
+        "replace-all-in-file"
+    }
+}
+
+impl EditorCommand for ReplaceAllCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Replace All in File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            let font_size = app_state.font_size();
+            app_state.editor_tab_mut(active_tab).replace_all(font_size);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TriggerCompletionCommand(pub RadioAppState);
+
+impl TriggerCompletionCommand {
+    pub fn id() -> &'static str {
+        "trigger-completion"
+    }
+}
+
+impl EditorCommand for TriggerCompletionCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Trigger Suggest"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            EditorTab::refresh_completion(radio, active_tab, CompletionTrigger::Invoked);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GoToDefinitionCommand(pub RadioAppState);
+
+impl GoToDefinitionCommand {
+    pub fn id() -> &'static str {
+        "go-to-definition"
+    }
+}
+
+impl EditorCommand for GoToDefinitionCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Go to Definition"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            go_to_definition(radio, active_tab);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PeekDefinitionCommand(pub RadioAppState);
+
+impl PeekDefinitionCommand {
+    pub fn id() -> &'static str {
+        "peek-definition"
+    }
+}
+
+impl EditorCommand for PeekDefinitionCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Peek Definition"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            peek_definition(radio, active_tab);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FindReferencesCommand(pub RadioAppState);
+
+impl FindReferencesCommand {
+    pub fn id() -> &'static str {
+        "find-references"
+    }
+}
+
+impl EditorCommand for FindReferencesCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Find All References"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            find_references(radio, active_tab);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GoToLineCommand(pub RadioAppState);
+
+impl GoToLineCommand {
+    pub fn id() -> &'static str {
+        "go-to-line"
+    }
+}
+
+impl EditorCommand for GoToLineCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Go to Line"
+    }
+
+    fn run(&self, ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if radio.get_active_editor_tab().is_some() {
+            let mut app_state = radio.write_channel(Channel::Global);
+            app_state.focus_view(EditorView::GoToLine);
+            ctx.focus_previous_view = false;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CopyActiveFilePathCommand(pub RadioAppState);
+
+impl CopyActiveFilePathCommand {
+    pub fn id() -> &'static str {
+        "copy-active-file-path"
+    }
+}
+
+impl EditorCommand for CopyActiveFilePathCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Copy Path of Active File"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(tab_id) = radio.get_active_editor_tab() {
+            let path = radio.read().editor_tab(tab_id).path.clone();
+            copy_to_clipboard(path.to_string_lossy().into_owned());
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct NextChangeCommand(pub RadioAppState);
+
+impl NextChangeCommand {
+    pub fn id() -> &'static str {
+        "go-to-next-change"
+    }
+}
+
+impl EditorCommand for NextChangeCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Go to Next Change"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            app_state.editor_tab_mut(active_tab).go_to_change(false);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PreviousChangeCommand(pub RadioAppState);
+
+impl PreviousChangeCommand {
+    pub fn id() -> &'static str {
+        "go-to-previous-change"
+    }
+}
+
+impl EditorCommand for PreviousChangeCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Go to Previous Change"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            app_state.editor_tab_mut(active_tab).go_to_change(true);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PreviewChangeCommand(pub RadioAppState);
+
+impl PreviewChangeCommand {
+    pub fn id() -> &'static str {
+        "preview-change"
+    }
+}
+
+impl EditorCommand for PreviewChangeCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Toggle Change Preview"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            let editor_tab = app_state.editor_tab_mut(active_tab);
+            editor_tab.show_change_preview = !editor_tab.show_change_preview;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RevertChangeCommand(pub RadioAppState);
+
+impl RevertChangeCommand {
+    pub fn id() -> &'static str {
+        "revert-change"
+    }
+}
+
+impl EditorCommand for RevertChangeCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Revert Change"
+    }
+
     fn run(&self, _ctx: &mut CommandRunContext) {
         let mut radio = self.0;
-        let active_tab = radio.get_active_tab();
-
-        if let Some(active_tab) = active_tab {
-            let editor_data = radio.read().editor_tab_data(active_tab);
-
-            if let Some((file_path, rope, transport)) = editor_data {
-                spawn(async move {
-                    let bytes: Vec<u8> = rope.bytes().collect();
-                    let new_file_size = bytes.len() as u64;
-                    let mut options = OpenOptions::new();
-                    options.write(true).create(true);
-                    let mut writer = transport.open(&file_path, &mut options).await.unwrap();
-                    writer.write_all(&bytes).await.unwrap();
-                    writer.set_len(new_file_size).await.unwrap();
-                    writer.sync_all().await.unwrap();
-                    let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
-                    let editor_tab = app_state.editor_tab_mut(active_tab);
-                    editor_tab.data.mark_as_saved();
-                });
-            }
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            let font_size = app_state.font_size();
+            app_state
+                .editor_tab_mut(active_tab)
+                .revert_change(font_size);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct StageChangeCommand(pub RadioAppState);
+
+impl StageChangeCommand {
+    pub fn id() -> &'static str {
+        "stage-change"
+    }
+}
+
+impl EditorCommand for StageChangeCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Stage Change"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        if let Some(active_tab) = radio.get_active_editor_tab() {
+            spawn(stage_change(radio, active_tab));
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ToggleBlameCommand(pub RadioAppState);
+
+impl ToggleBlameCommand {
+    pub fn id() -> &'static str {
+        "toggle-blame"
+    }
+}
+
+impl EditorCommand for ToggleBlameCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Toggle Blame"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let mut radio = self.0;
+        let Some(active_tab) = radio.get_active_editor_tab() else {
+            return;
+        };
+        if radio.read().editor_tab(active_tab).blame.is_none() {
+            spawn(load_blame(radio, active_tab));
+        } else {
+            let mut app_state = radio.write_channel(Channel::follow_tab(active_tab));
+            let editor_tab = app_state.editor_tab_mut(active_tab);
+            editor_tab.blame = None;
+            editor_tab.focus_id.request_focus();
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OpenCommitDiffCommand(pub RadioAppState);
+
+impl OpenCommitDiffCommand {
+    pub fn id() -> &'static str {
+        "open-commit-diff"
+    }
+}
+
+impl EditorCommand for OpenCommitDiffCommand {
+    fn matches(&self, input: &str) -> bool {
+        self.text().to_lowercase().contains(&input.to_lowercase())
+    }
+
+    fn id(&self) -> &str {
+        Self::id()
+    }
+
+    fn text(&self) -> &str {
+        "Open Diff of Line Commit"
+    }
+
+    fn run(&self, _ctx: &mut CommandRunContext) {
+        let radio = self.0;
+        let Some(active_tab) = radio.get_active_editor_tab() else {
+            return;
+        };
+        let commit = commit_at_cursor(&radio.read(), active_tab);
+        if let Some((root, commit)) = commit {
+            spawn(open_commit_diff(radio, root, commit));
         }
     }
 }
@@ -0,0 +1,385 @@
+use std::{collections::HashSet, ops::Range};
+
+use freya::code_editor::Rope;
+
+/// Most items shown at once, the rest are reached by scrolling the selection.
+pub const MAX_VISIBLE_ITEMS: usize = 10;
+/// Stop collecting buffer words once this many were found.
+const MAX_WORDS: usize = 5_000;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CompletionItem {
+    pub label: String,
+    /// Text matched against the typed prefix.
+    pub filter_text: String,
+    /// Text used to order items with the same score.
+    pub sort_text: String,
+    pub detail: Option<String>,
+    pub documentation: Option<String>,
+    pub insert_text: String,
+    /// Whether `insert_text` has `$1`/`${1:placeholder}` tab stops.
+    pub is_snippet: bool,
+}
+
+impl CompletionItem {
+    pub fn word(word: &str) -> Self {
+        Self {
+            label: word.to_string(),
+            filter_text: word.to_string(),
+            sort_text: word.to_string(),
+            detail: None,
+            documentation: None,
+            insert_text: word.to_string(),
+            is_snippet: false,
+        }
+    }
+}
+
+/// What caused the completion to be refreshed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CompletionTrigger {
+    /// The cursor moved or text was removed, only update an opened popup.
+    Update,
+    /// A character was typed.
+    Typing,
+    /// Explicitly asked for, e.g. with `Ctrl Space`.
+    Invoked,
+}
+
+/// The completion popup of an editor.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CompletionState {
+    /// Char index where the completed word starts.
+    pub start: usize,
+    pub query: String,
+    items: Vec<CompletionItem>,
+    /// Indexes of `items` matching `query`, best first.
+    filtered: Vec<usize>,
+    pub selected: usize,
+    /// Waiting for the language server to answer.
+    pub is_loading: bool,
+}
+
+impl CompletionState {
+    pub fn new(start: usize, query: String, items: Vec<CompletionItem>) -> Self {
+        let mut state = Self {
+            start,
+            query,
+            items,
+            filtered: Vec::new(),
+            selected: 0,
+            is_loading: false,
+        };
+        state.filter();
+        state
+    }
+
+    /// Put the language server items first, keeping the buffer words it didn't suggest.
+    pub fn set_provider_items(&mut self, items: Vec<CompletionItem>) {
+        let labels = items
+            .iter()
+            .map(|item| item.label.clone())
+            .collect::<HashSet<_>>();
+        let words = self
+            .items
+            .drain(..)
+            .filter(|item| !labels.contains(&item.label));
+        self.items = items.into_iter().chain(words).collect();
+        self.is_loading = false;
+        self.filter();
+    }
+
+    pub fn set_query(&mut self, query: String) {
+        if self.query != query {
+            self.query = query;
+            self.filter();
+        }
+    }
+
+    fn filter(&mut self) {
+        let mut scored = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| Some((fuzzy_score(&self.query, &item.filter_text)?, i)))
+            .collect::<Vec<_>>();
+        scored.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| self.items[*a].sort_text.cmp(&self.items[*b].sort_text))
+        });
+        self.filtered = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filtered.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.filtered.len()
+    }
+
+    pub fn item(&self, index: usize) -> Option<&CompletionItem> {
+        self.items.get(*self.filtered.get(index)?)
+    }
+
+    pub fn selected_item(&self) -> Option<&CompletionItem> {
+        self.item(self.selected)
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.is_empty() {
+            self.selected = (self.selected + 1) % self.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.is_empty() {
+            self.selected = (self.selected + self.len() - 1) % self.len();
+        }
+    }
+
+    /// Range of filtered indexes to show, keeping the selected one visible.
+    pub fn visible_range(&self) -> Range<usize> {
+        let start = self
+            .selected
+            .saturating_sub(MAX_VISIBLE_ITEMS - 1)
+            .min(self.len().saturating_sub(MAX_VISIBLE_ITEMS));
+        start..(start + MAX_VISIBLE_ITEMS).min(self.len())
+    }
+}
+
+/// Tab stops left to visit after inserting a snippet.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SnippetSession {
+    /// Char ranges of the stops in the rope, `$0` last.
+    stops: Vec<Range<usize>>,
+    current: usize,
+    /// Length of the rope when the current stop was selected, to shift
+    /// the next stops by whatever was typed in it.
+    len_chars: usize,
+}
+
+impl SnippetSession {
+    /// Start a session from stops relative to `offset`, returning the first stop.
+    pub fn new(
+        offset: usize,
+        stops: Vec<Range<usize>>,
+        len_chars: usize,
+    ) -> Option<(Self, Range<usize>)> {
+        let stops = stops
+            .into_iter()
+            .map(|stop| offset + stop.start..offset + stop.end)
+            .collect::<Vec<_>>();
+        let first = stops.first()?.clone();
+        Some((
+            Self {
+                stops,
+                current: 0,
+                len_chars,
+            },
+            first,
+        ))
+    }
+
+    /// Whether the current stop is the last one.
+    pub fn is_finished(&self) -> bool {
+        self.current + 1 >= self.stops.len()
+    }
+
+    /// The next stop, if any is left. Whatever was typed in the current stop moves the
+    /// stops after it, and the end of those around it.
+    pub fn next(&mut self, len_chars: usize) -> Option<Range<usize>> {
+        let delta = len_chars as isize - self.len_chars as isize;
+        self.len_chars = len_chars;
+        let edited_end = self.stops.get(self.current)?.end;
+        for stop in &mut self.stops {
+            if stop.start >= edited_end {
+                stop.start = stop.start.saturating_add_signed(delta);
+            }
+            if stop.end >= edited_end {
+                stop.end = stop.end.saturating_add_signed(delta);
+            }
+        }
+        self.current += 1;
+        self.stops.get(self.current).cloned()
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Start and text of the word being typed before the cursor.
+pub fn word_prefix(rope: &Rope, cursor: usize) -> (usize, String) {
+    let cursor = cursor.min(rope.len_chars());
+    let mut start = cursor;
+    while start > 0 && is_word_char(rope.char(start - 1)) {
+        start -= 1;
+    }
+    (start, rope.slice(start..cursor).to_string())
+}
+
+/// Every distinct word of the buffer, except the one being typed at `start`.
+pub fn buffer_words(rope: &Rope, start: usize) -> Vec<CompletionItem> {
+    let mut words = HashSet::new();
+    let mut items = Vec::new();
+    let mut word = String::new();
+    let mut word_start = 0;
+    for (i, c) in rope.chars().chain(std::iter::once(' ')).enumerate() {
+        if is_word_char(c) {
+            if word.is_empty() {
+                word_start = i;
+            }
+            word.push(c);
+            continue;
+        }
+        let is_typed = word_start == start;
+        let is_number = word.starts_with(|c: char| c.is_ascii_digit());
+        if word.chars().count() > 1 && !is_typed && !is_number && words.insert(word.clone()) {
+            items.push(CompletionItem::word(&word));
+            if items.len() >= MAX_WORDS {
+                break;
+            }
+        }
+        word.clear();
+    }
+    items
+}
+
+/// Score how well `query` matches `candidate` as a case insensitive subsequence.
+/// Consecutive chars and chars starting a word score higher. `None` if it doesn't match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate = candidate.chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match = None;
+    for query_char in query.chars() {
+        let found = (position..candidate.len())
+            .find(|&i| candidate[i].to_lowercase().eq(query_char.to_lowercase()))?;
+        score += 1;
+        if previous_match == Some(found.wrapping_sub(1)) {
+            score += 5;
+        }
+        let starts_word = found == 0
+            || !is_word_char(candidate[found - 1])
+            || (candidate[found].is_uppercase() && candidate[found - 1].is_lowercase());
+        if starts_word {
+            score += 3;
+        }
+        if candidate[found] == query_char {
+            score += 1;
+        }
+        previous_match = Some(found);
+        position = found + 1;
+    }
+    // Prefer shorter candidates and exact prefixes
+    if candidate.iter().collect::<String>().starts_with(query) {
+        score += 10;
+    }
+    Some(score * 100 - candidate.len() as i64)
+}
+
+/// Expand a snippet into plain text and the char ranges of its tab stops,
+/// in visiting order with `$0` last. Supports `$1`, `${1}`, `${1:placeholder}`, with
+/// stops inside of placeholders, and `${1|one,two|}` (the first choice is used).
+/// Variables like `$TM_FILENAME` are left out, or replaced with their default in
+/// `${TM_FILENAME:default}`.
+pub fn parse_snippet(snippet: &str) -> (String, Vec<Range<usize>>) {
+    let (text, mut stops) = parse_snippet_stops(snippet);
+    // Visit `$1`, `$2`... in order and `$0` at the end, once per number
+    stops.sort_by_key(|(number, _)| if *number == 0 { usize::MAX } else { *number });
+    stops.dedup_by_key(|(number, _)| *number);
+    (text, stops.into_iter().map(|(_, range)| range).collect())
+}
+
+/// Text of a snippet and its numbered tab stops, in the order they are written.
+fn parse_snippet_stops(snippet: &str) -> (String, Vec<(usize, Range<usize>)>) {
+    let mut text = String::new();
+    let mut len = 0;
+    let mut stops = Vec::new();
+    let mut chars = snippet.chars().peekable();
+
+    fn push(text: &mut String, len: &mut usize, c: char) {
+        text.push(c);
+        *len += 1;
+    }
+
+    fn is_name_char(c: &char) -> bool {
+        c.is_ascii_alphanumeric() || *c == '_'
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    push(&mut text, &mut len, escaped);
+                }
+            }
+            '$' if chars.peek().is_some_and(|c| c.is_ascii_digit()) => {
+                let mut number = String::new();
+                while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                    number.push(digit);
+                }
+                stops.push((number.parse().unwrap_or(0), len..len));
+            }
+            '$' if chars
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphabetic() || *c == '_') =>
+            {
+                while chars.next_if(is_name_char).is_some() {}
+            }
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(is_name_char) {
+                    name.push(c);
+                }
+                let start = len;
+                let mut depth = 1;
+                let mut placeholder = String::new();
+                for c in chars.by_ref() {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    placeholder.push(c);
+                }
+                let (placeholder, nested) = if let Some(choices) = placeholder.strip_prefix('|') {
+                    let choice = choices.split([',', '|']).next().unwrap_or_default();
+                    (choice.to_string(), Vec::new())
+                } else {
+                    placeholder
+                        .strip_prefix(':')
+                        .map(parse_snippet_stops)
+                        .unwrap_or_default()
+                };
+                for c in placeholder.chars() {
+                    push(&mut text, &mut len, c);
+                }
+                stops.extend(
+                    nested
+                        .into_iter()
+                        .map(|(number, range)| (number, start + range.start..start + range.end)),
+                );
+                // Variables only leave their default behind
+                if let Ok(number) = name.parse() {
+                    stops.push((number, start..len));
+                }
+            }
+            c => push(&mut text, &mut len, c),
+        }
+    }
+    (text, stops)
+}
@@ -0,0 +1,142 @@
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::state::{AppState, Channel, TabId};
+
+use super::{AppStateEditorUtils, completion::CompletionItem};
+
+const ITEM_HEIGHT: f32 = 22.;
+
+/// Completion items of an [EditorTab](super::EditorTab), with the documentation
+/// of the selected one next to them.
+#[derive(Clone, PartialEq)]
+pub struct CompletionPopup {
+    pub tab_id: TabId,
+}
+
+impl Component for CompletionPopup {
+    fn render(&self) -> impl IntoElement {
+        let tab_id = self.tab_id;
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::follow_tab(tab_id));
+
+        let (items, selected, is_loading) = {
+            let app_state = radio_app_state.read();
+            let Some(completion) = app_state.editor_tab(tab_id).completion.as_ref() else {
+                return rect().into_element();
+            };
+            let items = completion
+                .visible_range()
+                .filter_map(|index| Some((index, completion.item(index)?.clone())))
+                .collect::<Vec<_>>();
+            (items, completion.selected, completion.is_loading)
+        };
+
+        let documentation =
+            items
+                .iter()
+                .find(|(index, _)| *index == selected)
+                .and_then(|(_, item)| {
+                    let text = [item.detail.as_deref(), item.documentation.as_deref()]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join("\n\n");
+                    (!text.is_empty()).then_some(text)
+                });
+
+        let mut accept = move |index: usize| {
+            let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+            let font_size = app_state.font_size();
+            let editor_tab = app_state.editor_tab_mut(tab_id);
+            if let Some(completion) = editor_tab.completion.as_mut() {
+                completion.selected = index;
+            }
+            editor_tab.accept_completion(font_size);
+            editor_tab.focus_id.request_focus();
+        };
+
+        rect()
+            .horizontal()
+            .width(Size::fill())
+            .content(Content::Flex)
+            .padding(4.)
+            .spacing(8.)
+            .background((22, 27, 34))
+            .color((230, 237, 243))
+            .font_size(13.)
+            .child(
+                rect()
+                    .width(Size::percent(50.))
+                    .children(items.into_iter().map(|(index, item)| {
+                        CompletionOption {
+                            item,
+                            is_selected: index == selected,
+                            on_press: (move |_| accept(index)).into(),
+                        }
+                        .into()
+                    }))
+                    .maybe_child(is_loading.then(|| {
+                        label()
+                            .color((125, 133, 144))
+                            .font_size(12.)
+                            .text("Loading…")
+                    })),
+            )
+            .maybe_child(documentation.map(|documentation| {
+                label()
+                    .width(Size::flex(1.))
+                    .max_lines(10)
+                    .text_overflow(TextOverflow::Ellipsis)
+                    .color((180, 186, 194))
+                    .text(documentation)
+            }))
+            .into_element()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct CompletionOption {
+    item: CompletionItem,
+    is_selected: bool,
+    on_press: EventHandler<Event<PressEventData>>,
+}
+
+impl Component for CompletionOption {
+    fn render_key(&self) -> DiffKey {
+        DiffKey::from(&self.item.label)
+    }
+
+    fn render(&self) -> impl IntoElement {
+        let background = if self.is_selected {
+            Color::from((47, 129, 247, 0.4))
+        } else {
+            Color::TRANSPARENT
+        };
+
+        rect()
+            .horizontal()
+            .width(Size::fill())
+            .height(Size::px(ITEM_HEIGHT))
+            .padding((0., 6.))
+            .corner_radius(4.)
+            .cross_align(Alignment::Center)
+            .content(Content::Flex)
+            .spacing(8.)
+            .background(background)
+            .on_press(self.on_press.clone())
+            .child(
+                label()
+                    .width(Size::flex(1.))
+                    .max_lines(1)
+                    .text_overflow(TextOverflow::Ellipsis)
+                    .text(self.item.label.clone()),
+            )
+            .maybe_child(self.item.detail.clone().map(|detail| {
+                label()
+                    .max_lines(1)
+                    .color((125, 133, 144))
+                    .font_size(12.)
+                    .text(detail.lines().next().unwrap_or_default().to_string())
+            }))
+    }
+}
@@ -1,34 +1,98 @@
-use std::path::PathBuf;
+use std::{
+    cell::RefCell,
+    ops::Range,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 use crate::{
+    backup::forget_backup,
+    diff::{Change, changes, diff_lines},
+    file_watcher::content_hash,
     fs::{FSReadTransportInterface, FSTransport},
+    lsp::{LspManager, completion_trigger_characters, request_completion},
     state::{
-        AppSettings, AppState, Channel, EditorCommands, KeyboardShortcuts, PanelTab, PanelTabData,
-        RadioAppState, TabId, TabProps,
+        AppSettings, AppState, Channel, EditorCommands, JumpLocation, KeyboardShortcuts, PanelTab,
+        PanelTabData, RadioAppState, TabId, TabProps,
     },
-    views::panels::tabs::editor::{
-        AppStateEditorUtils,
-        commands::{DecreaseFontSizeCommand, IncreaseFontSizeCommand, SaveFileCommand},
+    views::{
+        notifications::notifications_state::Notification,
+        panels::tabs::editor::{
+            AppStateEditorUtils,
+            blame::{BlameColumn, BlameState, CommitPopup},
+            commands::{
+                CopyActiveFilePathCommand, DecreaseFontSizeCommand, FindCommand, FindNextCommand,
+                FindPreviousCommand, FindReferencesCommand, GoToDefinitionCommand, GoToLineCommand,
+                IncreaseFontSizeCommand, NextChangeCommand, OpenCommitDiffCommand,
+                PeekDefinitionCommand, PreviewChangeCommand, PreviousChangeCommand,
+                ReplaceAllCommand, ReplaceCommand, RevertChangeCommand, SaveFileCommand,
+                StageChangeCommand, ToggleBlameCommand, TriggerCompletionCommand,
+            },
+            completion::{
+                CompletionState, CompletionTrigger, SnippetSession, buffer_words, parse_snippet,
+                word_prefix,
+            },
+            completion_popup::CompletionPopup,
+            editor_view::EditorView,
+            find::{FindState, expand_replacement, replace_all_in},
+            find_bar::FindBar,
+            git_changes::{ChangePreview, load_git_base},
+            peek::{PeekState, PeekView},
+        },
+        problems::problems_ui::ProblemsHover,
+        references::navigation::go_to_definition,
     },
 };
 
-use crate::theme::{GITHUB_DARK_EDITOR_THEME, GITHUB_DARK_SYNTAX_THEME};
-use freya::code_editor::{CodeEditor, CodeEditorData, LanguageId, Rope};
+use crate::theme::GITHUB_DARK_SYNTAX_THEME;
+use freya::code_editor::{CodeEditorData, LanguageId, Rope};
 use freya::prelude::*;
 use freya::radio::use_radio;
-use tracing::info;
+use freya::text_edit::TextEditor;
+use tracing::{info, warn};
+
+/// Cursor moves across at least this many lines are recorded in the jump list.
+const JUMP_LINES: usize = 10;
 
 /// A tab with an embedded Editor.
 pub struct EditorTab {
     pub(crate) data: CodeEditorData,
     pub(crate) transport: FSTransport,
+    /// Told about every change of the buffer, as soon as it's made.
+    pub(crate) lsp: LspManager,
     pub(crate) id: TabId,
     pub(crate) focus_id: AccessibilityId,
     pub(crate) path: PathBuf,
     pub(crate) icon: freya::prelude::Bytes,
+    pub(crate) find: Option<FindState>,
+    pub(crate) completion: Option<CompletionState>,
+    pub(crate) snippet: Option<SnippetSession>,
+    pub(crate) peek: Option<PeekState>,
+    /// Last `(row, col)` of the cursor and length of the rope, to tell
+    /// when the cursor jumped far away.
+    pub(crate) cursor_anchor: ((usize, usize), usize),
+    /// Horizontal and vertical scroll of the editor.
+    pub(crate) scroll: (i32, i32),
+    /// [content_hash] of the file the last time it was read or written,
+    /// to tell apart changes made by other programs.
+    pub(crate) disk_hash: u64,
+    /// Version of the file staged in git, to tell which lines changed since.
+    git_base: Option<String>,
+    /// [EditorTab::git_changes] of the last text they were diffed for, reset along
+    /// with the `git_base`.
+    git_changes: RefCell<Option<(Rope, Vec<Change>)>>,
+    /// Show the original lines of the change under the cursor.
+    pub(crate) show_change_preview: bool,
+    /// Commits of the lines, shown instead of the editor while it's `Some`.
+    pub(crate) blame: Option<BlameState>,
 }
 
 impl PanelTab for EditorTab {
+    fn on_close(&mut self, app_state: &mut AppState) {
+        app_state.lsp.did_close(&self.path);
+        forget_backup(app_state, &self.path);
+    }
+
     fn on_settings_changed(&mut self, app_settings: &AppSettings) {
         self.data
             .measure(app_settings.editor.font_size, "Jetbrains Mono");
@@ -48,31 +112,118 @@ impl PanelTab for EditorTab {
         |props| {
             let tab_id = props.tab_id;
             let radio_app_state = use_radio(Channel::follow_tab(tab_id));
-            let focus_id = radio_app_state.slice_current(move |s| &s.editor_tab(tab_id).focus_id);
-            let editor =
-                radio_app_state.slice_mut_current(move |s| &mut s.editor_tab_mut(tab_id).data);
-            CodeEditor::new(editor.into_writable(), *focus_id.read())
-                .font_size(radio_app_state.read().font_size())
-                .line_height(radio_app_state.read().line_height())
-                .theme(GITHUB_DARK_EDITOR_THEME)
-                .on_pre_key_down(|e: Event<KeyboardEventData>| {
+            let (
+                show_find,
+                show_completion,
+                show_peek,
+                show_change_preview,
+                show_blame,
+                blame_popup,
+            ) = {
+                let app_state = radio_app_state.read();
+                let editor_tab = app_state.editor_tab(tab_id);
+                (
+                    editor_tab.find.is_some(),
+                    editor_tab.completion.is_some(),
+                    editor_tab.peek.is_some(),
+                    editor_tab.show_change_preview,
+                    editor_tab.blame.is_some(),
+                    editor_tab.blame.as_ref().and_then(|blame| blame.popup),
+                )
+            };
+
+            let cursor_problems = {
+                let app_state = radio_app_state.read();
+                let editor_tab = app_state.editor_tab(tab_id);
+                app_state
+                    .problems
+                    .at_line(&editor_tab.path, editor_tab.data.cursor_row())
+            };
+
+            // Record large cursor moves in the jump list, like clicking far away or `Ctrl End`
+            use_side_effect(move || {
+                let is_tracked = {
+                    let app_state = radio_app_state.read();
+                    let editor_tab = app_state.editor_tab(tab_id);
+                    editor_tab.cursor_anchor == editor_tab.current_anchor()
+                };
+                if !is_tracked {
+                    let mut radio_app_state = radio_app_state;
+                    let mut app_state = radio_app_state.write_channel(Channel::Navigation);
+                    if let Some(from) = app_state.editor_tab_mut(tab_id).track_cursor_jump() {
+                        app_state.jump_list.push(from);
+                    }
+                }
+            });
+
+            let editor_view = EditorView {
+                tab_id,
+                on_pre_key_down: Callback::new(move |e: Event<KeyboardEventData>| {
                     e.stop_propagation();
                     let alt = e.modifiers.contains(Modifiers::ALT);
                     let ctrl = e.modifiers == Modifiers::CONTROL;
                     let ctrl_shift = e.modifiers == (Modifiers::CONTROL | Modifiers::SHIFT);
                     let is_global_shortcut = alt
-                        || matches!(e.code, Code::KeyS | Code::KeyW if ctrl)
-                        || matches!(e.code, Code::KeyP | Code::Tab if ctrl || ctrl_shift);
+                        || matches!(e.code, Code::KeyS | Code::KeyW | Code::KeyH if ctrl)
+                        || matches!(e.code, Code::KeyG | Code::Space if ctrl)
+                        || matches!(e.code, Code::KeyG if ctrl_shift)
+                        || matches!(e.code, Code::KeyP | Code::Tab if ctrl || ctrl_shift)
+                        || matches!(e.code, Code::KeyF if ctrl || ctrl_shift)
+                        || matches!(e.code, Code::KeyM if ctrl_shift)
+                        || matches!(e.code, Code::F3 | Code::F12)
+                        || matches!(e.code, Code::ControlLeft | Code::ControlRight);
                     if is_global_shortcut {
                         // Skip editor processing and let GlobalKeyDown fire;
                         // calling `prevent_default()` here would cancel it.
                         return false;
                     }
+                    if Self::completion_pre_key_down(radio_app_state, tab_id, &e) {
+                        // Handled by the completion, neither the editor nor the
+                        // global shortcuts should see it.
+                        e.prevent_default();
+                        return false;
+                    }
                     if e.key == Key::Named(NamedKey::Tab) {
                         e.prevent_default();
                     }
                     true
-                })
+                }),
+            };
+
+            rect()
+                .expanded()
+                .content(Content::Flex)
+                .maybe_child(show_find.then_some(FindBar { tab_id }))
+                .child(
+                    rect()
+                        .horizontal()
+                        .width(Size::fill())
+                        .height(Size::flex(1.))
+                        .content(Content::Flex)
+                        .maybe_child(show_blame.then_some(BlameColumn { tab_id }))
+                        .child(
+                            rect()
+                                .width(Size::flex(1.))
+                                .height(Size::fill())
+                                .on_pointer_press(move |_| {
+                                    // `Ctrl Click` goes to the definition, once the editor
+                                    // moved the cursor
+                                    if radio_app_state.read().is_ctrl_pressed {
+                                        spawn(async move {
+                                            go_to_definition(radio_app_state, tab_id)
+                                        });
+                                    }
+                                })
+                                .child(editor_view),
+                        )
+                )
+                .maybe_child(show_completion.then_some(CompletionPopup { tab_id }))
+                .maybe_child(show_peek.then_some(PeekView { tab_id }))
+                .maybe_child(show_change_preview.then_some(ChangePreview { tab_id }))
+                .maybe_child(blame_popup.map(|index| CommitPopup { tab_id, index }))
+                .maybe_child((!cursor_problems.is_empty()).then_some(ProblemsHover {
+                    problems: cursor_problems,
+                }))
                 .into()
         }
     }
@@ -91,6 +242,7 @@ impl EditorTab {
         id: TabId,
         data: CodeEditorData,
         transport: FSTransport,
+        lsp: LspManager,
         path: PathBuf,
         icon: freya::prelude::Bytes,
     ) -> Self {
@@ -99,8 +251,20 @@ impl EditorTab {
             focus_id: AccessibilityId::new_unique(),
             data,
             transport,
+            lsp,
             path,
             icon,
+            find: None,
+            completion: None,
+            snippet: None,
+            peek: None,
+            cursor_anchor: ((0, 0), 0),
+            scroll: (0, 0),
+            disk_hash: 0,
+            git_base: None,
+            git_changes: RefCell::default(),
+            show_change_preview: false,
+            blame: None,
         }
     }
 
@@ -108,12 +272,618 @@ impl EditorTab {
         self.path.file_name().unwrap().to_str().unwrap().to_owned()
     }
 
+    /// Move the cursor to a char index, dropping any selection.
+    pub fn set_cursor(&mut self, char_idx: usize) {
+        self.data.clear_selection();
+        self.data
+            .move_cursor_to(char_idx.min(self.data.rope.len_chars()));
+    }
+
+    /// Select a char range and place the cursor at its end.
+    pub fn select_range(&mut self, range: Range<usize>) {
+        self.data.set_selection((range.start, range.end));
+        self.data.move_cursor_to(range.end);
+    }
+
+    /// Text of the selection, if anything is selected.
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.data.get_selection()?;
+        let range = start.min(end)..start.max(end);
+        (!range.is_empty()).then(|| self.data.rope.slice(range).to_string())
+    }
+
+    /// Replace a char range of the rope, recording it in the editor history as a
+    /// single step so it is undone at once.
+    pub fn replace_range(&mut self, range: Range<usize>, text: &str, font_size: f32) {
+        let history = self.data.editor_history();
+        history.clear_redos();
+        // Close the previous transaction so the replacement isn't grouped with recent typing
+        if let Some(last) = history.transactions.last_mut()
+            && let Some(closed) = Instant::now().checked_sub(Duration::from_secs(60))
+        {
+            last.timestamp = closed;
+        }
+        let first = history.transactions.len();
+
+        if !range.is_empty() {
+            let start = self.data.char_to_utf16_cu(range.start);
+            let end = self.data.char_to_utf16_cu(range.end);
+            self.data.remove(start..end);
+        }
+        if !text.is_empty() {
+            let start = self.data.char_to_utf16_cu(range.start);
+            self.data.insert(text, start);
+        }
+
+        let history = self.data.editor_history();
+        if history.transactions.len() > first + 1 {
+            let changes: Vec<_> = history
+                .transactions
+                .drain(first + 1..)
+                .flat_map(|transaction| transaction.changes)
+                .collect();
+            history.transactions[first].changes.extend(changes);
+            history.current_transaction = first + 1;
+        }
+        self.data.measure(font_size, "Jetbrains Mono");
+        self.lsp.did_change(&self.path, &self.data.rope);
+    }
+
+    /// Show the find bar, keeping the previous query if it was already open.
+    pub fn open_find(&mut self, show_replace: bool) {
+        match self.find.as_mut() {
+            Some(find) => {
+                find.show_replace |= show_replace;
+                find.focus_id.request_focus();
+            }
+            None => {
+                let mut find = FindState::new(String::new(), show_replace);
+                find.update(&self.data.rope, self.data.cursor_pos());
+                self.find = Some(find);
+            }
+        }
+    }
+
+    pub fn close_find(&mut self) {
+        self.find = None;
+        self.focus_id.request_focus();
+    }
+
+    /// Search again after the query, the options or the rope changed,
+    /// anchoring on the selected match so it stays selected while typing.
+    pub fn refresh_find(&mut self) {
+        let cursor = self.data.cursor_pos();
+        if let Some(find) = self.find.as_mut() {
+            let anchor = find.current_match().map(|m| m.start).unwrap_or(cursor);
+            find.update(&self.data.rope, anchor);
+        }
+    }
+
+    pub fn find_next(&mut self) {
+        let cursor = self.data.cursor_pos();
+        let Some(find) = self.find.as_mut() else {
+            return;
+        };
+        find.update(&self.data.rope, cursor);
+        if let Some(range) = find.select_next(cursor) {
+            self.select_range(range);
+        }
+    }
+
+    pub fn find_previous(&mut self) {
+        let cursor = self.data.cursor_pos();
+        let Some(find) = self.find.as_mut() else {
+            return;
+        };
+        find.update(&self.data.rope, cursor);
+        if let Some(range) = find.select_previous(cursor) {
+            self.select_range(range);
+        }
+    }
+
+    /// Replace the selected match and select the next one.
+    pub fn replace_current(&mut self, font_size: f32) {
+        let Some(find) = self.find.as_ref() else {
+            return;
+        };
+        let (Some(range), Ok(regex)) = (find.current_match(), find.regex()) else {
+            return;
+        };
+        let text = self.data.rope.to_string();
+        let start = self.data.rope.char_to_byte(range.start);
+        // The match could be stale if the rope changed since the last search
+        let Some(replacement) =
+            expand_replacement(&regex, find.options, &text, start, &find.replacement)
+        else {
+            self.refresh_find();
+            return;
+        };
+
+        self.replace_range(range.clone(), &replacement, font_size);
+        self.set_cursor(range.start + replacement.chars().count());
+        self.refresh_find();
+        if let Some(range) = self.find.as_ref().and_then(|find| find.current_match()) {
+            self.select_range(range);
+        }
+    }
+
+    /// Replace every match of the find bar.
+    pub fn replace_all(&mut self, font_size: f32) -> usize {
+        let Some(find) = self.find.as_ref() else {
+            return 0;
+        };
+        let Ok(regex) = find.regex() else {
+            return 0;
+        };
+        let text = self.data.rope.to_string();
+        let (replaced, count) = replace_all_in(&text, &regex, find.options, &find.replacement);
+        if count > 0 {
+            self.replace_text(&text, &replaced, font_size);
+            self.refresh_find();
+        }
+        count
+    }
+
+    /// Replace the content with the file as it is on disk now, keeping the cursor
+    /// where it was. The reload can be undone.
+    pub fn reload(&mut self, content: &str, font_size: f32) {
+        let (row, col) = (self.data.cursor_row(), self.data.cursor_col());
+        let old_text = self.data.rope.to_string();
+        self.replace_text(&old_text, content, font_size);
+        self.move_to_position(row, col);
+        self.data.mark_as_saved();
+        self.disk_hash = content_hash([content]);
+    }
+
+    /// Replace the whole content with `new_text`. Only the span that actually
+    /// changed is rewritten so the whole operation is undone in one step.
+    pub fn replace_text(&mut self, old_text: &str, new_text: &str, font_size: f32) {
+        if old_text == new_text {
+            return;
+        }
+
+        let prefix = old_text
+            .chars()
+            .zip(new_text.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let old_len = old_text.chars().count();
+        let new_len = new_text.chars().count();
+        let suffix = old_text
+            .chars()
+            .rev()
+            .zip(new_text.chars().rev())
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(old_len - prefix)
+            .min(new_len - prefix);
+
+        let new_span: String = new_text
+            .chars()
+            .skip(prefix)
+            .take(new_len - prefix - suffix)
+            .collect();
+        self.replace_range(prefix..old_len - suffix, &new_span, font_size);
+        self.set_cursor(prefix + new_span.chars().count());
+    }
+
+    /// Handle the keys that drive the completion popup, snippet tab stops and
+    /// the peeked definition. Returns whether the key was consumed.
+    fn completion_pre_key_down(
+        mut radio: RadioAppState,
+        tab_id: TabId,
+        e: &Event<KeyboardEventData>,
+    ) -> bool {
+        let is_typing = e.modifiers.is_empty() || e.modifiers == Modifiers::SHIFT;
+        let (has_completion, has_snippet, has_peek) = {
+            let app_state = radio.read();
+            let editor_tab = app_state.editor_tab(tab_id);
+            (
+                editor_tab.completion.is_some(),
+                editor_tab.snippet.is_some(),
+                editor_tab.peek.is_some(),
+            )
+        };
+
+        if has_completion && e.modifiers.is_empty() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(tab_id));
+            let font_size = app_state.font_size();
+            let editor_tab = app_state.editor_tab_mut(tab_id);
+            match &e.key {
+                Key::Named(NamedKey::ArrowDown) => {
+                    if let Some(completion) = editor_tab.completion.as_mut() {
+                        completion.select_next();
+                    }
+                    return true;
+                }
+                Key::Named(NamedKey::ArrowUp) => {
+                    if let Some(completion) = editor_tab.completion.as_mut() {
+                        completion.select_previous();
+                    }
+                    return true;
+                }
+                Key::Named(NamedKey::Enter) | Key::Named(NamedKey::Tab) => {
+                    editor_tab.accept_completion(font_size);
+                    return true;
+                }
+                Key::Named(NamedKey::Escape) => {
+                    editor_tab.completion = None;
+                    return true;
+                }
+                _ => {}
+            }
+        }
+
+        if has_snippet && e.modifiers.is_empty() {
+            let mut app_state = radio.write_channel(Channel::follow_tab(tab_id));
+            let editor_tab = app_state.editor_tab_mut(tab_id);
+            match &e.key {
+                Key::Named(NamedKey::Tab) => return editor_tab.next_snippet_stop(),
+                Key::Named(NamedKey::Escape) => editor_tab.snippet = None,
+                _ => {}
+            }
+        }
+
+        if has_peek && e.key == Key::Named(NamedKey::Escape) {
+            radio
+                .write_channel(Channel::follow_tab(tab_id))
+                .editor_tab_mut(tab_id)
+                .peek = None;
+            return true;
+        }
+
+        // Refresh once the editor applied the key
+        let trigger = match &e.key {
+            Key::Character(_) if is_typing => Some(CompletionTrigger::Typing),
+            Key::Named(
+                NamedKey::Backspace
+                | NamedKey::Delete
+                | NamedKey::ArrowLeft
+                | NamedKey::ArrowRight
+                | NamedKey::Home
+                | NamedKey::End,
+            ) if has_completion => Some(CompletionTrigger::Update),
+            _ => None,
+        };
+        if let Some(trigger) = trigger {
+            spawn(async move { Self::refresh_completion(radio, tab_id, trigger) });
+        }
+        false
+    }
+
+    /// Show or update the completion of the word before the cursor. Buffer words are
+    /// shown right away and the language server items, if any, once it answers.
+    pub fn refresh_completion(mut radio: RadioAppState, tab_id: TabId, trigger: CompletionTrigger) {
+        let mut app_state = radio.write_channel(Channel::follow_tab(tab_id));
+        let lsp = app_state.lsp.clone();
+        let Some(editor_tab) = app_state.tab_mut(&tab_id).as_text_editor_mut() else {
+            return;
+        };
+        let cursor = editor_tab.data.cursor_pos();
+        let (start, query) = word_prefix(&editor_tab.data.rope, cursor);
+
+        if let Some(completion) = editor_tab.completion.as_mut()
+            && completion.start == start
+            && trigger != CompletionTrigger::Invoked
+        {
+            completion.set_query(query);
+            if completion.is_empty() && !completion.is_loading {
+                editor_tab.completion = None;
+            }
+            return;
+        }
+        editor_tab.completion = None;
+
+        let client = lsp.client_for(&editor_tab.path);
+        let rope = editor_tab.data.rope.clone();
+        let is_trigger_character = client.as_ref().is_some_and(|client| {
+            let before = rope.slice(cursor.saturating_sub(3)..cursor).to_string();
+            completion_trigger_characters(client)
+                .iter()
+                .any(|trigger| before.ends_with(trigger.as_str()))
+        });
+        let should_open = match trigger {
+            CompletionTrigger::Update => false,
+            CompletionTrigger::Typing => !query.is_empty() || is_trigger_character,
+            CompletionTrigger::Invoked => true,
+        };
+        if !should_open {
+            return;
+        }
+
+        let mut completion = CompletionState::new(start, query, buffer_words(&rope, start));
+        completion.is_loading = client.is_some();
+        if completion.is_empty() && !completion.is_loading {
+            return;
+        }
+        editor_tab.completion = Some(completion);
+        let Some(client) = client else {
+            return;
+        };
+        let path = editor_tab.path.clone();
+        drop(app_state);
+
+        spawn(async move {
+            let items = request_completion(&client, &path, &rope, cursor).await;
+            let mut app_state = radio.write_channel(Channel::follow_tab(tab_id));
+            let Some(editor_tab) = app_state.tab_mut(&tab_id).as_text_editor_mut() else {
+                return;
+            };
+            let Some(completion) = editor_tab.completion.as_mut() else {
+                return;
+            };
+            if completion.start != start {
+                return;
+            }
+            match items {
+                Ok(items) => completion.set_provider_items(items),
+                Err(err) => {
+                    warn!("Failed to complete {path:?}: {err}");
+                    completion.is_loading = false;
+                }
+            }
+            if completion.is_empty() {
+                editor_tab.completion = None;
+            }
+        });
+    }
+
+    /// Insert the selected completion item in place of the typed word.
+    pub fn accept_completion(&mut self, font_size: f32) {
+        let Some(completion) = self.completion.take() else {
+            return;
+        };
+        let Some(item) = completion.selected_item() else {
+            return;
+        };
+        let (text, stops) = if item.is_snippet {
+            parse_snippet(&item.insert_text)
+        } else {
+            (item.insert_text.clone(), Vec::new())
+        };
+
+        let cursor = self.data.cursor_pos().max(completion.start);
+        self.replace_range(completion.start..cursor, &text, font_size);
+        let len_chars = self.data.rope.len_chars();
+        match SnippetSession::new(completion.start, stops, len_chars) {
+            Some((session, stop)) => {
+                self.snippet = (!session.is_finished()).then_some(session);
+                self.select_range(stop);
+            }
+            None => self.set_cursor(completion.start + text.chars().count()),
+        }
+    }
+
+    /// Select the next tab stop of the inserted snippet. Returns whether there was one.
+    pub fn next_snippet_stop(&mut self) -> bool {
+        let len_chars = self.data.rope.len_chars();
+        let Some(session) = self.snippet.as_mut() else {
+            return false;
+        };
+        let Some(stop) = session.next(len_chars) else {
+            self.snippet = None;
+            return false;
+        };
+        if session.is_finished() {
+            self.snippet = None;
+        }
+        self.select_range(stop);
+        true
+    }
+
+    /// Move the cursor to a 0-based line and column, clamped to the rope.
+    pub fn move_to_position(&mut self, row: usize, col: usize) {
+        let rope = &self.data.rope;
+        let row = row.min(rope.len_lines().saturating_sub(1));
+        let line_start = rope.line_to_char(row);
+        let line_len = rope.line(row).len_chars();
+        let char_idx = line_start + col.min(line_len);
+        self.set_cursor(char_idx);
+        // Whoever moved it here on purpose records the jump
+        self.cursor_anchor = self.current_anchor();
+    }
+
+    pub fn git_base(&self) -> Option<&str> {
+        self.git_base.as_deref()
+    }
+
+    pub fn set_git_base(&mut self, git_base: Option<String>) {
+        self.git_base = git_base;
+        self.git_changes.take();
+    }
+
+    /// Lines changed since the version staged in git. Diffed again only once the text
+    /// or the staged version changed.
+    pub fn git_changes(&self) -> Vec<Change> {
+        let Some(git_base) = &self.git_base else {
+            return Vec::new();
+        };
+        let mut cache = self.git_changes.borrow_mut();
+        if let Some((rope, changes)) = cache.as_ref()
+            && rope == &self.data.rope
+        {
+            return changes.clone();
+        }
+        let changes = changes(&diff_lines(git_base, &self.data.rope.to_string()));
+        *cache = Some((self.data.rope.clone(), changes.clone()));
+        changes
+    }
+
+    /// The change under the cursor. Deleted lines are under the line that follows them.
+    pub fn change_at_cursor(&self) -> Option<Change> {
+        let row = self.data.cursor_row();
+        self.git_changes().into_iter().find(|change| {
+            change.new_lines.contains(&row)
+                || (change.new_lines.is_empty() && change.new_lines.start == row)
+        })
+    }
+
+    /// Move the cursor to the next change, or the previous one, wrapping around the file.
+    pub fn go_to_change(&mut self, backwards: bool) {
+        let row = self.data.cursor_row();
+        let starts = self
+            .git_changes()
+            .iter()
+            .map(|change| change.new_lines.start)
+            .collect::<Vec<_>>();
+        let start = if backwards {
+            starts
+                .iter()
+                .rev()
+                .find(|start| **start < row)
+                .or(starts.last())
+        } else {
+            starts.iter().find(|start| **start > row).or(starts.first())
+        };
+        if let Some(&start) = start {
+            self.move_to_position(start, 0);
+        }
+    }
+
+    /// Put back the staged lines of the change under the cursor.
+    pub fn revert_change(&mut self, font_size: f32) {
+        let (Some(git_base), Some(change)) = (self.git_base.as_ref(), self.change_at_cursor())
+        else {
+            return;
+        };
+        let original = git_base
+            .split_inclusive('\n')
+            .skip(change.old_lines.start)
+            .take(change.old_lines.len())
+            .collect::<String>();
+        let rope = &self.data.rope;
+        let start = rope.line_to_char(change.new_lines.start.min(rope.len_lines()));
+        let end = rope.line_to_char(change.new_lines.end.min(rope.len_lines()));
+        self.replace_range(start..end, &original, font_size);
+        self.move_to_position(change.new_lines.start, 0);
+    }
+
+    /// The staged version of the file with the change under the cursor applied to it.
+    pub fn staged_with_change(&self) -> Option<String> {
+        let git_base = self.git_base.as_ref()?;
+        let change = self.change_at_cursor()?;
+        let base_lines = git_base.split_inclusive('\n').collect::<Vec<_>>();
+        let mut content = base_lines[..change.old_lines.start].concat();
+        for row in change.new_lines {
+            content.extend(self.data.rope.line(row).chunks());
+        }
+        content.push_str(&base_lines[change.old_lines.end..].concat());
+        Some(content)
+    }
+
+    /// Where the cursor is, to come back to it later.
+    pub fn jump_location(&self) -> JumpLocation {
+        JumpLocation {
+            path: self.path.clone(),
+            line: self.data.cursor_row(),
+            col: self.data.cursor_col(),
+        }
+    }
+
+    fn current_anchor(&self) -> ((usize, usize), usize) {
+        (
+            (self.data.cursor_row(), self.data.cursor_col()),
+            self.data.rope.len_chars(),
+        )
+    }
+
+    /// Remember where the cursor is, returning where it was if it jumped far
+    /// away without the text changing.
+    pub fn track_cursor_jump(&mut self) -> Option<JumpLocation> {
+        let current_anchor = self.current_anchor();
+        let ((row, col), len_chars) = std::mem::replace(&mut self.cursor_anchor, current_anchor);
+        let (new_row, _) = self.cursor_anchor.0;
+        let is_jump = len_chars == self.cursor_anchor.1 && row.abs_diff(new_row) >= JUMP_LINES;
+        is_jump.then(|| JumpLocation {
+            path: self.path.clone(),
+            line: row,
+            col,
+        })
+    }
+
     /// Open an EditorTab in the focused panel.
     pub fn open_with(
+        radio: RadioAppState,
+        app_state: &mut AppState,
+        path: PathBuf,
+        read_transport: Box<dyn FSReadTransportInterface + 'static>,
+    ) {
+        Self::open_at(radio, app_state, path, read_transport, None);
+    }
+
+    /// Open an EditorTab in the focused panel and place the cursor at a
+    /// 0-based `(row, col)` once the content is loaded.
+    /// If the file is already opened its tab is focused and the cursor moved.
+    /// Where the cursor was is recorded in the jump list.
+    pub fn open_at(
+        radio: RadioAppState,
+        app_state: &mut AppState,
+        path: PathBuf,
+        read_transport: Box<dyn FSReadTransportInterface + 'static>,
+        position: Option<(usize, usize)>,
+    ) {
+        if let Some(from) = app_state.active_jump_location() {
+            app_state.jump_list.push(from);
+        }
+        Self::open_location(radio, app_state, path, read_transport, position);
+    }
+
+    /// Go back or forward in the jump list, reopening the file if it was closed.
+    pub fn navigate_jump_list(radio: RadioAppState, app_state: &mut AppState, forward: bool) {
+        let current = app_state.active_jump_location();
+        let target = if forward {
+            app_state.jump_list.forward(current)
+        } else {
+            app_state.jump_list.back(current)
+        };
+        if let Some(JumpLocation { path, line, col }) = target {
+            let transport = app_state.default_transport.clone();
+            Self::open_location(
+                radio,
+                app_state,
+                path,
+                transport.as_read(),
+                Some((line, col)),
+            );
+        }
+    }
+
+    /// Like [EditorTab::open_at], without recording the jump.
+    pub fn open_location(
+        radio: RadioAppState,
+        app_state: &mut AppState,
+        path: PathBuf,
+        read_transport: Box<dyn FSReadTransportInterface + 'static>,
+        position: Option<(usize, usize)>,
+    ) {
+        Self::open_tab(radio, app_state, path, read_transport, position, None);
+    }
+
+    /// Open a file with the content recovered from a backup, as unsaved changes.
+    pub fn open_recovered(
+        radio: RadioAppState,
+        app_state: &mut AppState,
+        path: PathBuf,
+        content: String,
+    ) {
+        let transport = app_state.default_transport.clone();
+        Self::open_tab(
+            radio,
+            app_state,
+            path,
+            transport.as_read(),
+            None,
+            Some(content),
+        );
+    }
+
+    fn open_tab(
         mut radio: RadioAppState,
         app_state: &mut AppState,
         path: PathBuf,
         read_transport: Box<dyn FSReadTransportInterface + 'static>,
+        position: Option<(usize, usize)>,
+        recovered: Option<String>,
     ) {
         let tab_id = TabId::new();
 
@@ -132,12 +902,25 @@ impl EditorTab {
             tab_id,
             code_data,
             app_state.default_transport.clone(),
+            app_state.lsp.clone(),
             path.clone(),
             icon,
         );
 
         // Dont create the same tab twice
         if !app_state.push_tab(tab, app_state.focused_panel) {
+            let font_size = app_state.font_size();
+            if let Some(existing_id) = app_state.find_tab_by_content_id(&path.to_string_lossy())
+                && let Some(editor_tab) = app_state.tab_mut(&existing_id).as_text_editor_mut()
+            {
+                if let Some((row, col)) = position {
+                    editor_tab.move_to_position(row, col);
+                }
+                if let Some(recovered) = recovered {
+                    let current = editor_tab.data.rope.to_string();
+                    editor_tab.replace_text(&current, &recovered, font_size);
+                }
+            }
             return;
         }
 
@@ -145,19 +928,52 @@ impl EditorTab {
         spawn_forever({
             let path = path.clone();
             async move {
-                let content = read_transport.read_to_string(&path).await;
-                if let Ok(content) = content {
-                    let mut app_state = radio.write_channel(Channel::follow_tab(tab_id));
-                    let font_size = app_state.font_size();
-
-                    let tab = app_state.tab_mut(&tab_id);
-                    let editor_tab = tab.as_text_editor_mut().unwrap();
-                    editor_tab.data.rope.insert(0, &content);
-                    editor_tab.data.parse();
-                    editor_tab.data.measure(font_size, "Jetbrains Mono");
-
-                    info!("Loaded file content for {path:?}");
+                let content = match read_transport.read_to_string(&path).await {
+                    Ok(content) => content,
+                    // A recovered file might not exist anymore
+                    Err(_) if recovered.is_some() => String::new(),
+                    Err(err) => {
+                        radio.write_channel(Channel::Global).close_tab(tab_id);
+                        radio
+                            .write_channel(Channel::Notifications)
+                            .notify(Notification::error(format!(
+                                "Failed to open {}: {err}",
+                                path.display()
+                            )));
+                        return;
+                    }
+                };
+                let mut app_state = radio.write_channel(Channel::follow_tab(tab_id));
+                let font_size = app_state.font_size();
+
+                let tab = app_state.tab_mut(&tab_id);
+                let editor_tab = tab.as_text_editor_mut().unwrap();
+                editor_tab.data.rope.insert(0, &content);
+                editor_tab.disk_hash = content_hash([content.as_str()]);
+                editor_tab.data.measure(font_size, "Jetbrains Mono");
+                if let Some(recovered) = &recovered {
+                    editor_tab.replace_text(&content, recovered, font_size);
+                }
+                if let Some((row, col)) = position {
+                    editor_tab.move_to_position(row, col);
                 }
+                let rope = editor_tab.data.rope.clone();
+                let language_id = editor_tab.data.language_id;
+
+                info!("Loaded file content for {path:?}");
+
+                let root = app_state
+                    .file_explorer
+                    .folder_of(&path)
+                    .or_else(|| path.parent())
+                    .map(|root| root.to_path_buf())
+                    .unwrap_or_default();
+                app_state
+                    .lsp
+                    .did_open(&app_state.settings.lsp, &root, &path, &language_id, &rope);
+                drop(app_state);
+
+                load_git_base(radio, tab_id).await;
             }
         });
     }
@@ -172,6 +988,24 @@ impl EditorTab {
         commands.register(IncreaseFontSizeCommand(radio_app_state));
         commands.register(DecreaseFontSizeCommand(radio_app_state));
         commands.register(SaveFileCommand(radio_app_state));
+        commands.register(FindCommand(radio_app_state));
+        commands.register(ReplaceCommand(radio_app_state));
+        commands.register(FindNextCommand(radio_app_state));
+        commands.register(FindPreviousCommand(radio_app_state));
+        commands.register(ReplaceAllCommand(radio_app_state));
+        commands.register(TriggerCompletionCommand(radio_app_state));
+        commands.register(GoToDefinitionCommand(radio_app_state));
+        commands.register(GoToLineCommand(radio_app_state));
+        commands.register(PeekDefinitionCommand(radio_app_state));
+        commands.register(FindReferencesCommand(radio_app_state));
+        commands.register(CopyActiveFilePathCommand(radio_app_state));
+        commands.register(NextChangeCommand(radio_app_state));
+        commands.register(PreviousChangeCommand(radio_app_state));
+        commands.register(PreviewChangeCommand(radio_app_state));
+        commands.register(RevertChangeCommand(radio_app_state));
+        commands.register(StageChangeCommand(radio_app_state));
+        commands.register(ToggleBlameCommand(radio_app_state));
+        commands.register(OpenCommitDiffCommand(radio_app_state));
 
         // Register Shortcuts
         keyboard_shorcuts.register(
@@ -180,6 +1014,8 @@ impl EditorTab {
              _radio_app_state: RadioAppState| {
                 let is_pressing_alt = data.modifiers == Modifiers::ALT;
                 let is_pressing_ctrl = data.modifiers == Modifiers::CONTROL;
+                let is_pressing_shift = data.modifiers == Modifiers::SHIFT;
+                let is_pressing_alt_shift = data.modifiers == (Modifiers::ALT | Modifiers::SHIFT);
                 match data.code {
                     // Pressing `Alt ,`
                     Code::Period if is_pressing_alt => {
@@ -193,6 +1029,54 @@ impl EditorTab {
                     Code::KeyS if is_pressing_ctrl => {
                         commands.trigger(SaveFileCommand::id());
                     }
+                    // Pressing `Ctrl F`
+                    Code::KeyF if is_pressing_ctrl => {
+                        commands.trigger(FindCommand::id());
+                    }
+                    // Pressing `Ctrl H`
+                    Code::KeyH if is_pressing_ctrl => {
+                        commands.trigger(ReplaceCommand::id());
+                    }
+                    // Pressing `Ctrl Space`
+                    Code::Space if is_pressing_ctrl => {
+                        commands.trigger(TriggerCompletionCommand::id());
+                    }
+                    // Pressing `Shift F3`
+                    Code::F3 if is_pressing_shift => {
+                        commands.trigger(FindPreviousCommand::id());
+                    }
+                    // Pressing `F3`
+                    Code::F3 if data.modifiers.is_empty() => {
+                        commands.trigger(FindNextCommand::id());
+                    }
+                    // Pressing `Ctrl G`
+                    Code::KeyG if is_pressing_ctrl => {
+                        commands.trigger(GoToLineCommand::id());
+                    }
+                    // Pressing `F12`
+                    Code::F12 if data.modifiers.is_empty() => {
+                        commands.trigger(GoToDefinitionCommand::id());
+                    }
+                    // Pressing `Alt F12`
+                    Code::F12 if is_pressing_alt => {
+                        commands.trigger(PeekDefinitionCommand::id());
+                    }
+                    // Pressing `Shift F12`
+                    Code::F12 if is_pressing_shift => {
+                        commands.trigger(FindReferencesCommand::id());
+                    }
+                    // Pressing `Alt F5`
+                    Code::F5 if is_pressing_alt => {
+                        commands.trigger(NextChangeCommand::id());
+                    }
+                    // Pressing `Alt Shift F5`
+                    Code::F5 if is_pressing_alt_shift => {
+                        commands.trigger(PreviousChangeCommand::id());
+                    }
+                    // Pressing `Alt B`
+                    Code::KeyB if is_pressing_alt => {
+                        commands.trigger(ToggleBlameCommand::id());
+                    }
                     _ => return false,
                 }
 
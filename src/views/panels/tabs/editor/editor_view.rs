@@ -0,0 +1,635 @@
+use std::{borrow::Cow, cell::RefCell, ops::Range, rc::Rc};
+
+use freya::code_editor::{
+    CodeEditorData, InputEditExt, LanguageId, Rope, SyntaxBlocks, SyntaxHighlighter, TextNode,
+};
+use freya::engine::prelude::{FontCollection, ParagraphBuilder, ParagraphStyle, TextStyle};
+use freya::prelude::*;
+use freya::radio::use_radio;
+use freya::text_edit::{EditableEvent, EditorLine, TextEditor};
+use tree_sitter::InputEdit;
+
+use crate::{
+    diff::{Change, ChangeKind},
+    state::{AppState, Channel, RadioAppState, TabId},
+    theme::{GITHUB_DARK_EDITOR_THEME, GITHUB_DARK_SYNTAX_THEME},
+    views::problems::problems_state::{Problem, Severity},
+};
+
+use super::{AppStateEditorUtils, git_changes::change_color};
+
+const FONT_FAMILY: &str = "Jetbrains Mono";
+
+/// Height of the line under the chars with problems.
+const SQUIGGLE_HEIGHT: f32 = 2.;
+
+/// Width of the bar in the gutter of the lines changed since they were staged.
+const CHANGE_MARKER_WIDTH: f32 = 3.;
+
+/// The buffer of an editor tab for the [EditorView]. The edits made by the editor itself,
+/// like typing or undoing, are sent to the language server as soon as they are made.
+fn editor_writable(radio_app_state: RadioAppState, tab_id: TabId) -> Writable<CodeEditorData> {
+    let slice = radio_app_state.slice_mut_current(move |s| &mut s.editor_tab_mut(tab_id).data);
+    Writable::new(
+        Box::new({
+            let slice = slice.clone();
+            move || slice.peek_unchecked()
+        }),
+        Box::new({
+            let slice = slice.clone();
+            move || slice.write_silently()
+        }),
+        Box::new({
+            let slice = slice.clone();
+            move || {
+                slice.read();
+            }
+        }),
+        Box::new(move || {
+            slice.notify();
+            let app_state = radio_app_state.read();
+            if let Some(editor_tab) = app_state
+                .tabs
+                .get(&tab_id)
+                .and_then(|tab| tab.as_text_editor())
+            {
+                editor_tab
+                    .lsp
+                    .did_change(&editor_tab.path, &editor_tab.data.rope);
+            }
+        }),
+    )
+}
+
+/// Scroll kept in the [EditorTab::scroll](super::EditorTab) of a tab, so it survives the
+/// view and views beside the editor can follow it. Without `horizontal`, only the
+/// vertical scroll is followed.
+pub(super) fn editor_scroll_controller(
+    mut radio_app_state: RadioAppState,
+    tab_id: TabId,
+    horizontal: bool,
+) -> ScrollController {
+    ScrollController::managed(
+        State::create(()),
+        State::create(vec![]),
+        State::create(Callback::new(move |ev| {
+            let scroll = radio_app_state.read().editor_tab(tab_id).scroll;
+            let new_scroll = match ev {
+                ScrollEvent::X(x) if horizontal => (x, scroll.1),
+                ScrollEvent::X(_) => scroll,
+                ScrollEvent::Y(y) => (scroll.0, y),
+            };
+            if new_scroll == scroll {
+                return false;
+            }
+            radio_app_state
+                .write_channel(Channel::follow_tab(tab_id))
+                .editor_tab_mut(tab_id)
+                .scroll = new_scroll;
+            true
+        })),
+        State::create(Callback::new(move |_| {
+            let scroll = radio_app_state.read().editor_tab(tab_id).scroll;
+            if horizontal { scroll } else { (0, scroll.1) }
+        })),
+    )
+}
+
+/// Width of a char of the editor font, which is monospaced.
+fn measure_char_width(font_size: f32) -> f32 {
+    let font_collection = consume_root_context::<FontCollection>();
+    let mut paragraph_style = ParagraphStyle::new();
+    let mut text_style = TextStyle::new();
+    text_style.set_font_size(font_size);
+    text_style.set_font_families(&[FONT_FAMILY]);
+    paragraph_style.set_text_style(&text_style);
+    let mut paragraph_builder = ParagraphBuilder::new(&paragraph_style, font_collection);
+    paragraph_builder.add_text("W");
+    let mut paragraph = paragraph_builder.build();
+    paragraph.layout(f32::MAX);
+    paragraph.longest_line()
+}
+
+/// The edit turning `old` into `new`, spanning from where they start to differ to where
+/// they stop differing, for the parser to only parse that part again.
+fn edit_between(old: &Rope, new: &Rope) -> Option<InputEdit> {
+    let prefix = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix == old.len_bytes() && prefix == new.len_bytes() {
+        return None;
+    }
+    let suffix = old
+        .bytes_at(old.len_bytes())
+        .reversed()
+        .zip(new.bytes_at(new.len_bytes()).reversed())
+        .take(old.len_bytes().min(new.len_bytes()) - prefix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    // Cut between chars, the suffix is the same on both sides so the new end is too
+    let start_byte = old.char_to_byte(old.byte_to_char(prefix));
+    let old_end_char = old.byte_to_char(old.len_bytes() - suffix);
+    let mut old_end_byte = old.char_to_byte(old_end_char);
+    if old_end_byte < old.len_bytes() - suffix {
+        old_end_byte = old.char_to_byte(old_end_char + 1);
+    }
+    let new_end_byte = new.len_bytes() - (old.len_bytes() - old_end_byte);
+    let position = |rope: &Rope, byte: usize| {
+        let line = rope.byte_to_line(byte);
+        (line, byte - rope.line_to_byte(line))
+    };
+    Some(InputEdit::new_edit(
+        start_byte,
+        old_end_byte,
+        new_end_byte,
+        position(old, start_byte),
+        position(old, old_end_byte),
+        position(new, new_end_byte),
+    ))
+}
+
+/// Highlighted lines of the text last shown by an editor.
+#[derive(Default)]
+struct Highlights {
+    highlighter: SyntaxHighlighter,
+    blocks: SyntaxBlocks,
+    /// Text the lines were built for, to tell which part the next edits changed.
+    rope: Rope,
+    /// Language, version of the editor history and length of the text the lines were
+    /// built for. The text loaded on open isn't in the history, the length tells it apart.
+    source: Option<(LanguageId, usize, usize)>,
+    /// Bumped every time the lines are built again.
+    revision: usize,
+    /// Font size the chars were measured with, and their width.
+    char_width: (f32, f32),
+    longest_width: f32,
+}
+
+impl Highlights {
+    /// Parse again the part of the text edited since the lines were last built, or
+    /// everything if its language changed.
+    fn update(&mut self, editor: &mut CodeEditorData, font_size: f32) {
+        if self.char_width.0 != font_size {
+            self.char_width = (font_size, measure_char_width(font_size));
+            self.longest_width = self.longest_line() as f32 * self.char_width.1;
+        }
+        let source = (
+            editor.language_id,
+            editor.editor_history().version,
+            editor.rope.len_bytes(),
+        );
+        if self.source == Some(source) {
+            return;
+        }
+        let is_same_language = self
+            .source
+            .is_some_and(|(language_id, ..)| language_id == source.0);
+        self.source = Some(source);
+        let edit = if is_same_language {
+            // Edits undone before the lines were built again leave nothing to parse
+            let Some(edit) = edit_between(&self.rope, &editor.rope) else {
+                return;
+            };
+            Some(edit)
+        } else {
+            self.highlighter
+                .set_language(source.0, &GITHUB_DARK_SYNTAX_THEME);
+            self.highlighter.invalidate_tree();
+            None
+        };
+        self.rope = editor.rope.clone();
+        self.highlighter.parse(
+            &self.rope,
+            &mut self.blocks,
+            edit,
+            &GITHUB_DARK_SYNTAX_THEME,
+        );
+        self.longest_width = self.longest_line() as f32 * self.char_width.1;
+        self.revision += 1;
+    }
+
+    fn longest_line(&self) -> usize {
+        let longest_line = self.rope.lines().map(|line| line.len_chars()).max();
+        longest_line.unwrap_or_default()
+    }
+
+    /// Text and color of the spans of a line.
+    fn spans(&self, rope: &Rope, line_index: usize) -> Vec<(Color, String)> {
+        if line_index >= self.blocks.len() {
+            return Vec::new();
+        }
+        self.blocks
+            .get_line(line_index)
+            .iter()
+            .map(|(color, node)| {
+                let text = match node {
+                    TextNode::Range(range) => rope.slice(range.clone()).to_string(),
+                    TextNode::LineOfChars { len, char } => char.to_string().repeat(*len),
+                };
+                (*color, text)
+            })
+            .collect()
+    }
+}
+
+/// What the editor draws over a line besides its text.
+#[derive(Clone, Default, PartialEq)]
+struct LineMarks {
+    /// Most severe problem touching the line, marked in the gutter.
+    severity: Option<Severity>,
+    /// Chars of the line with problems, least severe first so the worst are drawn on top.
+    squiggles: Vec<(Range<usize>, Severity)>,
+    /// How the line changed since it was staged, marked in the gutter. Deleted lines are
+    /// marked above the line that follows them.
+    change: Option<ChangeKind>,
+}
+
+impl LineMarks {
+    fn new(problems: &[Problem], changes: &[Change], rope: &Rope, line_index: usize) -> Self {
+        let line_len = rope
+            .get_line(line_index)
+            .map(|line| {
+                line.to_string()
+                    .trim_end_matches(['\n', '\r'])
+                    .chars()
+                    .count()
+            })
+            .unwrap_or_default();
+        let mut marks = Self::default();
+        for problem in problems.iter().rev() {
+            if line_index < problem.start.0 || problem.end.0 < line_index {
+                continue;
+            }
+            let start = if problem.start.0 == line_index {
+                problem.start.1
+            } else {
+                0
+            };
+            let end = if problem.end.0 == line_index {
+                problem.end.1
+            } else {
+                line_len
+            };
+            // Problems without length still get a char underlined
+            let end = end.max(start + 1);
+            marks.squiggles.push((start..end, problem.severity));
+            marks.severity = Some(problem.severity);
+        }
+        marks.change = changes
+            .iter()
+            .find(|change| {
+                change.new_lines.contains(&line_index)
+                    || (change.new_lines.is_empty() && change.new_lines.start == line_index)
+            })
+            .map(|change| change.kind);
+        marks
+    }
+}
+
+/// What every visible line is built from, so they are built again when any of it changes.
+#[derive(Clone, PartialEq)]
+struct LinesData {
+    tab_id: TabId,
+    revision: usize,
+    problems: Vec<Problem>,
+    changes: Vec<Change>,
+    font_size: f32,
+    line_height: f32,
+}
+
+/// The code editor of an editor tab, which also marks the problems of its lines and the
+/// lines changed since they were staged.
+#[derive(Clone, PartialEq)]
+pub struct EditorView {
+    pub tab_id: TabId,
+    /// Called for each key event. Return `true` to let the editor process it.
+    pub on_pre_key_down: Callback<Event<KeyboardEventData>, bool>,
+}
+
+impl Component for EditorView {
+    fn render(&self) -> impl IntoElement {
+        let tab_id = self.tab_id;
+        let on_pre_key_down = self.on_pre_key_down.clone();
+        let radio_app_state = use_radio::<AppState, Channel>(Channel::follow_tab(tab_id));
+        let editor = use_hook(|| editor_writable(radio_app_state, tab_id));
+        let highlights = use_hook(|| Rc::new(RefCell::new(Highlights::default())));
+
+        let (font_size, line_height, focus_id, problems, changes) = {
+            let app_state = radio_app_state.read();
+            let editor_tab = app_state.editor_tab(tab_id);
+            (
+                app_state.font_size(),
+                (app_state.font_size() * app_state.line_height()).floor(),
+                editor_tab.focus_id,
+                app_state.problems.of_file(&editor_tab.path),
+                editor_tab.git_changes(),
+            )
+        };
+        let (lines_len, revision) = {
+            let mut highlights = highlights.borrow_mut();
+            // Written only to read the version of its history, nothing to notify
+            editor.clone().write_if(|mut editor_data| {
+                highlights.update(&mut editor_data, font_size);
+                false
+            });
+            (editor.read().rope.len_lines(), highlights.revision)
+        };
+
+        let scroll_controller =
+            use_hook(|| editor_scroll_controller(radio_app_state, tab_id, true));
+
+        let on_key_up = {
+            let mut editor = editor.clone();
+            move |e: Event<KeyboardEventData>| {
+                editor.write_if(|mut editor| {
+                    editor.process(font_size, FONT_FAMILY, EditableEvent::KeyUp { key: &e.key })
+                });
+            }
+        };
+
+        let on_key_down = {
+            let mut editor = editor.clone();
+            move |e: Event<KeyboardEventData>| {
+                const LINES_JUMP_CONTROL: usize = 3;
+
+                let key = e.key.clone();
+                let modifiers = e.modifiers;
+
+                if !on_pre_key_down.call(e) {
+                    return;
+                }
+
+                let lines_jump = match &key {
+                    Key::Named(NamedKey::ArrowUp | NamedKey::ArrowDown)
+                        if modifiers.contains(Modifiers::CONTROL) =>
+                    {
+                        LINES_JUMP_CONTROL
+                    }
+                    _ => 1,
+                };
+
+                editor.write_if(|mut editor| {
+                    let mut changed = false;
+                    for _ in 0..lines_jump {
+                        changed |= editor.process(
+                            font_size,
+                            FONT_FAMILY,
+                            EditableEvent::KeyDown {
+                                key: &key,
+                                modifiers,
+                            },
+                        );
+                    }
+                    changed
+                });
+            }
+        };
+
+        let on_global_pointer_press = {
+            let mut editor = editor.clone();
+            move |_: Event<PointerEventData>| {
+                editor.write_if(|mut editor| {
+                    editor.process(font_size, FONT_FAMILY, EditableEvent::Release)
+                });
+            }
+        };
+
+        let lines_data = LinesData {
+            tab_id,
+            revision,
+            problems,
+            changes,
+            font_size,
+            line_height,
+        };
+
+        rect()
+            .a11y_focusable(true)
+            .a11y_id(focus_id)
+            .a11y_role(AccessibilityRole::TextInput)
+            .expanded()
+            .background(GITHUB_DARK_EDITOR_THEME.background)
+            .on_key_down(on_key_down)
+            .on_key_up(on_key_up)
+            .on_global_pointer_press(on_global_pointer_press)
+            .child(
+                VirtualScrollView::new_with_data_controlled(
+                    lines_data,
+                    move |line_index, lines_data: &LinesData| {
+                        let highlights = highlights.borrow();
+                        let (spans, marks) = {
+                            let editor_data = editor.peek();
+                            (
+                                highlights.spans(&editor_data.rope, line_index),
+                                LineMarks::new(
+                                    &lines_data.problems,
+                                    &lines_data.changes,
+                                    &editor_data.rope,
+                                    line_index,
+                                ),
+                            )
+                        };
+                        EditorLineView {
+                            editor: editor.clone(),
+                            line_index,
+                            spans,
+                            marks,
+                            font_size: lines_data.font_size,
+                            line_height: lines_data.line_height,
+                            char_width: highlights.char_width.1,
+                            longest_width: highlights.longest_width,
+                            focus_id,
+                        }
+                        .into()
+                    },
+                    scroll_controller,
+                )
+                .length(lines_len)
+                .item_size(line_height),
+            )
+    }
+}
+
+/// A line of the [EditorView], with its number, its problems and its change in the gutter.
+#[derive(Clone, PartialEq)]
+struct EditorLineView {
+    editor: Writable<CodeEditorData>,
+    line_index: usize,
+    spans: Vec<(Color, String)>,
+    marks: LineMarks,
+    font_size: f32,
+    line_height: f32,
+    char_width: f32,
+    longest_width: f32,
+    focus_id: AccessibilityId,
+}
+
+impl Component for EditorLineView {
+    fn render_key(&self) -> DiffKey {
+        DiffKey::from(&self.line_index)
+    }
+
+    fn render(&self) -> impl IntoElement {
+        let EditorLineView {
+            mut editor,
+            line_index,
+            spans,
+            marks,
+            font_size,
+            line_height,
+            char_width,
+            longest_width,
+            focus_id,
+        } = self.clone();
+        let theme = GITHUB_DARK_EDITOR_THEME;
+
+        let holder = use_state(ParagraphHolder::default);
+
+        let editor_data = editor.read();
+        let highlights = editor_data.get_visible_selection(EditorLine::Paragraph(line_index));
+        let gutter_width = font_size * 5.0;
+        let is_line_selected = editor_data.cursor_row() == line_index;
+        let cursor_index = is_line_selected.then(|| editor_data.cursor_col());
+        let has_selection = editor_data
+            .get_selection()
+            .is_some_and(|(start, end)| start != end);
+        drop(editor_data);
+
+        let on_tap = {
+            let mut editor = editor.clone();
+            move |e: Event<FocusPressEventData>| {
+                let processed = editor.write_if(|mut editor| {
+                    editor.process(
+                        font_size,
+                        FONT_FAMILY,
+                        EditableEvent::Down {
+                            location: e.element_location(),
+                            editor_line: EditorLine::Paragraph(line_index),
+                            holder: &holder.read(),
+                        },
+                    )
+                });
+                if processed {
+                    focus_id.request_focus();
+                }
+            }
+        };
+
+        let on_pointer_move = move |e: Event<PointerEventData>| {
+            editor.write_if(|mut editor| {
+                editor.process(
+                    font_size,
+                    FONT_FAMILY,
+                    EditableEvent::Move {
+                        location: e.element_location(),
+                        editor_line: EditorLine::Paragraph(line_index),
+                        holder: &holder.read(),
+                    },
+                )
+            });
+        };
+
+        let gutter_color = if is_line_selected {
+            theme.gutter_selected
+        } else {
+            theme.gutter_unselected
+        };
+        let line_background = if is_line_selected && !has_selection {
+            theme.line_selected_background
+        } else {
+            Color::TRANSPARENT
+        };
+        let severity_marker = marks.severity.map(|severity| {
+            rect()
+                .width(Size::px(6.))
+                .height(Size::px(6.))
+                .corner_radius(3.)
+                .background(severity.color())
+        });
+        let change_marker = marks.change.map(|kind| {
+            let height = if kind == ChangeKind::Deleted {
+                Size::px(CHANGE_MARKER_WIDTH)
+            } else {
+                Size::fill()
+            };
+            rect()
+                .width(Size::px(CHANGE_MARKER_WIDTH))
+                .height(height)
+                .background(change_color(kind))
+        });
+
+        rect()
+            .horizontal()
+            .height(Size::px(line_height))
+            .background(line_background)
+            .font_size(font_size)
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::px(gutter_width))
+                    .height(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .child(
+                        rect()
+                            .width(Size::px(CHANGE_MARKER_WIDTH))
+                            .height(Size::fill())
+                            .maybe_child(change_marker),
+                    )
+                    .child(
+                        rect()
+                            .width(Size::px(20. - CHANGE_MARKER_WIDTH))
+                            .main_align(Alignment::Center)
+                            .cross_align(Alignment::Center)
+                            .maybe_child(severity_marker),
+                    )
+                    .child(
+                        label()
+                            .color(gutter_color)
+                            .text(format!("{} ", line_index + 1)),
+                    ),
+            )
+            .child(
+                rect()
+                    .width(Size::px(longest_width))
+                    .min_width(Size::fill())
+                    .height(Size::fill())
+                    .child(
+                        paragraph()
+                            .holder(holder.read().clone())
+                            .on_pointer_move(on_pointer_move)
+                            .on_focus_press(on_tap)
+                            .cursor_color(theme.cursor)
+                            .cursor_style(CursorStyle::Block)
+                            .cursor_index(cursor_index)
+                            .cursor_mode(CursorMode::Expanded)
+                            .vertical_align(VerticalAlign::Center)
+                            .highlights(highlights.map(|highlight| vec![highlight]))
+                            .highlight_color(theme.highlight)
+                            .width(Size::fill())
+                            .height(Size::fill())
+                            .font_family(FONT_FAMILY)
+                            .max_lines(1)
+                            .color(theme.text)
+                            .spans_iter(
+                                spans
+                                    .into_iter()
+                                    .map(|(color, text)| Span::new(Cow::Owned(text)).color(color)),
+                            ),
+                    )
+                    .children(marks.squiggles.into_iter().map(|(chars, severity)| {
+                        rect()
+                            .position(
+                                Position::new_absolute()
+                                    .left(chars.start as f32 * char_width)
+                                    .top(line_height - SQUIGGLE_HEIGHT),
+                            )
+                            .width(Size::px(chars.len() as f32 * char_width))
+                            .height(Size::px(SQUIGGLE_HEIGHT))
+                            .background(severity.color())
+                            .into()
+                    })),
+            )
+    }
+}
@@ -0,0 +1,179 @@
+use std::ops::Range;
+
+use freya::code_editor::Rope;
+use freya::prelude::{AccessibilityId, AccessibilityIdExt};
+use regex::{Captures, Regex, RegexBuilder};
+
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct FindOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool,
+}
+
+/// State of the find bar of an [EditorTab](super::EditorTab).
+pub struct FindState {
+    pub query: String,
+    pub replacement: String,
+    pub options: FindOptions,
+    /// Matches as char ranges in the rope.
+    pub matches: Vec<Range<usize>>,
+    /// Index of the selected match in `matches`.
+    pub current: Option<usize>,
+    /// Error of an invalid regex query.
+    pub error: Option<String>,
+    pub show_replace: bool,
+    pub focus_id: AccessibilityId,
+}
+
+impl FindState {
+    pub fn new(query: String, show_replace: bool) -> Self {
+        Self {
+            query,
+            replacement: String::new(),
+            options: FindOptions::default(),
+            matches: Vec::new(),
+            current: None,
+            error: None,
+            show_replace,
+            focus_id: AccessibilityId::new_unique(),
+        }
+    }
+
+    pub fn regex(&self) -> Result<Regex, regex::Error> {
+        build_regex(&self.query, self.options)
+    }
+
+    /// Search the rope again, keeping the selected match closest to `cursor`.
+    pub fn update(&mut self, rope: &Rope, cursor: usize) {
+        self.error = None;
+        self.matches.clear();
+        self.current = None;
+
+        if self.query.is_empty() {
+            return;
+        }
+
+        match self.regex() {
+            Ok(regex) => {
+                self.matches = find_matches(rope, &regex);
+                self.current = self
+                    .matches
+                    .iter()
+                    .position(|m| m.start >= cursor)
+                    .or((!self.matches.is_empty()).then_some(0));
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    /// Select the first match after `cursor`, wrapping around.
+    pub fn select_next(&mut self, cursor: usize) -> Option<Range<usize>> {
+        self.current = self
+            .matches
+            .iter()
+            .position(|m| m.start >= cursor)
+            .or((!self.matches.is_empty()).then_some(0));
+        self.current_match()
+    }
+
+    /// Select the last match before `cursor`, wrapping around.
+    pub fn select_previous(&mut self, cursor: usize) -> Option<Range<usize>> {
+        self.current = self
+            .matches
+            .iter()
+            .rposition(|m| m.end < cursor)
+            .or(self.matches.len().checked_sub(1));
+        self.current_match()
+    }
+
+    pub fn current_match(&self) -> Option<Range<usize>> {
+        self.current.and_then(|i| self.matches.get(i).cloned())
+    }
+
+    /// Human friendly `n of m` summary.
+    pub fn summary(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.lines().last().unwrap_or("Invalid regex").to_string();
+        }
+        match (self.current, self.matches.len()) {
+            (_, 0) if self.query.is_empty() => String::new(),
+            (_, 0) => "No results".to_string(),
+            (Some(current), len) => format!("{} of {len}", current + 1),
+            (None, len) => format!("{len} results"),
+        }
+    }
+}
+
+pub fn build_regex(query: &str, options: FindOptions) -> Result<Regex, regex::Error> {
+    let mut pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    if options.whole_word {
+        pattern = format!(r"\b(?:{pattern})\b");
+    }
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+}
+
+/// Find all the non-empty matches of `regex` in the rope, as char ranges.
+pub fn find_matches(rope: &Rope, regex: &Regex) -> Vec<Range<usize>> {
+    let text = rope.to_string();
+    regex
+        .find_iter(&text)
+        .filter(|m| !m.is_empty())
+        .map(|m| rope.byte_to_char(m.start())..rope.byte_to_char(m.end()))
+        .collect()
+}
+
+/// Text that replaces the match starting at byte `start` of `text`, or `None` if nothing
+/// matches there anymore. The captures are read in the whole text, like in the search, so
+/// anchors and look-arounds resolve the same way. Capture groups (`$1`) are only expanded
+/// in regex mode.
+pub fn expand_replacement(
+    regex: &Regex,
+    options: FindOptions,
+    text: &str,
+    start: usize,
+    replacement: &str,
+) -> Option<String> {
+    let caps = regex.captures_at(text, start).filter(|caps| {
+        caps.get(0)
+            .is_some_and(|m| m.start() == start && !m.is_empty())
+    })?;
+    if !options.regex {
+        return Some(replacement.to_string());
+    }
+    let mut expanded = String::new();
+    caps.expand(replacement, &mut expanded);
+    Some(expanded)
+}
+
+/// Replace every match in `text`, returning the new text and how many matches were replaced.
+pub fn replace_all_in(
+    text: &str,
+    regex: &Regex,
+    options: FindOptions,
+    replacement: &str,
+) -> (String, usize) {
+    let mut count = 0;
+    let replaced = regex.replace_all(text, |caps: &Captures| {
+        let matched = caps.get(0).map(|m| m.as_str()).unwrap_or_default();
+        if matched.is_empty() {
+            return String::new();
+        }
+        count += 1;
+        if options.regex {
+            let mut expanded = String::new();
+            caps.expand(replacement, &mut expanded);
+            expanded
+        } else {
+            replacement.to_string()
+        }
+    });
+    (replaced.into_owned(), count)
+}
@@ -0,0 +1,240 @@
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    components::OptionToggle,
+    state::{AppState, Channel, TabId},
+    views::panels::tabs::editor::{AppStateEditorUtils, find::FindOptions},
+};
+
+/// Find and replace bar shown on top of an [EditorTab](super::EditorTab).
+#[derive(Clone, PartialEq)]
+pub struct FindBar {
+    pub tab_id: TabId,
+}
+
+impl Component for FindBar {
+    fn render(&self) -> impl IntoElement {
+        let tab_id = self.tab_id;
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::follow_tab(tab_id));
+
+        let query = use_state(|| {
+            radio_app_state
+                .read()
+                .editor_tab(tab_id)
+                .find
+                .as_ref()
+                .map(|find| find.query.clone())
+                .unwrap_or_default()
+        });
+        let replacement = use_state(String::new);
+
+        use_side_effect(move || {
+            let query = query.read().clone();
+            let mut app_state = radio_app_state.write();
+            let editor_tab = app_state.editor_tab_mut(tab_id);
+            if let Some(find) = editor_tab.find.as_mut() {
+                find.query = query;
+            }
+            editor_tab.refresh_find();
+            if let Some(range) = editor_tab.find.as_ref().and_then(|f| f.current_match()) {
+                editor_tab.select_range(range);
+            }
+        });
+
+        use_side_effect(move || {
+            let replacement = replacement.read().clone();
+            let mut app_state = radio_app_state.write();
+            if let Some(find) = app_state.editor_tab_mut(tab_id).find.as_mut() {
+                find.replacement = replacement;
+            }
+        });
+
+        let (summary, options, show_replace, focus_id) = {
+            let app_state = radio_app_state.read();
+            let Some(find) = app_state.editor_tab(tab_id).find.as_ref() else {
+                return rect().into_element();
+            };
+            (
+                find.summary(),
+                find.options,
+                find.show_replace,
+                find.focus_id,
+            )
+        };
+
+        let mut toggle_option = move |toggle: fn(&mut FindOptions)| {
+            let mut app_state = radio_app_state.write();
+            let editor_tab = app_state.editor_tab_mut(tab_id);
+            if let Some(find) = editor_tab.find.as_mut() {
+                toggle(&mut find.options);
+            }
+            editor_tab.refresh_find();
+        };
+
+        let on_find_next = move |_| {
+            radio_app_state.write().editor_tab_mut(tab_id).find_next();
+        };
+
+        let on_find_previous = move |_| {
+            radio_app_state
+                .write()
+                .editor_tab_mut(tab_id)
+                .find_previous();
+        };
+
+        let on_replace = move |_| {
+            let mut app_state = radio_app_state.write();
+            let font_size = app_state.font_size();
+            app_state.editor_tab_mut(tab_id).replace_current(font_size);
+        };
+
+        let on_replace_all = move |_| {
+            let mut app_state = radio_app_state.write();
+            let font_size = app_state.font_size();
+            app_state.editor_tab_mut(tab_id).replace_all(font_size);
+        };
+
+        let on_toggle_replace = move |_| {
+            let mut app_state = radio_app_state.write();
+            if let Some(find) = app_state.editor_tab_mut(tab_id).find.as_mut() {
+                find.show_replace = !find.show_replace;
+            }
+        };
+
+        let on_key_down = move |e: Event<KeyboardEventData>| {
+            if e.code == Code::Escape {
+                e.stop_propagation();
+                radio_app_state.write().editor_tab_mut(tab_id).close_find();
+            }
+        };
+
+        rect()
+            .width(Size::fill())
+            .background((22, 27, 34))
+            .padding(4.)
+            .spacing(4.)
+            .on_key_down(on_key_down)
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .spacing(4.)
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_toggle_replace)
+                            .child(if show_replace { "▾" } else { "▸" }),
+                    )
+                    .child(
+                        Input::new(query)
+                            .width(Size::flex(1.))
+                            .auto_focus(true)
+                            .a11y_id(focus_id)
+                            .placeholder("Find")
+                            .on_submit(move |_| {
+                                radio_app_state.write().editor_tab_mut(tab_id).find_next();
+                            })
+                            .on_pre_key_down(find_input_pre_key_down),
+                    )
+                    .child(OptionToggle {
+                        text: "Aa",
+                        enabled: options.case_sensitive,
+                        on_press: (move |_| {
+                            toggle_option(|o| o.case_sensitive = !o.case_sensitive)
+                        })
+                        .into(),
+                    })
+                    .child(OptionToggle {
+                        text: "ab",
+                        enabled: options.whole_word,
+                        on_press: (move |_| toggle_option(|o| o.whole_word = !o.whole_word)).into(),
+                    })
+                    .child(OptionToggle {
+                        text: ".*",
+                        enabled: options.regex,
+                        on_press: (move |_| toggle_option(|o| o.regex = !o.regex)).into(),
+                    })
+                    .child(
+                        label()
+                            .width(Size::px(90.))
+                            .max_lines(1)
+                            .text_overflow(TextOverflow::Ellipsis)
+                            .color((125, 133, 144))
+                            .text(summary),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_find_previous)
+                            .child("↑"),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_find_next)
+                            .child("↓"),
+                    ),
+            )
+            .maybe_child(show_replace.then(|| {
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .spacing(4.)
+                    .padding((0., 0., 0., 28.))
+                    .child(
+                        Input::new(replacement)
+                            .width(Size::flex(1.))
+                            .placeholder("Replace")
+                            .on_submit(move |_| {
+                                let mut app_state = radio_app_state.write();
+                                let font_size = app_state.font_size();
+                                app_state.editor_tab_mut(tab_id).replace_current(font_size);
+                            })
+                            .on_pre_key_down(find_input_pre_key_down),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_replace)
+                            .child("Replace"),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_replace_all)
+                            .child("Replace All"),
+                    )
+            }))
+            .into_element()
+    }
+}
+
+/// Keep typed characters inside the input while still letting the global
+/// shortcuts like `F3` or `Ctrl S` through.
+fn find_input_pre_key_down(e: Event<KeyboardEventData>) -> bool {
+    match e.code {
+        Code::F3 | Code::Escape => false,
+        Code::KeyS | Code::KeyW | Code::KeyF | Code::KeyH if e.modifiers == Modifiers::CONTROL => {
+            false
+        }
+        _ => match &e.key {
+            Key::Named(NamedKey::Enter) => true,
+            Key::Named(NamedKey::Tab) => false,
+            _ => {
+                e.stop_propagation();
+                e.prevent_default();
+                true
+            }
+        },
+    }
+}
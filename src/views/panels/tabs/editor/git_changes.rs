@@ -0,0 +1,221 @@
+use std::path::Path;
+
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    diff::ChangeKind,
+    git::{read_staged, stage_content},
+    state::{AppState, Channel, RadioAppState, TabId},
+    views::notifications::notifications_state::Notification,
+};
+
+use super::{AppStateEditorUtils, blame::load_blame};
+
+pub(super) fn change_color(kind: ChangeKind) -> Color {
+    match kind {
+        ChangeKind::Added => (63, 185, 80).into(),
+        ChangeKind::Modified => (56, 139, 253).into(),
+        ChangeKind::Deleted => (248, 81, 73).into(),
+    }
+}
+
+/// Read again the staged version of the file of a tab.
+pub async fn load_git_base(mut radio_app_state: RadioAppState, tab_id: TabId) {
+    let Some(path) = radio_app_state
+        .read()
+        .tabs
+        .get(&tab_id)
+        .and_then(|tab| tab.as_text_editor())
+        .map(|editor_tab| editor_tab.path.clone())
+    else {
+        return;
+    };
+    let git_base = read_staged(&path).await;
+
+    let is_changed = radio_app_state
+        .read()
+        .tabs
+        .get(&tab_id)
+        .and_then(|tab| tab.as_text_editor())
+        .is_some_and(|editor_tab| editor_tab.git_base() != git_base.as_deref());
+    if is_changed {
+        radio_app_state
+            .write_channel(Channel::follow_tab(tab_id))
+            .editor_tab_mut(tab_id)
+            .set_git_base(git_base);
+    }
+}
+
+/// Read again the staged versions of the files opened from a repository, and the
+/// blames shown for them.
+pub async fn refresh_git_bases(radio_app_state: RadioAppState, root: &Path) {
+    let tabs = radio_app_state
+        .read()
+        .tabs
+        .iter()
+        .filter_map(|(tab_id, tab)| {
+            let editor_tab = tab.as_text_editor()?;
+            editor_tab
+                .path
+                .starts_with(root)
+                .then_some((*tab_id, editor_tab.blame.is_some()))
+        })
+        .collect::<Vec<_>>();
+    for (tab_id, shows_blame) in tabs {
+        load_git_base(radio_app_state, tab_id).await;
+        if shows_blame {
+            load_blame(radio_app_state, tab_id).await;
+        }
+    }
+}
+
+/// Stage only the change under the cursor of a tab.
+pub async fn stage_change(mut radio_app_state: RadioAppState, tab_id: TabId) {
+    let Some((path, content)) = ({
+        let app_state = radio_app_state.read();
+        let editor_tab = app_state.editor_tab(tab_id);
+        editor_tab
+            .staged_with_change()
+            .map(|content| (editor_tab.path.clone(), content))
+    }) else {
+        return;
+    };
+
+    if let Err(err) = stage_content(&path, &content).await {
+        radio_app_state
+            .write_channel(Channel::Notifications)
+            .notify(Notification::error(format!(
+                "Failed to stage the change: {err}"
+            )));
+        return;
+    }
+    load_git_base(radio_app_state, tab_id).await;
+    radio_app_state.read().git.request_refresh(&path);
+}
+
+/// The staged and the current lines of the change under the cursor, below the editor.
+#[derive(Clone, PartialEq)]
+pub struct ChangePreview {
+    pub tab_id: TabId,
+}
+
+impl Component for ChangePreview {
+    fn render(&self) -> impl IntoElement {
+        let tab_id = self.tab_id;
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::follow_tab(tab_id));
+
+        let (change, removed, added) = {
+            let app_state = radio_app_state.read();
+            let editor_tab = app_state.editor_tab(tab_id);
+            let Some(change) = editor_tab.change_at_cursor() else {
+                return rect().into_element();
+            };
+            let removed = editor_tab
+                .git_base()
+                .iter()
+                .flat_map(|git_base| git_base.lines())
+                .skip(change.old_lines.start)
+                .take(change.old_lines.len())
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            let added = change
+                .new_lines
+                .clone()
+                .map(|row| {
+                    let line = editor_tab.data.rope.line(row).to_string();
+                    line.trim_end_matches(['\n', '\r']).to_string()
+                })
+                .collect::<Vec<_>>();
+            (change, removed, added)
+        };
+        let title = match change.kind {
+            ChangeKind::Added => format!("{} added lines", change.new_lines.len()),
+            ChangeKind::Modified => format!("{} modified lines", change.new_lines.len()),
+            ChangeKind::Deleted => format!("{} deleted lines", change.old_lines.len()),
+        };
+
+        let on_revert = move |_| {
+            let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+            let font_size = app_state.font_size();
+            let editor_tab = app_state.editor_tab_mut(tab_id);
+            editor_tab.revert_change(font_size);
+            editor_tab.focus_id.request_focus();
+        };
+
+        let on_stage = move |_| {
+            spawn(stage_change(radio_app_state, tab_id));
+        };
+
+        let on_close = move |_| {
+            let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+            let editor_tab = app_state.editor_tab_mut(tab_id);
+            editor_tab.show_change_preview = false;
+            editor_tab.focus_id.request_focus();
+        };
+
+        let line = |prefix: &str, text: String, background: Color| {
+            rect()
+                .horizontal()
+                .width(Size::fill())
+                .background(background)
+                .child(
+                    label()
+                        .width(Size::px(20.))
+                        .color((125, 133, 144))
+                        .text(prefix.to_string()),
+                )
+                .child(label().max_lines(1).text(text))
+                .into()
+        };
+
+        rect()
+            .width(Size::fill())
+            .padding((4., 8.))
+            .spacing(4.)
+            .background((22, 27, 34))
+            .color((230, 237, 243))
+            .font_size(13.)
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .spacing(4.)
+                    .child(
+                        label()
+                            .width(Size::flex(1.))
+                            .max_lines(1)
+                            .color(change_color(change.kind))
+                            .text(title),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_revert)
+                            .child("Revert"),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_stage)
+                            .child("Stage"),
+                    )
+                    .child(Button::new().flat().compact().on_press(on_close).child("✕")),
+            )
+            .children(
+                removed
+                    .into_iter()
+                    .map(|text| line("-", text, Color::from((248, 81, 73, 0.15))))
+                    .chain(
+                        added
+                            .into_iter()
+                            .map(|text| line("+", text, Color::from((63, 185, 80, 0.15)))),
+                    ),
+            )
+            .into_element()
+    }
+}
@@ -1,6 +1,16 @@
+mod blame;
 mod commands;
+pub mod completion;
+mod completion_popup;
 mod editor_tab;
+mod editor_view;
+pub mod find;
+mod find_bar;
+mod git_changes;
+pub mod peek;
 mod utils;
 
+pub use commands::{CopyActiveFilePathCommand, save_editor_tab};
 pub use editor_tab::*;
+pub use git_changes::refresh_git_bases;
 pub use utils::*;
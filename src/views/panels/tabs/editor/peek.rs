@@ -0,0 +1,113 @@
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    state::{AppState, Channel, TabId},
+    symbols::SymbolLocation,
+    views::workspace_search::workspace_search_ui::open_search_match,
+};
+
+use super::AppStateEditorUtils;
+
+/// Lines shown around a peeked definition.
+pub const PEEK_LINES: usize = 12;
+
+/// A definition shown below the editor.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PeekState {
+    pub location: SymbolLocation,
+    /// 0-based line of the first of `lines`.
+    pub first_line: usize,
+    pub lines: Vec<String>,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct PeekView {
+    pub tab_id: TabId,
+}
+
+impl Component for PeekView {
+    fn render(&self) -> impl IntoElement {
+        let tab_id = self.tab_id;
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::follow_tab(tab_id));
+
+        let Some(peek) = radio_app_state.read().editor_tab(tab_id).peek.clone() else {
+            return rect().into_element();
+        };
+        let PeekState {
+            location,
+            first_line,
+            lines,
+        } = peek;
+        let (line, col) = location.start;
+        let title = format!("{}:{}:{}", location.path.display(), line + 1, col + 1);
+
+        let on_open = move |_| {
+            let path = location.path.clone();
+            radio_app_state
+                .write_channel(Channel::follow_tab(tab_id))
+                .editor_tab_mut(tab_id)
+                .peek = None;
+            open_search_match(radio_app_state, path, line, col);
+        };
+
+        let on_close = move |_| {
+            let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+            let editor_tab = app_state.editor_tab_mut(tab_id);
+            editor_tab.peek = None;
+            editor_tab.focus_id.request_focus();
+        };
+
+        rect()
+            .width(Size::fill())
+            .padding((4., 8.))
+            .spacing(4.)
+            .background((22, 27, 34))
+            .color((230, 237, 243))
+            .font_size(13.)
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .spacing(4.)
+                    .child(
+                        label()
+                            .width(Size::flex(1.))
+                            .max_lines(1)
+                            .text_overflow(TextOverflow::Ellipsis)
+                            .color((125, 133, 144))
+                            .text(title),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_open)
+                            .child("Open"),
+                    )
+                    .child(Button::new().flat().compact().on_press(on_close).child("✕")),
+            )
+            .children(lines.into_iter().enumerate().map(|(i, text)| {
+                let background = if first_line + i == line {
+                    Color::from((210, 153, 34, 0.2))
+                } else {
+                    Color::TRANSPARENT
+                };
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .background(background)
+                    .child(
+                        label()
+                            .width(Size::px(40.))
+                            .color((125, 133, 144))
+                            .text(format!("{}", first_line + i + 1)),
+                    )
+                    .child(label().max_lines(1).text(text))
+                    .into()
+            }))
+            .into_element()
+    }
+}
@@ -1,8 +1,12 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use crate::{
+    backup::move_backup,
     fs::FSTransport,
-    state::{AppState, PanelTab, TabId},
+    state::{AppState, AppStateUtils, JumpLocation, PanelTab, RadioAppState, TabId},
     views::panels::tabs::editor::EditorTab,
 };
 use freya::code_editor::Rope;
@@ -13,6 +17,16 @@ pub trait AppStateEditorUtils {
     fn editor_tab_mut(&mut self, tab_id: TabId) -> &mut EditorTab;
 
     fn editor_tab_data(&self, tab_id: TabId) -> Option<(PathBuf, Rope, FSTransport)>;
+
+    /// Content of the files opened in editor tabs, which might have unsaved edits.
+    fn open_buffers(&self) -> HashMap<PathBuf, Rope>;
+
+    /// Cursor location of the active tab of the focused panel, only if it is an editor tab.
+    fn active_jump_location(&self) -> Option<JumpLocation>;
+
+    /// Follow a file or a folder that was renamed or moved with the editor tabs of its files,
+    /// and with the jump list, the backups and the problems of its files.
+    fn rename_editor_paths(&mut self, from: &Path, to: &Path);
 }
 
 impl AppStateEditorUtils for AppState {
@@ -38,6 +52,88 @@ impl AppStateEditorUtils for AppState {
             tab.transport.clone(),
         ))
     }
+
+    fn open_buffers(&self) -> HashMap<PathBuf, Rope> {
+        self.tabs
+            .values()
+            .filter_map(|tab| tab.as_text_editor())
+            .map(|tab| (tab.path.clone(), tab.data.rope.clone()))
+            .collect()
+    }
+
+    fn active_jump_location(&self) -> Option<JumpLocation> {
+        let panel_id = self.focused_panel?;
+        let tab_id = self.panel_tree.as_ref()?.panel(&panel_id)?.active_tab_id?;
+        let tab = self.tabs.get(&tab_id)?.as_text_editor()?;
+        Some(tab.jump_location())
+    }
+
+    fn rename_editor_paths(&mut self, from: &Path, to: &Path) {
+        let moved = |path: &Path| moved_path(path, from, to);
+        for tab in self.tabs.values_mut() {
+            let Some(editor_tab) = tab.as_text_editor_mut() else {
+                continue;
+            };
+            let Some(path) = moved(&editor_tab.path) else {
+                continue;
+            };
+            move_backup(&editor_tab.path, &path);
+
+            // The language server only knows documents by their path
+            self.lsp.did_close(&editor_tab.path);
+            let root = self
+                .file_explorer
+                .folder_of(&path)
+                .or_else(|| path.parent())
+                .map(|root| root.to_path_buf())
+                .unwrap_or_default();
+            self.lsp.did_open(
+                &self.settings.lsp,
+                &root,
+                &path,
+                &editor_tab.data.language_id,
+                &editor_tab.data.rope,
+            );
+
+            editor_tab.icon = self.file_icons.get_file(&path).svg.clone();
+            editor_tab.path = path;
+        }
+
+        for file in &mut self.recovered_files {
+            if let Some(path) = moved(&file.path) {
+                move_backup(&file.path, &path);
+                file.path = path;
+            }
+        }
+        self.jump_list.rename(moved);
+        self.problems.rename(moved);
+    }
+}
+
+/// Where `path` is after moving `from` to `to`, if it was moved along.
+fn moved_path(path: &Path, from: &Path, to: &Path) -> Option<PathBuf> {
+    let relative_path = path.strip_prefix(from).ok()?;
+    if relative_path.as_os_str().is_empty() {
+        Some(to.to_path_buf())
+    } else {
+        Some(to.join(relative_path))
+    }
+}
+
+pub trait RadioEditorUtils {
+    /// The active tab of the focused panel, only if it is an editor tab.
+    fn get_active_editor_tab(&self) -> Option<TabId>;
+}
+
+impl RadioEditorUtils for RadioAppState {
+    fn get_active_editor_tab(&self) -> Option<TabId> {
+        let tab_id = self.get_active_tab()?;
+        self.read()
+            .tabs
+            .get(&tab_id)?
+            .as_text_editor()
+            .map(|_| tab_id)
+    }
 }
 
 impl dyn PanelTab {
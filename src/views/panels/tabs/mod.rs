@@ -1,3 +1,5 @@
+pub mod diff;
 pub mod editor;
+pub mod recovery;
 pub mod settings;
 pub mod welcome;
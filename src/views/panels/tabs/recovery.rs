@@ -0,0 +1,266 @@
+use std::{path::Path, time::SystemTime};
+
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    backup::{RecoveredFile, remove_backup},
+    diff::{LineChange, diff_stats, hunks},
+    state::{AppState, Channel, PanelTab, PanelTabData, RadioAppState, TabId, TabProps},
+    views::panels::tabs::editor::EditorTab,
+};
+
+/// Unchanged lines shown around each change.
+const DIFF_CONTEXT: usize = 2;
+/// Most diff lines shown per file.
+const MAX_DIFF_LINES: usize = 200;
+
+/// Lists the unsaved buffers of a previous run, to restore or discard them.
+pub struct RecoveryTab {
+    id: TabId,
+    focus_id: AccessibilityId,
+}
+
+impl PanelTab for RecoveryTab {
+    fn get_data(&self) -> PanelTabData {
+        PanelTabData {
+            id: self.id,
+            title: "recovered files".to_string(),
+            edited: false,
+            focus_id: self.focus_id,
+            content_id: "recovery".to_string(),
+            icon: None,
+        }
+    }
+    fn render(&self) -> fn(&TabProps) -> Element {
+        render
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl RecoveryTab {
+    pub fn new() -> Self {
+        Self {
+            id: TabId::new(),
+            focus_id: AccessibilityId::new_unique(),
+        }
+    }
+
+    pub fn open_with(app_state: &mut AppState) {
+        app_state.push_tab(Self::new(), app_state.focused_panel);
+    }
+}
+
+/// Open the recovered content of a file as unsaved changes.
+fn restore(mut radio_app_state: RadioAppState, tab_id: TabId, path: &Path) {
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    let Some(index) = app_state
+        .recovered_files
+        .iter()
+        .position(|file| file.path == path)
+    else {
+        return;
+    };
+    let file = app_state.recovered_files.remove(index);
+    remove_backup(&file.path);
+    if app_state.recovered_files.is_empty() {
+        app_state.close_tab(tab_id);
+    }
+    EditorTab::open_recovered(radio_app_state, &mut app_state, file.path, file.content);
+}
+
+/// Forget the recovered content of a file.
+fn discard(mut radio_app_state: RadioAppState, tab_id: TabId, path: &Path) {
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    app_state.recovered_files.retain(|file| file.path != path);
+    // The backup is now of the changes made in this run
+    let is_edited = app_state
+        .find_tab_by_content_id(&path.to_string_lossy())
+        .and_then(|id| app_state.tab(&id).as_text_editor())
+        .is_some_and(|editor_tab| editor_tab.data.is_edited());
+    if !is_edited {
+        remove_backup(path);
+    }
+    if app_state.recovered_files.is_empty() {
+        app_state.close_tab(tab_id);
+    }
+}
+
+fn modified_ago(modified: SystemTime) -> String {
+    let seconds = modified.elapsed().unwrap_or_default().as_secs();
+    match seconds {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} minutes ago", seconds / 60),
+        3600..86400 => format!("{} hours ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+fn recovered_file(
+    radio_app_state: RadioAppState,
+    tab_id: TabId,
+    file: RecoveredFile,
+) -> impl IntoElement {
+    let (added, removed) = diff_stats(&file.diff);
+    let mut details = format!("{}, +{added} −{removed}", modified_ago(file.modified));
+    if !file.exists {
+        details.push_str(", deleted on disk");
+    }
+
+    let mut diff_lines = Vec::new();
+    for hunk in hunks(&file.diff, DIFF_CONTEXT) {
+        if diff_lines.len() >= MAX_DIFF_LINES {
+            break;
+        }
+        if !diff_lines.is_empty() {
+            diff_lines.push(label().color((125, 133, 144)).text("⋯").into_element());
+        }
+        for line in &file.diff[hunk] {
+            let (prefix, color) = match line.change {
+                LineChange::Equal => (' ', (125, 133, 144)),
+                LineChange::Added => ('+', (63, 185, 80)),
+                LineChange::Removed => ('-', (248, 81, 73)),
+            };
+            diff_lines.push(
+                label()
+                    .max_lines(1)
+                    .color(color)
+                    .text(format!("{prefix} {}", line.text))
+                    .into_element(),
+            );
+        }
+    }
+    diff_lines.truncate(MAX_DIFF_LINES);
+
+    let path = file.path.clone();
+    let on_restore = move |_| restore(radio_app_state, tab_id, &path);
+    let path = file.path.clone();
+    let on_discard = move |_| discard(radio_app_state, tab_id, &path);
+
+    rect()
+        .width(Size::fill())
+        .padding(8.)
+        .spacing(6.)
+        .corner_radius(6.)
+        .background((22, 27, 34))
+        .child(
+            rect()
+                .horizontal()
+                .width(Size::fill())
+                .cross_align(Alignment::Center)
+                .content(Content::Flex)
+                .spacing(4.)
+                .child(
+                    rect()
+                        .width(Size::flex(1.))
+                        .child(label().max_lines(1).text(file.path.display().to_string()))
+                        .child(
+                            label()
+                                .max_lines(1)
+                                .color((125, 133, 144))
+                                .font_size(12.)
+                                .text(details),
+                        ),
+                )
+                .child(
+                    Button::new()
+                        .compact()
+                        .on_press(on_restore)
+                        .child("Restore"),
+                )
+                .child(
+                    Button::new()
+                        .flat()
+                        .compact()
+                        .on_press(on_discard)
+                        .child("Discard"),
+                ),
+        )
+        .child(
+            rect()
+                .width(Size::fill())
+                .font_size(12.)
+                .children(diff_lines),
+        )
+}
+
+pub fn render(props: &TabProps) -> Element {
+    let tab_id = props.tab_id;
+    let radio_app_state = use_radio(Channel::follow_tab(tab_id));
+    let files = radio_app_state.read().recovered_files.clone();
+
+    let on_restore_all = move |_| {
+        let paths = radio_app_state
+            .read()
+            .recovered_files
+            .iter()
+            .map(|file| file.path.clone())
+            .collect::<Vec<_>>();
+        for path in paths {
+            restore(radio_app_state, tab_id, &path);
+        }
+    };
+
+    let on_discard_all = move |_| {
+        let paths = radio_app_state
+            .read()
+            .recovered_files
+            .iter()
+            .map(|file| file.path.clone())
+            .collect::<Vec<_>>();
+        for path in paths {
+            discard(radio_app_state, tab_id, &path);
+        }
+    };
+
+    rect()
+        .expanded()
+        .background((13, 17, 23))
+        .child(
+            ScrollView::new().child(
+                rect()
+                    .width(Size::fill())
+                    .padding(16.)
+                    .spacing(12.)
+                    .child(
+                        rect()
+                            .horizontal()
+                            .width(Size::fill())
+                            .cross_align(Alignment::Center)
+                            .content(Content::Flex)
+                            .spacing(4.)
+                            .child(
+                                label().width(Size::flex(1.)).text(
+                                    "These files had unsaved changes when valin last closed.",
+                                ),
+                            )
+                            .child(
+                                Button::new()
+                                    .compact()
+                                    .on_press(on_restore_all)
+                                    .child("Restore All"),
+                            )
+                            .child(
+                                Button::new()
+                                    .flat()
+                                    .compact()
+                                    .on_press(on_discard_all)
+                                    .child("Discard All"),
+                            ),
+                    )
+                    .children(
+                        files.into_iter().map(|file| {
+                            recovered_file(radio_app_state, tab_id, file).into_element()
+                        }),
+                    ),
+            ),
+        )
+        .into()
+}
@@ -0,0 +1,4 @@
+pub mod problems_state;
+pub mod problems_ui;
+
+pub use problems_ui::Problems;
@@ -0,0 +1,165 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use freya::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl Severity {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Error => "⊗",
+            Self::Warning => "⚠",
+            Self::Information => "ℹ",
+            Self::Hint => "💡",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Error => Color::from_rgb(248, 81, 73),
+            Self::Warning => Color::from_rgb(210, 153, 34),
+            Self::Information => Color::from_rgb(88, 166, 255),
+            Self::Hint => Color::from_rgb(125, 133, 144),
+        }
+    }
+}
+
+/// A diagnostic reported for a file, by a language server or any other source.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Problem {
+    pub severity: Severity,
+    pub message: String,
+    /// What reported it, e.g. `rustc`.
+    pub source: Option<String>,
+    /// 0-based `(line, column)`, in chars.
+    pub start: (usize, usize),
+    /// 0-based `(line, column)`, in chars.
+    pub end: (usize, usize),
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum ProblemsSort {
+    #[default]
+    Severity,
+    File,
+}
+
+pub struct ProblemsState {
+    /// Problems of every file, by the provider that reported them.
+    files: HashMap<PathBuf, HashMap<String, Vec<Problem>>>,
+    pub sort: ProblemsSort,
+    pub focus_id: AccessibilityId,
+}
+
+impl ProblemsState {
+    pub fn new() -> Self {
+        Self {
+            files: HashMap::new(),
+            sort: ProblemsSort::default(),
+            focus_id: AccessibilityId::new_unique(),
+        }
+    }
+
+    pub fn focus(&self) {
+        self.focus_id.request_focus();
+    }
+
+    /// Replace the problems a provider reported for a file.
+    pub fn set_problems(&mut self, provider: &str, path: PathBuf, problems: Vec<Problem>) {
+        if problems.is_empty() {
+            if let Some(providers) = self.files.get_mut(&path) {
+                providers.remove(provider);
+                if providers.is_empty() {
+                    self.files.remove(&path);
+                }
+            }
+        } else {
+            self.files
+                .entry(path)
+                .or_default()
+                .insert(provider.to_string(), problems);
+        }
+    }
+
+    /// Follow the files moved to other paths.
+    pub fn rename(&mut self, moved: impl Fn(&Path) -> Option<PathBuf>) {
+        let renamed = self
+            .files
+            .keys()
+            .filter_map(|path| Some((path.clone(), moved(path)?)))
+            .collect::<Vec<_>>();
+        for (from, to) in renamed {
+            if let Some(providers) = self.files.remove(&from) {
+                self.files.insert(to, providers);
+            }
+        }
+    }
+
+    fn problems_of(&self, path: &Path) -> impl Iterator<Item = &Problem> {
+        self.files
+            .get(path)
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, p)| p)
+    }
+
+    /// Problems of a file, most severe first.
+    pub fn of_file(&self, path: &Path) -> Vec<Problem> {
+        let mut problems = self.problems_of(path).cloned().collect::<Vec<_>>();
+        problems.sort_by_key(|problem| (problem.severity, problem.start));
+        problems
+    }
+
+    /// Problems of a file touching a line, most severe first.
+    pub fn at_line(&self, path: &Path, line: usize) -> Vec<Problem> {
+        let mut problems = self
+            .problems_of(path)
+            .filter(|problem| problem.start.0 <= line && line <= problem.end.0)
+            .cloned()
+            .collect::<Vec<_>>();
+        problems.sort_by_key(|problem| (problem.severity, problem.start));
+        problems
+    }
+
+    /// Amount of errors and warnings across all files.
+    pub fn counts(&self) -> (usize, usize) {
+        let problems = self.files.keys().flat_map(|path| self.problems_of(path));
+        problems.fold((0, 0), |(errors, warnings), problem| {
+            match problem.severity {
+                Severity::Error => (errors + 1, warnings),
+                Severity::Warning => (errors, warnings + 1),
+                _ => (errors, warnings),
+            }
+        })
+    }
+
+    /// Every problem, ordered by the current sort.
+    pub fn list(&self) -> Vec<(PathBuf, Problem)> {
+        let mut problems = self
+            .files
+            .keys()
+            .flat_map(|path| {
+                self.problems_of(path)
+                    .map(|problem| (path.clone(), problem.clone()))
+            })
+            .collect::<Vec<_>>();
+        match self.sort {
+            ProblemsSort::Severity => problems.sort_by(|(a_path, a), (b_path, b)| {
+                (a.severity, a_path, a.start).cmp(&(b.severity, b_path, b.start))
+            }),
+            ProblemsSort::File => problems.sort_by(|(a_path, a), (b_path, b)| {
+                (a_path, a.start, a.severity).cmp(&(b_path, b.start, b.severity))
+            }),
+        }
+        problems
+    }
+}
@@ -0,0 +1,245 @@
+use std::path::PathBuf;
+
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    components::OptionToggle,
+    state::{AppState, Channel, EditorView, RadioAppState},
+    views::{
+        panels::tabs::editor::EditorTab,
+        workspace_search::workspace_search_ui::{SearchResultRow, display_path},
+    },
+};
+
+use super::problems_state::{Problem, ProblemsSort};
+
+const ITEM_HEIGHT: f32 = 24.;
+
+/// Open a file at the start of a problem in the focused panel.
+pub fn open_problem(mut radio_app_state: RadioAppState, path: PathBuf, problem: &Problem) {
+    let (line, col) = problem.start;
+    let transport = radio_app_state.read().default_transport.clone();
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    EditorTab::open_at(
+        radio_app_state,
+        &mut app_state,
+        path,
+        transport.as_read(),
+        Some((line, col)),
+    );
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Problems;
+
+impl Component for Problems {
+    fn render(&self) -> impl IntoElement {
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::Problems);
+        let mut selected = use_state(|| 0usize);
+
+        let app_state = radio_app_state.read();
+        let focus_id = app_state.problems.focus_id;
+        let sort = app_state.problems.sort;
+        let (errors, warnings) = app_state.problems.counts();
+        let roots: Vec<PathBuf> = app_state
+            .file_explorer
+            .folders
+            .iter()
+            .map(|item| item.path().clone())
+            .collect();
+        let problems = app_state
+            .problems
+            .list()
+            .into_iter()
+            .map(|(path, problem)| (display_path(&path, &roots), path, problem))
+            .collect::<Vec<_>>();
+        drop(app_state);
+
+        let problems_len = problems.len();
+        let selected_problem = problems
+            .get((*selected.read()).min(problems_len.saturating_sub(1)))
+            .map(|(_, path, problem)| (path.clone(), problem.clone()));
+
+        let mut set_sort = move |sort: ProblemsSort| {
+            radio_app_state
+                .write_channel(Channel::Problems)
+                .problems
+                .sort = sort;
+            selected.set(0);
+        };
+
+        let on_key_down = move |e: Event<KeyboardEventData>| {
+            if radio_app_state.read().focused_view != EditorView::Problems || problems_len == 0 {
+                return;
+            }
+            let current = (*selected.read()).min(problems_len - 1);
+            match e.code {
+                Code::ArrowDown => selected.set((current + 1).min(problems_len - 1)),
+                Code::ArrowUp => selected.set(current.saturating_sub(1)),
+                Code::Enter => {
+                    if let Some((path, problem)) = &selected_problem {
+                        open_problem(radio_app_state, path.clone(), problem);
+                    }
+                }
+                _ => {}
+            }
+        };
+
+        let on_press = move |e: Event<PointerEventData>| {
+            e.stop_propagation();
+            radio_app_state
+                .write_channel(Channel::Global)
+                .focus_view(EditorView::Problems);
+        };
+
+        let sort_button = |text: &'static str, value: ProblemsSort| OptionToggle {
+            text,
+            enabled: sort == value,
+            on_press: (move |_| set_sort(value)).into(),
+        };
+
+        rect()
+            .expanded()
+            .content(Content::Flex)
+            .padding(6.)
+            .spacing(6.)
+            .on_key_down(on_key_down)
+            .on_pointer_press(on_press)
+            .a11y_id(focus_id)
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .spacing(2.)
+                    .child(
+                        label()
+                            .width(Size::flex(1.))
+                            .max_lines(1)
+                            .color((125, 133, 144))
+                            .font_size(12.)
+                            .text(format!("{errors} errors, {warnings} warnings")),
+                    )
+                    .child(sort_button("Severity", ProblemsSort::Severity))
+                    .child(sort_button("File", ProblemsSort::File)),
+            )
+            .child(
+                rect().width(Size::fill()).height(Size::flex(1.)).child(
+                    VirtualScrollView::new_with_data(
+                        (problems, selected, radio_app_state),
+                        problem_row_builder,
+                    )
+                    .length(problems_len)
+                    .item_size(ITEM_HEIGHT)
+                    .scroll_with_arrows(false),
+                ),
+            )
+    }
+}
+
+/// A problem with the path it's shown with and the file it's in.
+type ListedProblem = (String, PathBuf, Problem);
+
+fn problem_row_builder(
+    index: usize,
+    (problems, selected, radio_app_state): &(Vec<ListedProblem>, State<usize>, RadioAppState),
+) -> Element {
+    let (display, path, problem) = problems[index].clone();
+    let radio_app_state = *radio_app_state;
+    let mut selected = *selected;
+    let is_selected = *selected.read() == index;
+    let (line, col) = problem.start;
+
+    let on_press = {
+        let problem = problem.clone();
+        move |_| {
+            selected.set(index);
+            open_problem(radio_app_state, path.clone(), &problem);
+        }
+    };
+
+    let background = if is_selected {
+        Color::from((22, 27, 34))
+    } else {
+        Color::TRANSPARENT
+    };
+
+    SearchResultRow {
+        key_id: format!("{display}:{line}:{col}:{}", problem.message),
+        on_press: on_press.into(),
+        children: rect()
+            .horizontal()
+            .cross_align(Alignment::Center)
+            .expanded()
+            .content(Content::Flex)
+            .spacing(4.)
+            .background(background)
+            .child(ProblemMessage { problem })
+            .child(
+                label()
+                    .max_lines(1)
+                    .color((125, 133, 144))
+                    .font_size(12.)
+                    .text(format!("{display}:{}:{}", line + 1, col + 1)),
+            )
+            .into(),
+    }
+    .into()
+}
+
+/// Severity icon followed by the message of a problem, in a single line.
+#[derive(Clone, PartialEq)]
+pub struct ProblemMessage {
+    pub problem: Problem,
+}
+
+impl Component for ProblemMessage {
+    fn render(&self) -> impl IntoElement {
+        let message = match &self.problem.source {
+            Some(source) => format!("{} ({source})", self.problem.message),
+            None => self.problem.message.clone(),
+        };
+        rect()
+            .horizontal()
+            .width(Size::flex(1.))
+            .cross_align(Alignment::Center)
+            .spacing(4.)
+            .child(
+                label()
+                    .color(self.problem.severity.color())
+                    .text(self.problem.severity.icon()),
+            )
+            .child(
+                label()
+                    .max_lines(1)
+                    .text_overflow(TextOverflow::Ellipsis)
+                    .text(message.lines().next().unwrap_or_default().to_string()),
+            )
+    }
+}
+
+/// Problems of the line under the cursor, shown below the editor.
+#[derive(Clone, PartialEq)]
+pub struct ProblemsHover {
+    pub problems: Vec<Problem>,
+}
+
+impl Component for ProblemsHover {
+    fn render(&self) -> impl IntoElement {
+        rect()
+            .width(Size::fill())
+            .padding((4., 8.))
+            .spacing(2.)
+            .background((22, 27, 34))
+            .color((230, 237, 243))
+            .font_size(13.)
+            .children(self.problems.iter().take(3).map(|problem| {
+                ProblemMessage {
+                    problem: problem.clone(),
+                }
+                .into()
+            }))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod navigation;
+pub mod references_state;
+pub mod references_ui;
+
+pub use references_ui::References;
@@ -0,0 +1,232 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use freya::code_editor::Rope;
+use freya::prelude::spawn;
+use freya::text_edit::TextEditor;
+use tracing::{info, warn};
+
+use crate::{
+    state::{Channel, EditorSidePanel, RadioAppState, TabId},
+    symbols::{SymbolLocation, SymbolProviders},
+    views::{
+        panels::tabs::editor::{
+            completion::word_prefix,
+            peek::{PEEK_LINES, PeekState},
+        },
+        workspace_search::workspace_search_ui::open_search_match,
+    },
+};
+
+use super::references_state::{FileReferences, Reference};
+
+/// The document of an editor tab, its cursor and the providers able to resolve its symbols.
+struct SymbolQuery {
+    path: PathBuf,
+    rope: Rope,
+    cursor: usize,
+    providers: SymbolProviders,
+}
+
+impl SymbolQuery {
+    fn new(radio_app_state: RadioAppState, tab_id: TabId) -> Option<Self> {
+        let app_state = radio_app_state.read();
+        let editor_tab = app_state.tab(&tab_id).as_text_editor()?;
+        let path = editor_tab.path.clone();
+        let providers = app_state
+            .symbol_providers
+            .iter()
+            .filter(|provider| provider.supports(&path))
+            .cloned()
+            .collect::<Vec<_>>();
+        if providers.is_empty() {
+            info!("No symbol provider for {path:?}");
+            return None;
+        }
+        Some(Self {
+            path,
+            rope: editor_tab.data.rope.clone(),
+            cursor: editor_tab.data.cursor_pos(),
+            providers,
+        })
+    }
+
+    /// The word under the cursor, to title the results.
+    fn word(&self) -> String {
+        let (start, _) = word_prefix(&self.rope, self.cursor);
+        self.rope
+            .chars_at(start)
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect()
+    }
+
+    /// Definitions from the first provider that knows any.
+    async fn definitions(&self) -> Vec<SymbolLocation> {
+        for provider in &self.providers {
+            match provider
+                .definition(&self.path, &self.rope, self.cursor)
+                .await
+            {
+                Ok(locations) if !locations.is_empty() => return locations,
+                Ok(_) => {}
+                Err(err) => warn!("Failed to find the definition: {err}"),
+            }
+        }
+        Vec::new()
+    }
+
+    /// References from every provider, without duplicates.
+    async fn references(&self) -> Result<Vec<SymbolLocation>, String> {
+        let mut references = Vec::new();
+        let mut error = None;
+        for provider in &self.providers {
+            match provider
+                .references(&self.path, &self.rope, self.cursor)
+                .await
+            {
+                Ok(locations) => references.extend(locations),
+                Err(err) => error = Some(err),
+            }
+        }
+        match error {
+            Some(err) if references.is_empty() => Err(err),
+            _ => {
+                references.sort_by(|a, b| (&a.path, a.start).cmp(&(&b.path, b.start)));
+                references.dedup();
+                Ok(references)
+            }
+        }
+    }
+}
+
+/// Content of a file, from its editor tab if it is opened.
+async fn read_file(radio_app_state: RadioAppState, path: &Path) -> Option<Rope> {
+    let transport = {
+        let app_state = radio_app_state.read();
+        if let Some(tab_id) = app_state.find_tab_by_content_id(&path.to_string_lossy())
+            && let Some(editor_tab) = app_state.tab(&tab_id).as_text_editor()
+        {
+            return Some(editor_tab.data.rope.clone());
+        }
+        app_state.default_transport.clone()
+    };
+    let content = transport.read_to_string(path).await.ok()?;
+    Some(Rope::from_str(&content))
+}
+
+/// Group locations by file, reading the line of each one for its preview.
+async fn load_references(
+    radio_app_state: RadioAppState,
+    locations: Vec<SymbolLocation>,
+) -> Vec<FileReferences> {
+    let mut files: Vec<FileReferences> = Vec::new();
+    let mut contents = HashMap::new();
+    for location in locations {
+        if !contents.contains_key(&location.path) {
+            let content = read_file(radio_app_state, &location.path).await;
+            contents.insert(location.path.clone(), content);
+        }
+        let preview = contents[&location.path]
+            .as_ref()
+            .filter(|rope| location.start.0 < rope.len_lines())
+            .map(|rope| rope.line(location.start.0).to_string().trim().to_string())
+            .unwrap_or_default();
+        let reference = Reference { location, preview };
+        match files.last_mut() {
+            Some(file) if file.path == reference.location.path => file.references.push(reference),
+            _ => files.push(FileReferences {
+                path: reference.location.path.clone(),
+                references: vec![reference],
+            }),
+        }
+    }
+    files
+}
+
+/// Show locations in the References panel.
+async fn show_locations(
+    mut radio_app_state: RadioAppState,
+    title: String,
+    locations: Result<Vec<SymbolLocation>, String>,
+) {
+    let generation = {
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        app_state.side_panel = Some(EditorSidePanel::References);
+        app_state.references.start(title)
+    };
+    let (results, error) = match locations {
+        Ok(locations) => (load_references(radio_app_state, locations).await, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    let mut app_state = radio_app_state.write_channel(Channel::References);
+    if app_state.references.is_current(generation) {
+        app_state.references.results = results;
+        app_state.references.error = error;
+        app_state.references.is_loading = false;
+    }
+}
+
+/// Jump to the definition of the symbol under the cursor. If there are
+/// several they are listed in the References panel instead.
+pub fn go_to_definition(radio_app_state: RadioAppState, tab_id: TabId) {
+    let Some(query) = SymbolQuery::new(radio_app_state, tab_id) else {
+        return;
+    };
+    spawn(async move {
+        let mut locations = query.definitions().await;
+        if locations.len() > 1 {
+            let title = format!("Definitions of `{}`", query.word());
+            show_locations(radio_app_state, title, Ok(locations)).await;
+        } else if let Some(location) = locations.pop() {
+            let (line, col) = location.start;
+            open_search_match(radio_app_state, location.path, line, col);
+        } else {
+            info!("No definition found for `{}`", query.word());
+        }
+    });
+}
+
+/// List every reference of the symbol under the cursor in the References panel.
+pub fn find_references(radio_app_state: RadioAppState, tab_id: TabId) {
+    let Some(query) = SymbolQuery::new(radio_app_state, tab_id) else {
+        return;
+    };
+    spawn(async move {
+        let title = format!("References of `{}`", query.word());
+        let locations = query.references().await;
+        show_locations(radio_app_state, title, locations).await;
+    });
+}
+
+/// Show the definition of the symbol under the cursor below the editor, without leaving it.
+pub fn peek_definition(mut radio_app_state: RadioAppState, tab_id: TabId) {
+    let Some(query) = SymbolQuery::new(radio_app_state, tab_id) else {
+        return;
+    };
+    spawn(async move {
+        let Some(location) = query.definitions().await.into_iter().next() else {
+            info!("No definition found for `{}`", query.word());
+            return;
+        };
+        let Some(rope) = read_file(radio_app_state, &location.path).await else {
+            return;
+        };
+        let first_line = location.start.0.saturating_sub(2);
+        let lines = rope
+            .lines_at(first_line.min(rope.len_lines()))
+            .take(PEEK_LINES)
+            .map(|line| line.to_string().trim_end_matches(['\n', '\r']).to_string())
+            .collect();
+
+        let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+        if let Some(editor_tab) = app_state.tab_mut(&tab_id).as_text_editor_mut() {
+            editor_tab.peek = Some(PeekState {
+                location,
+                first_line,
+                lines,
+            });
+        }
+    });
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use crate::symbols::SymbolLocation;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Reference {
+    pub location: SymbolLocation,
+    /// Trimmed line of the reference.
+    pub preview: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FileReferences {
+    pub path: PathBuf,
+    pub references: Vec<Reference>,
+}
+
+/// Results of the last references or definitions lookup.
+#[derive(Default)]
+pub struct ReferencesState {
+    /// What was looked up, e.g. ``References of `foo` ``.
+    pub title: String,
+    pub results: Vec<FileReferences>,
+    pub is_loading: bool,
+    pub error: Option<String>,
+    generation: usize,
+}
+
+impl ReferencesState {
+    /// Reset the results for a new lookup, returning its generation.
+    pub fn start(&mut self, title: String) -> usize {
+        self.generation += 1;
+        self.title = title;
+        self.results.clear();
+        self.error = None;
+        self.is_loading = true;
+        self.generation
+    }
+
+    pub fn is_current(&self, generation: usize) -> bool {
+        self.generation == generation
+    }
+
+    pub fn count(&self) -> usize {
+        self.results.iter().map(|file| file.references.len()).sum()
+    }
+}
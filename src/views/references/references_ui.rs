@@ -0,0 +1,180 @@
+use std::path::PathBuf;
+
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    state::{AppState, Channel, RadioAppState},
+    views::workspace_search::workspace_search_ui::{
+        SearchResultRow, display_path, open_search_match,
+    },
+};
+
+use super::references_state::Reference;
+
+const ITEM_HEIGHT: f32 = 24.;
+
+#[derive(Clone, PartialEq)]
+enum ReferenceRow {
+    File {
+        path: PathBuf,
+        display: String,
+        count: usize,
+    },
+    Reference(Reference),
+}
+
+#[derive(Clone, PartialEq)]
+pub struct References;
+
+impl Component for References {
+    fn render(&self) -> impl IntoElement {
+        let radio_app_state = use_radio::<AppState, Channel>(Channel::References);
+
+        let app_state = radio_app_state.read();
+        let references = &app_state.references;
+        let roots: Vec<PathBuf> = app_state
+            .file_explorer
+            .folders
+            .iter()
+            .map(|item| item.path().clone())
+            .collect();
+
+        let summary = if let Some(error) = &references.error {
+            error.clone()
+        } else if references.is_loading {
+            "Searching…".to_string()
+        } else {
+            format!(
+                "{} results in {} files",
+                references.count(),
+                references.results.len()
+            )
+        };
+        let title = references.title.clone();
+
+        let mut rows = Vec::new();
+        for file in &references.results {
+            rows.push(ReferenceRow::File {
+                path: file.path.clone(),
+                display: display_path(&file.path, &roots),
+                count: file.references.len(),
+            });
+            rows.extend(file.references.iter().cloned().map(ReferenceRow::Reference));
+        }
+        drop(app_state);
+        let rows_len = rows.len();
+
+        rect()
+            .expanded()
+            .content(Content::Flex)
+            .padding(6.)
+            .spacing(6.)
+            .child(
+                label()
+                    .max_lines(1)
+                    .text_overflow(TextOverflow::Ellipsis)
+                    .text(title),
+            )
+            .child(
+                label()
+                    .max_lines(1)
+                    .text_overflow(TextOverflow::Ellipsis)
+                    .color((125, 133, 144))
+                    .font_size(12.)
+                    .text(summary),
+            )
+            .child(
+                rect().width(Size::fill()).height(Size::flex(1.)).child(
+                    VirtualScrollView::new_with_data(
+                        (rows, radio_app_state),
+                        reference_row_builder,
+                    )
+                    .length(rows_len)
+                    .item_size(ITEM_HEIGHT),
+                ),
+            )
+    }
+}
+
+fn reference_row_builder(
+    index: usize,
+    (rows, radio_app_state): &(Vec<ReferenceRow>, RadioAppState),
+) -> Element {
+    let radio_app_state = *radio_app_state;
+    match rows[index].clone() {
+        ReferenceRow::File {
+            path,
+            display,
+            count,
+        } => {
+            let icon = radio_app_state
+                .read()
+                .file_icons
+                .get_file(&path)
+                .svg
+                .clone();
+            SearchResultRow {
+                key_id: display.clone(),
+                on_press: (|_| {}).into(),
+                children: rect()
+                    .horizontal()
+                    .cross_align(Alignment::Center)
+                    .expanded()
+                    .content(Content::Flex)
+                    .spacing(4.)
+                    .child(
+                        svg(icon)
+                            .width(Size::px(14.))
+                            .height(Size::px(14.))
+                            .fill(Color::from_rgb(180, 180, 180)),
+                    )
+                    .child(
+                        label()
+                            .width(Size::flex(1.))
+                            .max_lines(1)
+                            .text_overflow(TextOverflow::Ellipsis)
+                            .text(display),
+                    )
+                    .child(
+                        label()
+                            .color((125, 133, 144))
+                            .font_size(12.)
+                            .text(count.to_string()),
+                    )
+                    .into(),
+            }
+            .into()
+        }
+        ReferenceRow::Reference(Reference { location, preview }) => {
+            let (line, col) = location.start;
+            SearchResultRow {
+                key_id: format!("{}:{line}:{col}", location.path.display()),
+                on_press: (move |_| {
+                    open_search_match(radio_app_state, location.path.clone(), line, col)
+                })
+                .into(),
+                children: rect()
+                    .horizontal()
+                    .cross_align(Alignment::Center)
+                    .expanded()
+                    .padding((0., 0., 0., 22.))
+                    .child(
+                        label()
+                            .color((125, 133, 144))
+                            .font_size(12.)
+                            .margin((0., 6., 0., 0.))
+                            .text(format!("{}", line + 1)),
+                    )
+                    .child(
+                        label()
+                            .max_lines(1)
+                            .text_overflow(TextOverflow::Ellipsis)
+                            .text(preview),
+                    )
+                    .into(),
+            }
+            .into()
+        }
+    }
+}
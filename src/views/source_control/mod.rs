@@ -0,0 +1,5 @@
+pub mod source_control_operations;
+pub mod source_control_state;
+pub mod source_control_ui;
+
+pub use source_control_ui::SourceControl;
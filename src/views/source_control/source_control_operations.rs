@@ -0,0 +1,196 @@
+use std::{
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    git::{
+        FileChange, commit, discard_file, last_commit_message, local_branches, read_committed,
+        read_staged, stage_file, switch_branch, unstage_file,
+    },
+    state::{Channel, RadioAppState},
+    views::{notifications::notifications_state::Notification, panels::tabs::diff::DiffTab},
+};
+
+fn notify_error(mut radio_app_state: RadioAppState, message: String) {
+    radio_app_state
+        .write_channel(Channel::Notifications)
+        .notify(Notification::error(message));
+}
+
+fn set_busy(mut radio_app_state: RadioAppState, is_busy: bool) {
+    radio_app_state
+        .write_channel(Channel::SourceControl)
+        .source_control
+        .is_busy = is_busy;
+}
+
+/// Run a git command on a repository, reporting whether it worked, and read its
+/// status again right away.
+async fn run_operation(
+    radio_app_state: RadioAppState,
+    root: &Path,
+    operation: impl Future<Output = io::Result<()>>,
+    failure: &str,
+) -> bool {
+    set_busy(radio_app_state, true);
+    let result = operation.await;
+    set_busy(radio_app_state, false);
+    radio_app_state.read().git.request_refresh(root);
+    if let Err(err) = &result {
+        notify_error(radio_app_state, format!("{failure}: {err}"));
+    }
+    result.is_ok()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+pub async fn stage(radio_app_state: RadioAppState, root: PathBuf, path: PathBuf) {
+    let failure = format!("Failed to stage {}", file_name(&path));
+    run_operation(radio_app_state, &root, stage_file(&root, &path), &failure).await;
+}
+
+pub async fn unstage(radio_app_state: RadioAppState, root: PathBuf, path: PathBuf) {
+    let failure = format!("Failed to unstage {}", file_name(&path));
+    run_operation(radio_app_state, &root, unstage_file(&root, &path), &failure).await;
+}
+
+/// Drop the unstaged changes of a file. Untracked files are moved to the trash, so
+/// they can still be restored.
+pub async fn discard(radio_app_state: RadioAppState, root: PathBuf, change: FileChange) {
+    let failure = format!(
+        "Failed to discard the changes of {}",
+        file_name(&change.path)
+    );
+    if change.code == 'U' {
+        let transport = radio_app_state.read().default_transport.clone();
+        let operation = async { transport.trash(&change.path).await };
+        run_operation(radio_app_state, &root, operation, &failure).await;
+    } else {
+        let operation = discard_file(&root, &change.path);
+        run_operation(radio_app_state, &root, operation, &failure).await;
+    }
+}
+
+/// Commit the staged changes, returning whether it worked.
+pub async fn commit_changes(
+    mut radio_app_state: RadioAppState,
+    root: PathBuf,
+    message: String,
+    amend: bool,
+) -> bool {
+    let failure = if amend {
+        "Failed to amend the last commit"
+    } else {
+        "Failed to commit"
+    };
+    let is_committed = run_operation(
+        radio_app_state,
+        &root,
+        commit(&root, &message, amend),
+        failure,
+    )
+    .await;
+    if is_committed {
+        let mut app_state = radio_app_state.write_channel(Channel::SourceControl);
+        app_state.source_control.message.clear();
+        app_state.source_control.amend = false;
+    }
+    is_committed
+}
+
+/// Message of the last commit, to start amending it from.
+pub async fn load_last_commit_message(radio_app_state: RadioAppState, root: PathBuf) -> String {
+    match last_commit_message(&root).await {
+        Ok(message) => message,
+        Err(err) => {
+            notify_error(
+                radio_app_state,
+                format!("Failed to read the last commit: {err}"),
+            );
+            String::new()
+        }
+    }
+}
+
+/// List the local branches to pick one to switch to.
+pub async fn load_branches(mut radio_app_state: RadioAppState, root: PathBuf) {
+    match local_branches(&root).await {
+        Ok(branches) => {
+            radio_app_state
+                .write_channel(Channel::SourceControl)
+                .source_control
+                .branches = Some(branches);
+        }
+        Err(err) => notify_error(
+            radio_app_state,
+            format!("Failed to list the branches: {err}"),
+        ),
+    }
+}
+
+pub async fn checkout_branch(mut radio_app_state: RadioAppState, root: PathBuf, branch: String) {
+    radio_app_state
+        .write_channel(Channel::SourceControl)
+        .source_control
+        .branches = None;
+    let failure = format!("Failed to switch to {branch}");
+    run_operation(
+        radio_app_state,
+        &root,
+        switch_branch(&root, &branch),
+        &failure,
+    )
+    .await;
+}
+
+/// Open the staged changes of a file against the last commit, or its unstaged changes
+/// against the index.
+pub async fn open_change_diff(
+    mut radio_app_state: RadioAppState,
+    root: PathBuf,
+    path: PathBuf,
+    is_staged: bool,
+) {
+    if path.is_dir() {
+        return;
+    }
+    let relative = path
+        .strip_prefix(&root)
+        .unwrap_or(&path)
+        .display()
+        .to_string();
+    let (old, new) = if is_staged {
+        (
+            (format!("{relative} (HEAD)"), read_committed(&path).await),
+            (format!("{relative} (Index)"), read_staged(&path).await),
+        )
+    } else {
+        let transport = radio_app_state.read().default_transport.clone();
+        (
+            (format!("{relative} (Index)"), read_staged(&path).await),
+            (
+                relative,
+                transport.as_read().read_to_string(&path).await.ok(),
+            ),
+        )
+    };
+
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    DiffTab::open_with(
+        &mut app_state,
+        format!(
+            "{}:{}",
+            if is_staged { "staged" } else { "unstaged" },
+            path.display()
+        ),
+        (old.0, old.1.as_deref().unwrap_or_default()),
+        (new.0, new.1.as_deref().unwrap_or_default()),
+    );
+}
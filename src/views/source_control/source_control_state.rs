@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use crate::{git::RepositoryStatus, state::AppState};
+
+/// What the source control panel is working on.
+#[derive(Default)]
+pub struct SourceControlState {
+    /// Repository picked in the panel, the one of the first opened folder if `None`.
+    pub root: Option<PathBuf>,
+    /// Message of the next commit, kept while the panel is closed.
+    pub message: String,
+    pub amend: bool,
+    /// Local branches, listed while picking one to switch to.
+    pub branches: Option<Vec<String>>,
+    /// A git command started from the panel is still running.
+    pub is_busy: bool,
+}
+
+impl SourceControlState {
+    /// The repository shown in the panel.
+    pub fn repository<'a>(&self, app_state: &'a AppState) -> Option<&'a RepositoryStatus> {
+        let repositories = &app_state.git.repositories;
+        self.root
+            .as_ref()
+            .and_then(|root| {
+                repositories
+                    .iter()
+                    .find(|repository| &repository.root == root)
+            })
+            .or_else(|| {
+                app_state
+                    .file_explorer
+                    .folders
+                    .iter()
+                    .find_map(|folder| app_state.git.repository(folder.path()))
+            })
+            .or_else(|| repositories.first())
+    }
+}
@@ -0,0 +1,428 @@
+use std::path::PathBuf;
+
+use freya::prelude::*;
+use freya::radio::use_radio;
+
+use crate::{
+    components::OptionToggle,
+    git::FileChange,
+    state::{AppState, Channel, RadioAppState},
+    views::workspace_search::workspace_search_ui::{SearchResultRow, search_input_pre_key_down},
+};
+
+use super::source_control_operations::{
+    checkout_branch, commit_changes, discard, load_branches, load_last_commit_message,
+    open_change_diff, stage, unstage,
+};
+
+const ITEM_HEIGHT: f32 = 24.;
+
+#[derive(Clone, PartialEq)]
+enum ChangeRow {
+    Section { title: &'static str, count: usize },
+    File { change: FileChange, is_staged: bool },
+}
+
+/// Staged and unstaged changes of a repository of the opened folders, to stage, discard
+/// and commit them.
+#[derive(Clone, PartialEq)]
+pub struct SourceControl;
+
+impl Component for SourceControl {
+    fn render(&self) -> impl IntoElement {
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::SourceControl);
+        let mut message = use_state(|| radio_app_state.read().source_control.message.clone());
+
+        use_side_effect(move || {
+            let message = message.read().clone();
+            radio_app_state
+                .write_channel(Channel::SourceControl)
+                .source_control
+                .message = message;
+        });
+
+        let app_state = radio_app_state.read();
+        let source_control = &app_state.source_control;
+        let Some(repository) = source_control.repository(&app_state) else {
+            return rect()
+                .expanded()
+                .padding(6.)
+                .child(
+                    label()
+                        .color((125, 133, 144))
+                        .font_size(12.)
+                        .text("None of the opened folders is in a git repository"),
+                )
+                .into_element();
+        };
+        let root = repository.root.clone();
+        let branch = repository.branch.clone();
+        let mut roots = app_state
+            .git
+            .repositories
+            .iter()
+            .map(|repository| repository.root.clone())
+            .collect::<Vec<_>>();
+        roots.sort();
+        let branches = source_control.branches.clone();
+        let amend = source_control.amend;
+        let is_busy = source_control.is_busy;
+        let has_staged = !repository.staged.is_empty();
+
+        let mut rows = Vec::new();
+        for (title, changes, is_staged) in [
+            ("Staged Changes", &repository.staged, true),
+            ("Changes", &repository.unstaged, false),
+        ] {
+            if changes.is_empty() {
+                continue;
+            }
+            rows.push(ChangeRow::Section {
+                title,
+                count: changes.len(),
+            });
+            rows.extend(changes.iter().map(|change| ChangeRow::File {
+                change: change.clone(),
+                is_staged,
+            }));
+        }
+        drop(app_state);
+        let rows_len = rows.len();
+
+        let on_toggle_branches = {
+            let root = root.clone();
+            move |_| {
+                if radio_app_state.read().source_control.branches.is_some() {
+                    radio_app_state
+                        .write_channel(Channel::SourceControl)
+                        .source_control
+                        .branches = None;
+                } else {
+                    spawn(load_branches(radio_app_state, root.clone()));
+                }
+            }
+        };
+
+        let on_toggle_amend = {
+            let root = root.clone();
+            move |_| {
+                let amend = !radio_app_state.read().source_control.amend;
+                radio_app_state
+                    .write_channel(Channel::SourceControl)
+                    .source_control
+                    .amend = amend;
+                // Start from the message of the commit being amended
+                if amend && message.read().trim().is_empty() {
+                    let root = root.clone();
+                    spawn(async move {
+                        let last_message = load_last_commit_message(radio_app_state, root).await;
+                        message.set(last_message);
+                    });
+                }
+            }
+        };
+
+        let on_commit = {
+            let root = root.clone();
+            move || {
+                let text = message.read().clone();
+                let is_busy = radio_app_state.read().source_control.is_busy;
+                if text.trim().is_empty() || is_busy || !(has_staged || amend) {
+                    return;
+                }
+                let root = root.clone();
+                spawn(async move {
+                    if commit_changes(radio_app_state, root, text, amend).await {
+                        message.set(String::new());
+                    }
+                });
+            }
+        };
+        let on_submit = on_commit.clone();
+        let on_press_commit = on_commit;
+
+        let commit_text = if amend { "Amend" } else { "Commit" };
+        let commit_color = if !is_busy && (has_staged || amend) && !message.read().trim().is_empty()
+        {
+            Color::from((230, 237, 243))
+        } else {
+            Color::from((125, 133, 144))
+        };
+        let branch_text = format!(
+            "⎇ {} {}",
+            branch.as_deref().unwrap_or("Detached HEAD"),
+            if branches.is_some() { "▴" } else { "▾" }
+        );
+
+        rect()
+            .expanded()
+            .content(Content::Flex)
+            .padding(6.)
+            .spacing(6.)
+            .maybe_child((roots.len() > 1).then(|| {
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .spacing(2.)
+                    .children(roots.into_iter().map(|repository_root| {
+                        let name = repository_root
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .into_owned();
+                        let background = if repository_root == root {
+                            Color::from((47, 129, 247, 0.4))
+                        } else {
+                            Color::TRANSPARENT
+                        };
+                        rect()
+                            .background(background)
+                            .corner_radius(6.)
+                            .child(
+                                Button::new()
+                                    .flat()
+                                    .compact()
+                                    .on_press(move |_| {
+                                        let mut app_state =
+                                            radio_app_state.write_channel(Channel::SourceControl);
+                                        app_state.source_control.root =
+                                            Some(repository_root.clone());
+                                        app_state.source_control.branches = None;
+                                    })
+                                    .child(name),
+                            )
+                            .into()
+                    }))
+            }))
+            .child(
+                Button::new()
+                    .flat()
+                    .compact()
+                    .on_press(on_toggle_branches)
+                    .child(branch_text),
+            )
+            .maybe_child(branches.map(|branches| {
+                rect()
+                    .width(Size::fill())
+                    .children(branches.into_iter().map(|name| {
+                        let text = if branch.as_ref() == Some(&name) {
+                            format!("✓ {name}")
+                        } else {
+                            format!("   {name}")
+                        };
+                        let root = root.clone();
+                        SearchResultRow {
+                            key_id: name.clone(),
+                            on_press: (move |_| {
+                                spawn(checkout_branch(radio_app_state, root.clone(), name.clone()));
+                            })
+                            .into(),
+                            children: label()
+                                .max_lines(1)
+                                .text_overflow(TextOverflow::Ellipsis)
+                                .text(text)
+                                .into(),
+                        }
+                        .into()
+                    }))
+            }))
+            .child(
+                Input::new(message)
+                    .width(Size::fill())
+                    .placeholder("Commit message")
+                    .on_submit(move |_| on_submit())
+                    .on_pre_key_down(search_input_pre_key_down),
+            )
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .spacing(2.)
+                    .child(OptionToggle {
+                        text: "Amend",
+                        enabled: amend,
+                        on_press: on_toggle_amend.into(),
+                    })
+                    .child(rect().width(Size::flex(1.)))
+                    .child(
+                        Button::new()
+                            .compact()
+                            .on_press(move |_| on_press_commit())
+                            .child(label().color(commit_color).text(commit_text)),
+                    ),
+            )
+            .maybe_child((rows_len == 0).then(|| {
+                label()
+                    .color((125, 133, 144))
+                    .font_size(12.)
+                    .text("No changes")
+            }))
+            .child(
+                rect().width(Size::fill()).height(Size::flex(1.)).child(
+                    VirtualScrollView::new_with_data(
+                        (rows, radio_app_state, root),
+                        change_row_builder,
+                    )
+                    .length(rows_len)
+                    .item_size(ITEM_HEIGHT),
+                ),
+            )
+            .into_element()
+    }
+}
+
+/// Small button on a changed file, which doesn't open its diff.
+#[derive(Clone, PartialEq)]
+struct ChangeAction {
+    text: &'static str,
+    on_press: EventHandler<()>,
+}
+
+impl Component for ChangeAction {
+    fn render(&self) -> impl IntoElement {
+        let mut is_hovering = use_state(|| false);
+        let on_press = self.on_press.clone();
+
+        let background = if *is_hovering.read() {
+            Color::from((110, 118, 129, 0.25))
+        } else {
+            Color::TRANSPARENT
+        };
+
+        rect()
+            .width(Size::px(18.))
+            .corner_radius(4.)
+            .main_align(Alignment::Center)
+            .cross_align(Alignment::Center)
+            .background(background)
+            .on_pointer_enter(move |_| is_hovering.set(true))
+            .on_pointer_leave(move |_| is_hovering.set(false))
+            .on_press(move |e: Event<PressEventData>| {
+                e.stop_propagation();
+                e.prevent_default();
+                on_press.call(());
+            })
+            .child(label().text(self.text))
+    }
+}
+
+fn change_row_builder(
+    index: usize,
+    (rows, radio_app_state, root): &(Vec<ChangeRow>, RadioAppState, PathBuf),
+) -> Element {
+    let radio_app_state = *radio_app_state;
+    match rows[index].clone() {
+        ChangeRow::Section { title, count } => rect()
+            .horizontal()
+            .width(Size::fill())
+            .height(Size::px(ITEM_HEIGHT))
+            .cross_align(Alignment::Center)
+            .content(Content::Flex)
+            .padding((0., 4.))
+            .font_size(12.)
+            .color((125, 133, 144))
+            .child(label().width(Size::flex(1.)).text(title))
+            .child(label().text(count.to_string()))
+            .into(),
+        ChangeRow::File { change, is_staged } => {
+            let path = change.path.clone();
+            let icon = radio_app_state
+                .read()
+                .file_icons
+                .get_file(&path)
+                .svg
+                .clone();
+            let name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let folder = path
+                .parent()
+                .and_then(|parent| parent.strip_prefix(root).ok())
+                .map(|parent| parent.display().to_string())
+                .unwrap_or_default();
+
+            let action = |text: &'static str, operation: fn(RadioAppState, PathBuf, FileChange)| {
+                let root = root.clone();
+                let change = change.clone();
+                ChangeAction {
+                    text,
+                    on_press: (move |_| operation(radio_app_state, root.clone(), change.clone()))
+                        .into(),
+                }
+            };
+            let actions = if is_staged {
+                vec![action("−", |radio, root, change| {
+                    spawn(unstage(radio, root, change.path));
+                })]
+            } else {
+                vec![
+                    action("↺", |radio, root, change| {
+                        spawn(discard(radio, root, change));
+                    }),
+                    action("+", |radio, root, change| {
+                        spawn(stage(radio, root, change.path));
+                    }),
+                ]
+            };
+
+            let on_press = {
+                let root = root.clone();
+                move |_| {
+                    spawn(open_change_diff(
+                        radio_app_state,
+                        root.clone(),
+                        path.clone(),
+                        is_staged,
+                    ));
+                }
+            };
+
+            SearchResultRow {
+                key_id: format!("{is_staged}:{}", change.path.display()),
+                on_press: on_press.into(),
+                children: rect()
+                    .horizontal()
+                    .cross_align(Alignment::Center)
+                    .expanded()
+                    .content(Content::Flex)
+                    .spacing(4.)
+                    .child(
+                        svg(icon)
+                            .width(Size::px(14.))
+                            .height(Size::px(14.))
+                            .fill(Color::from_rgb(180, 180, 180)),
+                    )
+                    .child(
+                        rect()
+                            .horizontal()
+                            .width(Size::flex(1.))
+                            .cross_align(Alignment::Center)
+                            .spacing(6.)
+                            .child(label().max_lines(1).color(change.color()).text(name))
+                            .child(
+                                label()
+                                    .max_lines(1)
+                                    .text_overflow(TextOverflow::Ellipsis)
+                                    .color((125, 133, 144))
+                                    .font_size(12.)
+                                    .text(folder),
+                            ),
+                    )
+                    .children(actions.into_iter().map(|action| action.into()))
+                    .child(
+                        label()
+                            .width(Size::px(14.))
+                            .color(change.color())
+                            .font_size(12.)
+                            .text(change.code.to_string()),
+                    )
+                    .into(),
+            }
+            .into()
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod workspace_replace;
+pub mod workspace_search_state;
+pub mod workspace_search_ui;
+
+pub use workspace_search_ui::WorkspaceSearch;
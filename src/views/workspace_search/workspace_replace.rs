@@ -0,0 +1,256 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use regex::{Captures, Regex};
+
+use crate::{
+    state::{Channel, RadioAppState},
+    views::{
+        notifications::notifications_state::Notification,
+        panels::tabs::{
+            diff::DiffTab,
+            editor::{AppStateEditorUtils, find::FindOptions},
+        },
+    },
+};
+
+use super::{
+    workspace_search_state::{
+        SearchQuery, WorkspaceSearchState, byte_to_line_col, line_starts, query_regex,
+    },
+    workspace_search_ui::start_workspace_search,
+};
+
+/// Outcome of applying a workspace replace.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct ReplaceReport {
+    pub replaced: usize,
+    pub files: usize,
+    /// Matches that changed since the search, left untouched.
+    pub skipped: usize,
+    /// Files that couldn't be read or written, with the reason.
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Matches of a file that should be replaced, by `(line, column)` with the text found there.
+pub struct FileReplace {
+    pub path: PathBuf,
+    pub selected: HashMap<(usize, usize), String>,
+}
+
+/// Replace only the selected matches, leaving the rest untouched. The text is searched
+/// as a whole and lines and columns are computed the same way the search does. A match
+/// is only replaced if it is still found at its position with the same text, otherwise
+/// it is counted as skipped. Returns the new text and the replaced and skipped counts.
+pub fn replace_selected_matches(
+    content: &str,
+    regex: &Regex,
+    options: FindOptions,
+    replacement: &str,
+    selected: &HashMap<(usize, usize), String>,
+) -> (String, usize, usize) {
+    let line_starts = line_starts(content);
+    let mut count = 0;
+    let replaced = regex.replace_all(content, |caps: &Captures| {
+        let found = caps.get(0).expect("Group 0 always exists");
+        let position = byte_to_line_col(content, &line_starts, found.start());
+        let is_valid = selected
+            .get(&position)
+            .is_some_and(|text| text == found.as_str());
+        if found.is_empty() || !is_valid {
+            return found.as_str().to_string();
+        }
+        count += 1;
+        if options.regex {
+            let mut expanded = String::new();
+            caps.expand(replacement, &mut expanded);
+            expanded
+        } else {
+            replacement.to_string()
+        }
+    });
+    (replaced.into_owned(), count, selected.len() - count)
+}
+
+/// Apply the replacements. Files opened in an editor tab are edited in their buffer,
+/// so unsaved changes are kept and the replace can be undone; the rest are written to disk.
+pub async fn apply_workspace_replace(
+    mut radio_app_state: RadioAppState,
+    query: SearchQuery,
+    replacement: String,
+    files: Vec<FileReplace>,
+) -> ReplaceReport {
+    let mut report = ReplaceReport::default();
+    let regex = match query_regex(&query) {
+        Ok(regex) => regex,
+        Err(err) => {
+            report.failed = files
+                .into_iter()
+                .map(|file| (file.path, err.to_string()))
+                .collect();
+            return report;
+        }
+    };
+
+    for file in files {
+        let open_tab = {
+            let app_state = radio_app_state.read();
+            app_state
+                .find_tab_by_content_id(&file.path.to_string_lossy())
+                .filter(|tab_id| app_state.tab(tab_id).as_text_editor().is_some())
+        };
+
+        if let Some(tab_id) = open_tab {
+            let mut app_state = radio_app_state.write_channel(Channel::follow_tab(tab_id));
+            let font_size = app_state.font_size();
+            let editor_tab = app_state.editor_tab_mut(tab_id);
+            let content = editor_tab.data.rope.to_string();
+            let (new_content, count, skipped) = replace_selected_matches(
+                &content,
+                &regex,
+                query.options,
+                &replacement,
+                &file.selected,
+            );
+            report.skipped += skipped;
+            if count > 0 {
+                editor_tab.replace_text(&content, &new_content, font_size);
+                report.replaced += count;
+                report.files += 1;
+            }
+            continue;
+        }
+
+        let transport = radio_app_state.read().default_transport.clone();
+        let content = match transport.read_to_string(&file.path).await {
+            Ok(content) => content,
+            Err(err) => {
+                report.failed.push((file.path, err.to_string()));
+                continue;
+            }
+        };
+        let (new_content, count, skipped) = replace_selected_matches(
+            &content,
+            &regex,
+            query.options,
+            &replacement,
+            &file.selected,
+        );
+        report.skipped += skipped;
+        if count == 0 {
+            continue;
+        }
+        match transport
+            .write_atomic(&file.path, new_content.as_bytes())
+            .await
+        {
+            Ok(()) => {
+                report.replaced += count;
+                report.files += 1;
+            }
+            Err(err) => report.failed.push((file.path, err.to_string())),
+        }
+    }
+
+    report
+}
+
+/// The selected matches of each file of the search results.
+fn selected_files(search: &WorkspaceSearchState) -> Vec<FileReplace> {
+    search
+        .results
+        .iter()
+        .filter(|file| !search.excluded_files.contains(&file.path))
+        .map(|file| FileReplace {
+            path: file.path.clone(),
+            selected: file
+                .matches
+                .iter()
+                .filter(|m| !search.is_match_excluded(&file.path, m.line, m.column))
+                .map(|m| ((m.line, m.column), m.text.clone()))
+                .collect(),
+        })
+        .filter(|file| !file.selected.is_empty())
+        .collect()
+}
+
+/// Replace every selected match of the current search results and search again.
+pub fn replace_in_workspace(mut radio_app_state: RadioAppState) {
+    let (query, replacement, files) = {
+        let app_state = radio_app_state.read();
+        let search = &app_state.workspace_search;
+        (
+            search.query.clone(),
+            search.replacement.clone(),
+            selected_files(search),
+        )
+    };
+
+    if files.is_empty() {
+        return;
+    }
+
+    freya::prelude::spawn(async move {
+        let report =
+            apply_workspace_replace(radio_app_state, query.clone(), replacement, files).await;
+        radio_app_state
+            .write_channel(Channel::WorkspaceSearch)
+            .workspace_search
+            .replace_report = Some(report);
+        start_workspace_search(radio_app_state, query);
+    });
+}
+
+/// Open a diff of a file before and after replacing its selected matches.
+/// Open files are previewed from their buffer, like they are replaced.
+pub fn preview_file_replace(mut radio_app_state: RadioAppState, path: PathBuf) {
+    let (query, replacement, selected, buffer, transport) = {
+        let app_state = radio_app_state.read();
+        let search = &app_state.workspace_search;
+        let selected = selected_files(search)
+            .into_iter()
+            .find(|file| file.path == path)
+            .map(|file| file.selected)
+            .unwrap_or_default();
+        (
+            search.query.clone(),
+            search.replacement.clone(),
+            selected,
+            app_state.open_buffers().remove(&path),
+            app_state.default_transport.clone(),
+        )
+    };
+    let Ok(regex) = query_regex(&query) else {
+        return;
+    };
+
+    freya::prelude::spawn(async move {
+        let content = match buffer {
+            Some(rope) => rope.to_string(),
+            None => match transport.read_to_string(&path).await {
+                Ok(content) => content,
+                Err(err) => {
+                    radio_app_state
+                        .write_channel(Channel::Notifications)
+                        .notify(Notification::error(format!(
+                            "Failed to read {}: {err}",
+                            path.display()
+                        )));
+                    return;
+                }
+            },
+        };
+        let (new_content, _, _) =
+            replace_selected_matches(&content, &regex, query.options, &replacement, &selected);
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        DiffTab::open_with(
+            &mut app_state,
+            format!("replace:{}", path.display()),
+            (name.clone(), &content),
+            (format!("{name} (Replaced)"), &new_content),
+        );
+    });
+}
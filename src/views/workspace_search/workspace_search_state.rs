@@ -0,0 +1,281 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use freya::code_editor::Rope;
+use freya::prelude::*;
+use futures_channel::mpsc::UnboundedSender;
+use ignore::{WalkBuilder, overrides::OverrideBuilder};
+use regex::Regex;
+
+use crate::views::panels::tabs::editor::find::{FindOptions, build_regex};
+
+use super::workspace_replace::ReplaceReport;
+
+/// Stop collecting once this many matches were found.
+const MAX_MATCHES: usize = 20_000;
+/// Longest line preview, in chars.
+const MAX_PREVIEW_LEN: usize = 250;
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct SearchQuery {
+    pub text: String,
+    pub options: FindOptions,
+    /// Comma separated globs of files to include.
+    pub include: String,
+    /// Comma separated globs of files to exclude.
+    pub exclude: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchMatch {
+    /// 0-based line.
+    pub line: usize,
+    /// 0-based column, in chars.
+    pub column: usize,
+    /// Whole matched text, to tell whether the match is still there before replacing it.
+    pub text: String,
+    pub preview: String,
+    /// Matched chars in `preview`.
+    pub preview_range: Range<usize>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FileMatches {
+    pub path: PathBuf,
+    pub matches: Vec<SearchMatch>,
+}
+
+pub struct WorkspaceSearchState {
+    pub query: SearchQuery,
+    pub results: Vec<FileMatches>,
+    pub is_searching: bool,
+    pub error: Option<String>,
+    pub focus_id: AccessibilityId,
+    pub replace_mode: bool,
+    pub replacement: String,
+    /// Files unticked in the replace preview.
+    pub excluded_files: HashSet<PathBuf>,
+    /// Matches unticked in the replace preview, as `(path, line, column)`.
+    pub excluded_matches: HashSet<(PathBuf, usize, usize)>,
+    pub replace_report: Option<ReplaceReport>,
+    /// Bumped when the query is changed from outside of the search panel,
+    /// so the panel starts over from it.
+    pub revision: usize,
+    generation: usize,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl WorkspaceSearchState {
+    pub fn new() -> Self {
+        Self {
+            query: SearchQuery::default(),
+            results: Vec::new(),
+            is_searching: false,
+            error: None,
+            focus_id: AccessibilityId::new_unique(),
+            replace_mode: false,
+            replacement: String::new(),
+            excluded_files: HashSet::new(),
+            excluded_matches: HashSet::new(),
+            replace_report: None,
+            revision: 0,
+            generation: 0,
+            cancelled: Arc::default(),
+        }
+    }
+
+    pub fn focus(&self) {
+        self.focus_id.request_focus();
+    }
+
+    /// Cancel any running search and reset the results for a new one.
+    /// Returns the generation of the new search and its cancellation flag.
+    pub fn start(&mut self, query: SearchQuery) -> (usize, Arc<AtomicBool>) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.cancelled = Arc::default();
+        self.generation += 1;
+        self.query = query;
+        self.results.clear();
+        self.excluded_files.clear();
+        self.excluded_matches.clear();
+        self.error = None;
+        self.is_searching = true;
+        (self.generation, self.cancelled.clone())
+    }
+
+    pub fn is_current(&self, generation: usize) -> bool {
+        self.generation == generation
+    }
+
+    pub fn clear(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.generation += 1;
+        self.results.clear();
+        self.error = None;
+        self.is_searching = false;
+    }
+
+    pub fn matches_count(&self) -> usize {
+        self.results.iter().map(|file| file.matches.len()).sum()
+    }
+
+    pub fn is_match_excluded(&self, path: &Path, line: usize, column: usize) -> bool {
+        self.excluded_files.contains(path)
+            || self
+                .excluded_matches
+                .contains(&(path.to_path_buf(), line, column))
+    }
+
+    pub fn toggle_file(&mut self, path: &Path) {
+        if !self.excluded_files.remove(path) {
+            self.excluded_files.insert(path.to_path_buf());
+        }
+    }
+
+    pub fn toggle_match(&mut self, path: &Path, line: usize, column: usize) {
+        let key = (path.to_path_buf(), line, column);
+        if !self.excluded_matches.remove(&key) {
+            self.excluded_matches.insert(key);
+        }
+    }
+}
+
+/// Build the search regex of a query.
+pub fn query_regex(query: &SearchQuery) -> Result<Regex, regex::Error> {
+    build_regex(&query.text, query.options)
+}
+
+fn split_globs(globs: &str) -> impl Iterator<Item = &str> {
+    globs
+        .split(',')
+        .map(str::trim)
+        .filter(|glob| !glob.is_empty())
+}
+
+/// Walk every folder honouring the ignore files and the include/exclude globs,
+/// sending the matches of each file as soon as they are found.
+/// Files in `buffers` are searched in their open content instead of on disk.
+pub fn search_in_folders(
+    folders: &[PathBuf],
+    buffers: &HashMap<PathBuf, Rope>,
+    query: &SearchQuery,
+    regex: &Regex,
+    sender: UnboundedSender<FileMatches>,
+    cancelled: &AtomicBool,
+) -> Result<(), ignore::Error> {
+    let mut found = 0;
+    for folder in folders {
+        let mut overrides = OverrideBuilder::new(folder);
+        for glob in split_globs(&query.include) {
+            overrides.add(glob)?;
+        }
+        for glob in split_globs(&query.exclude) {
+            overrides.add(&format!("!{glob}"))?;
+        }
+
+        let walker = WalkBuilder::new(folder)
+            .hidden(false)
+            .overrides(overrides.build()?)
+            .build();
+
+        for entry in walker.flatten() {
+            if cancelled.load(Ordering::Relaxed) || found >= MAX_MATCHES {
+                return Ok(());
+            }
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            let path = entry.into_path();
+            let matches = match buffers.get(&path) {
+                Some(rope) => search_in_text(&rope.to_string(), regex),
+                None => search_in_file(&path, regex),
+            };
+            if matches.is_empty() {
+                continue;
+            }
+            found += matches.len();
+            if sender
+                .unbounded_send(FileMatches { path, matches })
+                .is_err()
+            {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Find the matches of a single file, skipping binary or non UTF-8 files.
+pub fn search_in_file(path: &Path, regex: &Regex) -> Vec<SearchMatch> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    search_in_text(&content, regex)
+}
+
+/// Find the matches of a text. The whole text is searched at once so patterns can
+/// span several lines, multi-line matches are shown by their first line.
+pub fn search_in_text(content: &str, regex: &Regex) -> Vec<SearchMatch> {
+    if content.contains('\0') {
+        return Vec::new();
+    }
+
+    let line_starts = line_starts(content);
+    let mut matches = Vec::new();
+    for found in regex.find_iter(content).filter(|m| !m.is_empty()) {
+        let (line_idx, column) = byte_to_line_col(content, &line_starts, found.start());
+        let line_start = line_starts[line_idx];
+        let line_end = line_starts
+            .get(line_idx + 1)
+            .map_or(content.len(), |next| next - 1);
+        let line = content[line_start..line_end].trim_end_matches('\r');
+        let len = content[found.start()..found.end().min(line_end)]
+            .chars()
+            .count();
+        let (preview, preview_range) = preview_line(line, column, len);
+        matches.push(SearchMatch {
+            line: line_idx,
+            column,
+            text: found.as_str().to_string(),
+            preview,
+            preview_range,
+        });
+    }
+    matches
+}
+
+/// Byte offsets where each line of `content` starts.
+pub fn line_starts(content: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect()
+}
+
+/// 0-based line and char column of a byte offset, given where each line starts.
+pub fn byte_to_line_col(content: &str, line_starts: &[usize], offset: usize) -> (usize, usize) {
+    let line = line_starts.partition_point(|start| *start <= offset) - 1;
+    let column = content[line_starts[line]..offset].chars().count();
+    (line, column)
+}
+
+/// Trim the line around the match so long lines still show the matched text.
+fn preview_line(line: &str, column: usize, len: usize) -> (String, Range<usize>) {
+    let leading = line.chars().take_while(|c| c.is_whitespace()).count();
+    let start = if column > MAX_PREVIEW_LEN / 2 {
+        column - MAX_PREVIEW_LEN / 4
+    } else {
+        leading.min(column)
+    };
+    let preview: String = line.chars().skip(start).take(MAX_PREVIEW_LEN).collect();
+    let preview_len = preview.chars().count();
+    let range_start = (column - start).min(preview_len);
+    let range_end = (range_start + len).min(preview_len);
+    (preview, range_start..range_end)
+}
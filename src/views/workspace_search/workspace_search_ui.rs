@@ -0,0 +1,658 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use freya::prelude::*;
+use freya::radio::use_radio;
+use futures::StreamExt;
+
+use crate::{
+    components::OptionToggle,
+    state::{AppState, Channel, EditorSidePanel, RadioAppState},
+    views::panels::tabs::editor::{
+        AppStateEditorUtils, EditorTab,
+        find::{FindOptions, expand_replacement},
+    },
+};
+
+use super::{
+    workspace_replace::{ReplaceReport, preview_file_replace, replace_in_workspace},
+    workspace_search_state::{
+        FileMatches, SearchMatch, SearchQuery, query_regex, search_in_folders,
+    },
+};
+
+const ITEM_HEIGHT: f32 = 24.;
+
+/// Start a new search over all the folders opened in the file explorer,
+/// cancelling the previous one.
+pub fn start_workspace_search(mut radio_app_state: RadioAppState, query: SearchQuery) {
+    let (folders, buffers) = {
+        let app_state = radio_app_state.read();
+        let folders: Vec<PathBuf> = app_state
+            .file_explorer
+            .folders
+            .iter()
+            .map(|item| item.path().clone())
+            .collect();
+        (folders, app_state.open_buffers())
+    };
+
+    let mut app_state = radio_app_state.write_channel(Channel::WorkspaceSearch);
+    if query.text.is_empty() {
+        app_state.workspace_search.clear();
+        app_state.workspace_search.query = query;
+        return;
+    }
+    let regex = match query_regex(&query) {
+        Ok(regex) => regex,
+        Err(err) => {
+            app_state.workspace_search.clear();
+            app_state.workspace_search.query = query;
+            app_state.workspace_search.error = Some(err.to_string());
+            return;
+        }
+    };
+    let (generation, cancelled) = app_state.workspace_search.start(query.clone());
+    drop(app_state);
+
+    let (tx, mut rx) = futures_channel::mpsc::unbounded::<FileMatches>();
+    let worker = smol::unblock(move || {
+        search_in_folders(&folders, &buffers, &query, &regex, tx, &cancelled)
+    });
+
+    spawn(async move {
+        let receive = async {
+            while let Some(file) = rx.next().await {
+                let mut app_state = radio_app_state.write_channel(Channel::WorkspaceSearch);
+                if !app_state.workspace_search.is_current(generation) {
+                    break;
+                }
+                app_state.workspace_search.results.push(file);
+            }
+        };
+        let (result, _) = futures::join!(worker, receive);
+
+        let mut app_state = radio_app_state.write_channel(Channel::WorkspaceSearch);
+        if app_state.workspace_search.is_current(generation) {
+            app_state.workspace_search.is_searching = false;
+            if let Err(err) = result {
+                app_state.workspace_search.error = Some(err.to_string());
+            }
+        }
+    });
+}
+
+/// Open a file at a match in the focused panel.
+pub fn open_search_match(
+    mut radio_app_state: RadioAppState,
+    path: PathBuf,
+    line: usize,
+    col: usize,
+) {
+    let transport = radio_app_state.read().default_transport.clone();
+    let mut app_state = radio_app_state.write_channel(Channel::Global);
+    EditorTab::open_at(
+        radio_app_state,
+        &mut app_state,
+        path,
+        transport.as_read(),
+        Some((line, col)),
+    );
+}
+
+#[derive(Clone, PartialEq)]
+enum SearchRow {
+    File {
+        path: PathBuf,
+        display: String,
+        count: usize,
+        is_collapsed: bool,
+        /// `Some` in replace mode, telling whether the file is ticked.
+        is_included: Option<bool>,
+    },
+    Match {
+        path: PathBuf,
+        found: SearchMatch,
+        /// `Some` in replace mode, with the replaced text and whether the match is ticked.
+        replace: Option<(String, bool)>,
+    },
+}
+
+/// Search only inside of a folder, relative to the opened folder it belongs to,
+/// and show the search panel.
+pub fn search_in_folder(mut radio_app_state: RadioAppState, folder: &Path) {
+    let query = {
+        let mut app_state = radio_app_state.write_channel(Channel::Global);
+        let include = app_state
+            .file_explorer
+            .folder_of(folder)
+            .and_then(|root| folder.strip_prefix(root).ok())
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .map(|relative| format!("{}/**", relative.to_string_lossy()))
+            .unwrap_or_default();
+        let search = &mut app_state.workspace_search;
+        search.query.include = include;
+        search.revision += 1;
+        let query = search.query.clone();
+
+        app_state.close_popup_before_focus();
+        app_state.side_panel = Some(EditorSidePanel::Search);
+        app_state.workspace_search.focus();
+        query
+    };
+    start_workspace_search(radio_app_state, query);
+}
+
+/// Search panel. Started over whenever `revision` changes.
+#[derive(Clone, PartialEq)]
+pub struct WorkspaceSearch {
+    pub revision: usize,
+}
+
+impl Component for WorkspaceSearch {
+    fn render_key(&self) -> DiffKey {
+        DiffKey::from(&self.revision)
+    }
+
+    fn render(&self) -> impl IntoElement {
+        let mut radio_app_state = use_radio::<AppState, Channel>(Channel::WorkspaceSearch);
+
+        let initial_query = radio_app_state.read().workspace_search.query.clone();
+        let text = use_state(|| initial_query.text.clone());
+        let include = use_state(|| initial_query.include.clone());
+        let exclude = use_state(|| initial_query.exclude.clone());
+        let mut options = use_state(|| initial_query.options);
+        let replacement = use_state(|| radio_app_state.read().workspace_search.replacement.clone());
+        let mut collapsed = use_state(HashSet::<PathBuf>::new);
+
+        use_side_effect(move || {
+            let replacement = replacement.read().clone();
+            radio_app_state
+                .write_channel(Channel::WorkspaceSearch)
+                .workspace_search
+                .replacement = replacement;
+        });
+
+        let mut search = move || {
+            radio_app_state
+                .write_channel(Channel::WorkspaceSearch)
+                .workspace_search
+                .replace_report = None;
+            let query = SearchQuery {
+                text: text.read().clone(),
+                options: *options.read(),
+                include: include.read().clone(),
+                exclude: exclude.read().clone(),
+            };
+            start_workspace_search(radio_app_state, query);
+        };
+
+        let mut toggle_option = move |toggle: fn(&mut FindOptions)| {
+            toggle(&mut options.write());
+            search();
+        };
+
+        let app_state = radio_app_state.read();
+        let search_state = &app_state.workspace_search;
+        let focus_id = search_state.focus_id;
+        let roots: Vec<PathBuf> = app_state
+            .file_explorer
+            .folders
+            .iter()
+            .map(|item| item.path().clone())
+            .collect();
+
+        let summary = if let Some(error) = &search_state.error {
+            error.lines().last().unwrap_or_default().to_string()
+        } else if search_state.query.text.is_empty() {
+            String::new()
+        } else {
+            let files = search_state.results.len();
+            let matches = search_state.matches_count();
+            let suffix = if search_state.is_searching {
+                " (searching…)"
+            } else {
+                ""
+            };
+            format!("{matches} results in {files} files{suffix}")
+        };
+
+        let replace_mode = search_state.replace_mode;
+        let report = search_state.replace_report.as_ref().map(report_summary);
+        let preview_regex = replace_mode
+            .then(|| query_regex(&search_state.query).ok())
+            .flatten();
+
+        let collapsed_paths = collapsed.read();
+        let mut rows = Vec::new();
+        for file in &search_state.results {
+            let is_collapsed = collapsed_paths.contains(&file.path);
+            rows.push(SearchRow::File {
+                path: file.path.clone(),
+                display: display_path(&file.path, &roots),
+                count: file.matches.len(),
+                is_collapsed,
+                is_included: replace_mode
+                    .then(|| !search_state.excluded_files.contains(&file.path)),
+            });
+            if !is_collapsed {
+                rows.extend(file.matches.iter().map(|found| {
+                    let replace = preview_regex.as_ref().map(|regex| {
+                        let start = found
+                            .preview
+                            .char_indices()
+                            .nth(found.preview_range.start)
+                            .map_or(found.preview.len(), |(i, _)| i);
+                        let replaced = expand_replacement(
+                            regex,
+                            search_state.query.options,
+                            &found.preview,
+                            start,
+                            &search_state.replacement,
+                        )
+                        .unwrap_or_else(|| search_state.replacement.clone());
+                        let is_included =
+                            !search_state.is_match_excluded(&file.path, found.line, found.column);
+                        (replaced, is_included)
+                    });
+                    SearchRow::Match {
+                        path: file.path.clone(),
+                        found: found.clone(),
+                        replace,
+                    }
+                }));
+            }
+        }
+        drop(collapsed_paths);
+        let rows_len = rows.len();
+        let current_options = *options.read();
+
+        let on_toggle_replace_mode = move |_| {
+            let mut app_state = radio_app_state.write_channel(Channel::WorkspaceSearch);
+            app_state.workspace_search.replace_mode = !app_state.workspace_search.replace_mode;
+        };
+
+        let on_replace_all = move |_| replace_in_workspace(radio_app_state);
+
+        let on_toggle_collapsed = move |path: PathBuf| {
+            let mut collapsed = collapsed.write();
+            if !collapsed.remove(&path) {
+                collapsed.insert(path);
+            }
+        };
+
+        rect()
+            .expanded()
+            .content(Content::Flex)
+            .padding(6.)
+            .spacing(6.)
+            .child(
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .spacing(2.)
+                    .child(OptionToggle {
+                        text: "⇄",
+                        enabled: replace_mode,
+                        on_press: on_toggle_replace_mode.into(),
+                    })
+                    .child(
+                        Input::new(text)
+                            .width(Size::flex(1.))
+                            .auto_focus(true)
+                            .a11y_id(focus_id)
+                            .placeholder("Search")
+                            .on_submit(move |_| search())
+                            .on_pre_key_down(search_input_pre_key_down),
+                    )
+                    .child(OptionToggle {
+                        text: "Aa",
+                        enabled: current_options.case_sensitive,
+                        on_press: (move |_| {
+                            toggle_option(|o| o.case_sensitive = !o.case_sensitive)
+                        })
+                        .into(),
+                    })
+                    .child(OptionToggle {
+                        text: "ab",
+                        enabled: current_options.whole_word,
+                        on_press: (move |_| toggle_option(|o| o.whole_word = !o.whole_word)).into(),
+                    })
+                    .child(OptionToggle {
+                        text: ".*",
+                        enabled: current_options.regex,
+                        on_press: (move |_| toggle_option(|o| o.regex = !o.regex)).into(),
+                    }),
+            )
+            .maybe_child(replace_mode.then(|| {
+                rect()
+                    .horizontal()
+                    .width(Size::fill())
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .spacing(2.)
+                    .child(
+                        Input::new(replacement)
+                            .width(Size::flex(1.))
+                            .placeholder("Replace")
+                            .on_pre_key_down(search_input_pre_key_down),
+                    )
+                    .child(
+                        Button::new()
+                            .flat()
+                            .compact()
+                            .on_press(on_replace_all)
+                            .child("Replace All"),
+                    )
+            }))
+            .child(
+                Input::new(include)
+                    .width(Size::fill())
+                    .placeholder("Files to include, e.g. src/**, *.rs")
+                    .on_submit(move |_| search())
+                    .on_pre_key_down(search_input_pre_key_down),
+            )
+            .child(
+                Input::new(exclude)
+                    .width(Size::fill())
+                    .placeholder("Files to exclude")
+                    .on_submit(move |_| search())
+                    .on_pre_key_down(search_input_pre_key_down),
+            )
+            .child(
+                label()
+                    .max_lines(1)
+                    .text_overflow(TextOverflow::Ellipsis)
+                    .color((125, 133, 144))
+                    .font_size(12.)
+                    .text(summary),
+            )
+            .maybe_child(report.map(|report| {
+                label()
+                    .max_lines(3)
+                    .text_overflow(TextOverflow::Ellipsis)
+                    .color((210, 153, 34))
+                    .font_size(12.)
+                    .text(report)
+            }))
+            .child(
+                rect().width(Size::fill()).height(Size::flex(1.)).child(
+                    VirtualScrollView::new_with_data(
+                        (
+                            rows,
+                            radio_app_state,
+                            EventHandler::from(on_toggle_collapsed),
+                        ),
+                        search_row_builder,
+                    )
+                    .length(rows_len)
+                    .item_size(ITEM_HEIGHT),
+                ),
+            )
+    }
+}
+
+/// Keep typed characters inside the search inputs.
+pub fn search_input_pre_key_down(e: Event<KeyboardEventData>) -> bool {
+    match &e.key {
+        Key::Named(NamedKey::Enter) | Key::Named(NamedKey::Escape) => true,
+        Key::Named(NamedKey::Tab) => false,
+        _ => {
+            e.stop_propagation();
+            e.prevent_default();
+            true
+        }
+    }
+}
+
+fn report_summary(report: &ReplaceReport) -> String {
+    let mut summary = format!(
+        "Replaced {} occurrences in {} files",
+        report.replaced, report.files
+    );
+    if report.skipped > 0 {
+        summary.push_str(&format!(
+            ", skipped {} that changed since the search",
+            report.skipped
+        ));
+    }
+    if !report.failed.is_empty() {
+        summary.push_str(&format!(", failed to write {}:", report.failed.len()));
+        for (path, err) in &report.failed {
+            summary.push_str(&format!("\n{}: {err}", path.display()));
+        }
+    }
+    summary
+}
+
+/// Path relative to the explorer folder containing it, prefixed by the folder name.
+pub fn display_path(path: &std::path::Path, roots: &[PathBuf]) -> String {
+    roots
+        .iter()
+        .find_map(|root| {
+            let relative = path.strip_prefix(root).ok()?;
+            let root_name = root.file_name()?.to_string_lossy();
+            Some(format!("{root_name}/{}", relative.to_string_lossy()))
+        })
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn search_row_builder(
+    index: usize,
+    (rows, radio_app_state, on_toggle_collapsed): &(
+        Vec<SearchRow>,
+        RadioAppState,
+        EventHandler<PathBuf>,
+    ),
+) -> Element {
+    let mut radio_app_state = *radio_app_state;
+    match rows[index].clone() {
+        SearchRow::File {
+            path,
+            display,
+            count,
+            is_collapsed,
+            is_included,
+        } => {
+            let icon = radio_app_state
+                .read()
+                .file_icons
+                .get_file(&path)
+                .svg
+                .clone();
+            let on_toggle_collapsed = on_toggle_collapsed.clone();
+            let checkbox = is_included.map(|is_included| {
+                let path = path.clone();
+                ReplaceCheckbox {
+                    checked: is_included,
+                    on_press: (move |_| {
+                        radio_app_state
+                            .write_channel(Channel::WorkspaceSearch)
+                            .workspace_search
+                            .toggle_file(&path)
+                    })
+                    .into(),
+                }
+            });
+            SearchResultRow {
+                key_id: display.clone(),
+                on_press: (move |_| on_toggle_collapsed.call(path.clone())).into(),
+                children: rect()
+                    .horizontal()
+                    .cross_align(Alignment::Center)
+                    .expanded()
+                    .content(Content::Flex)
+                    .spacing(4.)
+                    .child(label().text(if is_collapsed { "▸" } else { "▾" }))
+                    .maybe_child(checkbox)
+                    .child(
+                        svg(icon)
+                            .width(Size::px(14.))
+                            .height(Size::px(14.))
+                            .fill(Color::from_rgb(180, 180, 180)),
+                    )
+                    .child(
+                        label()
+                            .width(Size::flex(1.))
+                            .max_lines(1)
+                            .text_overflow(TextOverflow::Ellipsis)
+                            .text(display),
+                    )
+                    .child(
+                        label()
+                            .color((125, 133, 144))
+                            .font_size(12.)
+                            .text(count.to_string()),
+                    )
+                    .into(),
+            }
+            .into()
+        }
+        SearchRow::Match {
+            path,
+            found,
+            replace,
+        } => {
+            let SearchMatch {
+                line,
+                column,
+                preview,
+                preview_range,
+                ..
+            } = found;
+            let before: String = preview.chars().take(preview_range.start).collect();
+            let matched: String = preview
+                .chars()
+                .skip(preview_range.start)
+                .take(preview_range.len())
+                .collect();
+            let after: String = preview.chars().skip(preview_range.end).collect();
+            let checkbox = replace.as_ref().map(|(_, is_included)| {
+                let path = path.clone();
+                ReplaceCheckbox {
+                    checked: *is_included,
+                    on_press: (move |_| {
+                        radio_app_state
+                            .write_channel(Channel::WorkspaceSearch)
+                            .workspace_search
+                            .toggle_match(&path, line, column)
+                    })
+                    .into(),
+                }
+            });
+            let is_replacing = replace.is_some();
+            let matched_background = if is_replacing {
+                Color::from((248, 81, 73, 0.35))
+            } else {
+                Color::from((210, 153, 34, 0.35))
+            };
+            SearchResultRow {
+                key_id: format!("{}:{line}:{column}", path.display()),
+                // While replacing, the file is previewed with the replacements applied
+                on_press: (move |_| {
+                    if is_replacing {
+                        preview_file_replace(radio_app_state, path.clone());
+                    } else {
+                        open_search_match(radio_app_state, path.clone(), line, column);
+                    }
+                })
+                .into(),
+                children: rect()
+                    .horizontal()
+                    .cross_align(Alignment::Center)
+                    .expanded()
+                    .padding((0., 0., 0., 22.))
+                    .maybe_child(checkbox)
+                    .child(
+                        label()
+                            .color((125, 133, 144))
+                            .font_size(12.)
+                            .margin((0., 6., 0., 0.))
+                            .text(format!("{}", line + 1)),
+                    )
+                    .child(label().max_lines(1).text(before))
+                    .child(
+                        rect()
+                            .background(matched_background)
+                            .corner_radius(2.)
+                            .child(label().max_lines(1).text(matched)),
+                    )
+                    .maybe_child(replace.map(|(replaced, _)| {
+                        rect()
+                            .background((63, 185, 80, 0.35))
+                            .corner_radius(2.)
+                            .child(label().max_lines(1).text(replaced))
+                    }))
+                    .child(
+                        label()
+                            .max_lines(1)
+                            .text_overflow(TextOverflow::Ellipsis)
+                            .text(after),
+                    )
+                    .into(),
+            }
+            .into()
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct SearchResultRow {
+    pub key_id: String,
+    pub on_press: EventHandler<Event<PressEventData>>,
+    pub children: Element,
+}
+
+impl Component for SearchResultRow {
+    fn render_key(&self) -> DiffKey {
+        DiffKey::from(&self.key_id)
+    }
+
+    fn render(&self) -> impl IntoElement {
+        let mut is_hovering = use_state(|| false);
+
+        let background = if *is_hovering.read() {
+            Color::from((110, 118, 129, 0.1))
+        } else {
+            Color::TRANSPARENT
+        };
+
+        rect()
+            .width(Size::fill())
+            .height(Size::px(ITEM_HEIGHT))
+            .padding((0., 4.))
+            .corner_radius(4.)
+            .background(background)
+            .color((230, 237, 243))
+            .font_size(13.)
+            .on_pointer_enter(move |_| is_hovering.set(true))
+            .on_pointer_leave(move |_| is_hovering.set(false))
+            .on_press(self.on_press.clone())
+            .child(self.children.clone())
+    }
+}
+
+/// Tick box of a file or match in the replace preview.
+#[derive(Clone, PartialEq)]
+struct ReplaceCheckbox {
+    checked: bool,
+    on_press: EventHandler<()>,
+}
+
+impl Component for ReplaceCheckbox {
+    fn render(&self) -> impl IntoElement {
+        let on_press = self.on_press.clone();
+        rect()
+            .margin((0., 4., 0., 0.))
+            .on_press(move |e: Event<PressEventData>| {
+                e.stop_propagation();
+                e.prevent_default();
+                on_press.call(());
+            })
+            .child(label().text(if self.checked { "☑" } else { "☐" }))
+    }
+}